@@ -0,0 +1,53 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use supernovas::{
+    batch,
+    bright_stars::BrightStars,
+    positions::{Observer, ReferenceSystem, Weather},
+    time::{Timescale, Timespec},
+    Accuracy,
+};
+
+fn batch_scaling(c: &mut Criterion) {
+    let ovro = Observer::new_on_surface(
+        37.2339,
+        -118.282,
+        1222.0,
+        Weather {
+            temperature_c: 10.0,
+            pressure_mbar: 1010.0,
+            humidity: 50.0,
+        },
+    );
+    let targets = BrightStars.all().unwrap();
+    // An hour of 1 s samples
+    let times = (0..3600)
+        .map(|s| Timespec::from_split_time(Timescale::UTC, 2460571, s as f64 / 86400.0, 37, 0.0))
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("apparent_local_coordinates");
+    group.sample_size(10);
+    for threads in [1, 2, 4, 8] {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(threads), &threads, |b, _| {
+            b.iter(|| {
+                pool.install(|| {
+                    let frames = batch::frames(Accuracy::Reduced, &ovro, &times, 0.0, 0.0).unwrap();
+                    batch::apparent_local_coordinates(
+                        &frames,
+                        &targets,
+                        ReferenceSystem::CIRS,
+                        None,
+                    )
+                    .unwrap()
+                })
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, batch_scaling);
+criterion_main!(benches);
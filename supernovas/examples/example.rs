@@ -1,7 +1,7 @@
 use hifitime::{prelude::*, ut1::Ut1Provider};
 use supernovas::{
     ephem::provide_ephem,
-    positions::{CatalogEntry, Frame, Observer, ReferenceSystem},
+    positions::{CatalogEntry, Frame, Observer, ReferenceSystem, Weather},
     time::Timespec,
     Accuracy,
 };
@@ -10,7 +10,16 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load the ephemeris
     provide_ephem("supernovas/examples/de440.bsp")?;
     // Construct an observer on the surface
-    let ovro = Observer::new_on_surface(37.2339, -118.282, 1222.0, 10.0, 1010.0);
+    let ovro = Observer::new_on_surface(
+        37.2339,
+        -118.282,
+        1222.0,
+        Weather {
+            temperature_c: 10.0,
+            pressure_mbar: 1010.0,
+            humidity: 50.0,
+        },
+    );
     // Convert from a hifitime Epoch and UT1 provider to  NOVAS Timespec
     let time = Timespec::from((
         Epoch::from_gregorian_utc(2024, 9, 17, 6, 12, 18, 0),
@@ -23,7 +32,7 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("SIMBAD Result: {:#?}", entry);
     // Compute the pointing
     let now = std::time::SystemTime::now();
-    let (az, el) = frame.apparent_local_coordinates(ReferenceSystem::CIRS, &entry)?;
+    let (az, el) = frame.apparent_local_coordinates(ReferenceSystem::CIRS, &entry, None)?;
 
     println!("Az: {az}, El: {el}");
 
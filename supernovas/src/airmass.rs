@@ -0,0 +1,33 @@
+//! Airmass computations
+
+/// Formulas for the relative airmass as a function of elevation
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum AirmassModel {
+    /// The plane-parallel atmosphere, sec(z), which diverges towards the horizon
+    PlaneParallel,
+    /// Kasten & Young (1989), for unrefracted elevations
+    #[default]
+    KastenYoung,
+    /// Pickering (2002), for observed (refracted) elevations
+    Pickering,
+}
+
+/// The relative airmass at elevation `el` in degrees using the given formula
+///
+/// This is `f64::INFINITY` for sources below the horizon.
+pub fn airmass(el: f64, model: AirmassModel) -> f64 {
+    if el <= 0.0 {
+        return f64::INFINITY;
+    }
+    match model {
+        AirmassModel::PlaneParallel => 1.0 / el.to_radians().sin(),
+        AirmassModel::KastenYoung => {
+            1.0 / (el.to_radians().sin() + 0.50572 * (el + 6.07995).powf(-1.6364))
+        }
+        AirmassModel::Pickering => {
+            1.0 / (el + 244.0 / (165.0 + 47.0 * el.powf(1.1)))
+                .to_radians()
+                .sin()
+        }
+    }
+}
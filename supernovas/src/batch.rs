@@ -0,0 +1,136 @@
+//! Batch computations over many sources and many times
+//!
+//! With the `rayon` feature enabled, these are computed in parallel over all available cores. The `_into` variants
+//! write into caller-owned buffers instead, so that a real-time thread can reuse its buffers and never allocate.
+//! Any [`EphemerisSet::scope`](crate::ephem::EphemerisSet::scope) active on the calling thread is carried over to the
+//! worker threads.
+
+use crate::{
+    error::Error,
+    positions::{Frame, Observer, Placeable, ReferenceSystem},
+    refraction::RefractionModel,
+    time::Timespec,
+    Accuracy,
+};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// Iterate over a slice, in parallel when the `rayon` feature is enabled
+macro_rules! maybe_par_iter {
+    ($slice:expr) => {{
+        #[cfg(feature = "rayon")]
+        let iter = $slice.par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let iter = $slice.iter();
+        iter
+    }};
+}
+
+/// The ephemeris set active on the calling thread, to enter on the worker threads
+struct ActiveEphem {
+    #[cfg(feature = "calceph")]
+    set: Option<crate::ephem::EphemerisSet>,
+}
+
+impl ActiveEphem {
+    fn get() -> Self {
+        Self {
+            #[cfg(feature = "calceph")]
+            set: crate::ephem::EphemerisSet::active(),
+        }
+    }
+
+    /// Run `f` with the set active on the current thread
+    fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        #[cfg(feature = "calceph")]
+        if let Some(set) = &self.set {
+            return set.scope(f);
+        }
+        f()
+    }
+}
+
+/// Construct the observing frames for a single observer at each of the given times
+pub fn frames<'a>(
+    acc: Accuracy,
+    obs: &'a Observer,
+    times: &'a [Timespec],
+    dx: f64,
+    dy: f64,
+) -> super::Result<Vec<Frame<'a>>> {
+    let ephem = ActiveEphem::get();
+    maybe_par_iter!(times)
+        .map(|time| ephem.scope(|| Frame::new(acc, obs, time, dx, dy)))
+        .collect()
+}
+
+/// Computes the local coordinates (az,el in degrees) of every target in every frame
+///
+/// The result is indexed first by frame, then by target.
+pub fn apparent_local_coordinates<T: Placeable + Sync>(
+    frames: &[Frame],
+    targets: &[T],
+    ref_sys: ReferenceSystem,
+    refraction: Option<&dyn RefractionModel>,
+) -> super::Result<Vec<Vec<(f64, f64)>>> {
+    let ephem = ActiveEphem::get();
+    maybe_par_iter!(frames)
+        .map(|frame| {
+            maybe_par_iter!(targets)
+                .map(|target| {
+                    ephem.scope(|| frame.apparent_local_coordinates(ref_sys, target, refraction))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Construct the observing frames for a single observer at each of the given times, into a reusable buffer
+///
+/// The buffer is cleared first, and only grows if it is smaller than `times`.
+pub fn frames_into<'a>(
+    acc: Accuracy,
+    obs: &'a Observer,
+    times: &'a [Timespec],
+    dx: f64,
+    dy: f64,
+    out: &mut Vec<Frame<'a>>,
+) -> super::Result<()> {
+    out.clear();
+    for time in times {
+        out.push(Frame::new(acc, obs, time, dx, dy)?);
+    }
+    Ok(())
+}
+
+/// Computes the local coordinates (az,el in degrees) of every target in every frame, into a caller-owned slice
+///
+/// The output is frame-major, i.e. the coordinates of target `j` in frame `i` are at `out[i * targets.len() + j]`,
+/// and must have exactly `frames.len() * targets.len()` elements.
+pub fn apparent_local_coordinates_into<T: Placeable + Sync>(
+    frames: &[Frame],
+    targets: &[T],
+    ref_sys: ReferenceSystem,
+    refraction: Option<&dyn RefractionModel>,
+    out: &mut [(f64, f64)],
+) -> super::Result<()> {
+    if out.len() != frames.len() * targets.len() {
+        return Err(Error::InvalidValue("out"));
+    }
+    if targets.is_empty() {
+        return Ok(());
+    }
+    #[cfg(feature = "rayon")]
+    let rows = out.par_chunks_mut(targets.len()).zip(frames.par_iter());
+    #[cfg(not(feature = "rayon"))]
+    let mut rows = out.chunks_mut(targets.len()).zip(frames.iter());
+    let ephem = ActiveEphem::get();
+    rows.try_for_each(|(row, frame)| {
+        ephem.scope(|| {
+            for (azel, target) in row.iter_mut().zip(targets) {
+                *azel = frame.apparent_local_coordinates(ref_sys, target, refraction)?;
+            }
+            Ok(())
+        })
+    })
+}
@@ -0,0 +1,359 @@
+//! Command line tool for quick pointing calculations
+
+use clap::{Parser, Subcommand};
+use hifitime::{ut1::Ut1Provider, Epoch};
+use std::{fmt::Write, path::PathBuf, str::FromStr};
+use supernovas::{
+    airmass::{airmass, AirmassModel},
+    bright_stars::BrightStars,
+    catalog::CatalogSource,
+    ephem,
+    events::{self, RiseSet, Twilight},
+    planets::Planet,
+    positions::{Frame, Observer, ReferenceSystem, SkyPosition, Weather},
+    refraction::{OpticalRefraction, RefractionModel},
+    simbad::Simbad,
+    target::Target,
+    time::{Timescale, Timespec},
+    Accuracy,
+};
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// Planetary ephemeris kernel (e.g. de440.bsp), needed for the Moon and planets other than the Sun
+    #[arg(long, global = true)]
+    ephem: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Where the observer is
+#[derive(clap::Args)]
+struct SiteArgs {
+    /// Observatory from the built-in site database (e.g. OVRO)
+    #[arg(long, conflicts_with_all = ["lat", "lon"])]
+    site: Option<String>,
+    /// Geodetic latitude in degrees; north positive
+    #[arg(long, requires = "lon", allow_hyphen_values = true)]
+    lat: Option<f64>,
+    /// Geodetic longitude in degrees; east positive
+    #[arg(long, requires = "lat", allow_hyphen_values = true)]
+    lon: Option<f64>,
+    /// Altitude above sea level in meters
+    #[arg(long, default_value_t = 0.0)]
+    height: f64,
+}
+
+impl SiteArgs {
+    fn observer(&self) -> supernovas::Result<Observer> {
+        match (&self.site, self.lat, self.lon) {
+            (Some(site), _, _) => Observer::from_site(site),
+            (None, Some(lat), Some(lon)) => Ok(Observer::new_on_surface(
+                lat,
+                lon,
+                self.height,
+                Weather::default(),
+            )),
+            _ => Err(supernovas::error::Error::InvalidValue("site")),
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Compute the azimuth, elevation, and airmass of a target
+    Azel {
+        #[command(flatten)]
+        site: SiteArgs,
+        /// Target name: a planet, a bright star, or anything SIMBAD can resolve
+        #[arg(long)]
+        target: String,
+        /// Time of observation ("now" or e.g. "2024-09-17T06:12:18 UTC")
+        #[arg(long, default_value = "now")]
+        time: String,
+        /// Don't apply atmospheric refraction to the elevation
+        #[arg(long)]
+        no_refraction: bool,
+    },
+    /// Produce a nightly table of Sun and Moon events, twilights, and target rise/transit/set
+    Almanac {
+        #[command(flatten)]
+        site: SiteArgs,
+        /// The (UTC) date whose night to tabulate, e.g. "2024-09-17"
+        #[arg(long)]
+        date: String,
+        /// Targets to include: planets, bright stars, or anything SIMBAD can resolve
+        #[arg(long = "target")]
+        targets: Vec<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+/// Resolve a target name locally if possible, falling back to SIMBAD
+///
+/// Without an ephemeris, only the Sun (and Earth) can be placed among the solar-system bodies.
+fn resolve(name: &str, have_ephem: bool) -> Result<Target, Box<dyn std::error::Error>> {
+    if let Ok(planet) = Planet::from_str(name) {
+        if !have_ephem && !matches!(planet, Planet::Sun | Planet::Earth) {
+            return Err(format!("{name} needs a planetary ephemeris, given with --ephem").into());
+        }
+        return Ok(planet.into());
+    }
+    let entry = match BrightStars.resolve(name) {
+        Ok(entry) => entry,
+        Err(_) => Simbad::new("HIP").resolve(name)?,
+    };
+    Ok(entry.into())
+}
+
+/// Parse a time, applying the current Earth orientation parameters from JPL when they are available
+fn parse_time(time: &str) -> Result<Timespec, Box<dyn std::error::Error>> {
+    let epoch = if time.eq_ignore_ascii_case("now") {
+        Epoch::now()?
+    } else {
+        Epoch::from_str(time)?
+    };
+    let eop = Ut1Provider::download_short_from_jpl().unwrap_or_else(|e| {
+        eprintln!("warning: could not download EOP data ({e}), assuming UT1 = UTC");
+        Ut1Provider::default()
+    });
+    Ok(Timespec::from((epoch, eop)))
+}
+
+fn azel(
+    site: &SiteArgs,
+    target: &str,
+    time: &str,
+    no_refraction: bool,
+    have_ephem: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let observer = site.observer()?;
+    let target = resolve(target, have_ephem)?;
+    let time = parse_time(time)?;
+    let frame = Frame::new(Accuracy::Reduced, &observer, &time, 0.0, 0.0)?;
+    let (az, el) = frame.apparent_local_coordinates(ReferenceSystem::CIRS, &target, None)?;
+    let el_obs = if no_refraction {
+        el
+    } else {
+        OpticalRefraction.refract(time.jd(Timescale::TT), &observer, el)
+    };
+    println!("az:      {az:.4}°");
+    println!("el:      {el_obs:.4}°");
+    println!("airmass: {:.3}", airmass(el, AirmassModel::KastenYoung));
+    Ok(())
+}
+
+/// Format an instant as a UTC date and time
+fn format_time(time: &Timespec) -> String {
+    let epoch = Epoch::from_mjd_utc(time.jd(Timescale::UTC) - 2_400_000.5);
+    let (y, mo, d, h, mi, s, _) = epoch.to_gregorian_utc();
+    format!("{y:04}-{mo:02}-{d:02} {h:02}:{mi:02}:{s:02}")
+}
+
+fn format_event(time: &Option<Timespec>) -> String {
+    time.as_ref().map_or_else(|| "-".to_string(), format_time)
+}
+
+/// A string as a quoted JSON string, with quotes, backslashes, and control characters escaped
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn json_event(time: &Option<Timespec>) -> String {
+    time.as_ref()
+        .map_or_else(|| "null".to_string(), |t| json_string(&format_time(t)))
+}
+
+/// The almanac entries of a single target
+struct TargetRow {
+    name: String,
+    events: RiseSet,
+    transit: Timespec,
+    max_el: f64,
+    /// Separation from the Moon in degrees, if an ephemeris was given
+    moon_separation: Option<f64>,
+}
+
+fn almanac(
+    site: &SiteArgs,
+    date: &str,
+    targets: &[String],
+    format: Format,
+    have_ephem: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let observer = site.observer()?;
+    let midnight = Epoch::from_str(&format!("{date}T00:00:00 UTC"))?;
+    let eop = Ut1Provider::download_short_from_jpl().unwrap_or_else(|e| {
+        eprintln!("warning: could not download EOP data ({e}), assuming UT1 = UTC");
+        Ut1Provider::default()
+    });
+    // Start from local noon, so the whole night falls in the searched day
+    let noon =
+        Timespec::from((midnight, eop)).offset((12.0 - observer.longitude() / 15.0) * 3600.0);
+
+    let mut rows = vec![];
+    for (label, kind) in [
+        ("Sun", Twilight::Sunrise),
+        ("Civil twilight", Twilight::Civil),
+        ("Nautical twilight", Twilight::Nautical),
+        ("Astronomical twilight", Twilight::Astronomical),
+    ] {
+        rows.push((label.to_string(), events::twilight(&observer, &noon, kind)?));
+    }
+    if have_ephem {
+        rows.push((
+            "Moon".to_string(),
+            events::rise_set(&Planet::Moon, &observer, &noon, None)?,
+        ));
+    } else {
+        eprintln!("warning: no ephemeris given (--ephem), leaving out the Moon");
+    }
+
+    let mut target_rows = vec![];
+    for name in targets {
+        let target = resolve(name, have_ephem)?;
+        let (transit, max_el) = events::culmination(&target, &observer, &noon, None)?;
+        let frame = Frame::new(Accuracy::Reduced, &observer, &transit, 0.0, 0.0)?;
+        let pos = SkyPosition::try_from_frame(&target, &frame, ReferenceSystem::TOD)?;
+        let moon_separation = if have_ephem {
+            let moon = SkyPosition::try_from_frame(&Planet::Moon, &frame, ReferenceSystem::TOD)?;
+            Some(pos.separation(&moon))
+        } else {
+            None
+        };
+        target_rows.push(TargetRow {
+            name: name.clone(),
+            events: events::rise_set(&target, &observer, &noon, None)?,
+            transit,
+            max_el,
+            moon_separation,
+        });
+    }
+
+    match format {
+        Format::Text => {
+            println!("Almanac for the night of {date} (UTC)");
+            println!("{:<24}{:<22}{:<22}", "", "rise/morning", "set/evening");
+            for (label, events) in &rows {
+                println!(
+                    "{label:<24}{:<22}{:<22}",
+                    format_event(&events.rise),
+                    format_event(&events.set)
+                );
+            }
+            if !target_rows.is_empty() {
+                println!();
+                println!(
+                    "{:<24}{:<22}{:<22}{:<22}{:>8}{:>10}",
+                    "target", "rise", "transit", "set", "max el", "moon sep"
+                );
+            }
+            for row in &target_rows {
+                println!(
+                    "{:<24}{:<22}{:<22}{:<22}{:>7.1}°{:>10}",
+                    row.name,
+                    format_event(&row.events.rise),
+                    format_time(&row.transit),
+                    format_event(&row.events.set),
+                    row.max_el,
+                    row.moon_separation
+                        .map_or_else(|| "-".to_string(), |sep| format!("{sep:.1}°"))
+                );
+            }
+        }
+        Format::Json => {
+            let events = rows
+                .iter()
+                .map(|(label, events)| {
+                    format!(
+                        "{{\"event\":{},\"rise\":{},\"set\":{}}}",
+                        json_string(label),
+                        json_event(&events.rise),
+                        json_event(&events.set)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            let targets = target_rows
+                .iter()
+                .map(|row| {
+                    format!(
+                        "{{\"name\":{},\"rise\":{},\"transit\":{},\"set\":{},\"max_el\":{},\"moon_separation\":{}}}",
+                        json_string(&row.name),
+                        json_event(&row.events.rise),
+                        json_string(&format_time(&row.transit)),
+                        json_event(&row.events.set),
+                        row.max_el,
+                        row.moon_separation
+                            .map_or_else(|| "null".to_string(), |sep| sep.to_string())
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            println!(
+                "{{\"date\":{},\"events\":[{events}],\"targets\":[{targets}]}}",
+                json_string(date)
+            );
+        }
+    }
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    if let Some(path) = &cli.ephem {
+        ephem::provide_ephem(path)?;
+    }
+    let have_ephem = cli.ephem.is_some();
+    match cli.command {
+        Command::Azel {
+            site,
+            target,
+            time,
+            no_refraction,
+        } => azel(&site, &target, &time, no_refraction, have_ephem),
+        Command::Almanac {
+            site,
+            date,
+            targets,
+            format,
+        } => almanac(&site, &date, &targets, format, have_ephem),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_strings_are_escaped() {
+        assert_eq!(json_string("Vega"), r#""Vega""#);
+        assert_eq!(json_string(r#"a "b" \c"#), r#""a \"b\" \\c""#);
+        assert_eq!(json_string("tab\there\n\u{1}"), r#""tab\there\n\u0001""#);
+    }
+}
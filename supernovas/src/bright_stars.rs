@@ -0,0 +1,137 @@
+//! A small, embedded catalog of bright named stars
+//!
+//! The list covers about 300 named stars, including the brightest stars in both hemispheres (plus a few
+//! nearby and high proper motion favorites), with ICRS astrometry at epoch J2000 from Hipparcos. It is intended for pointing tests, first-light
+//! checks, and examples that should run with no network access and no data files, not as a replacement
+//! for a real catalog query.
+
+use crate::{
+    catalog::{separation, CatalogSource},
+    error::Error,
+    positions::CatalogEntry,
+};
+use std::sync::LazyLock;
+
+/// The raw catalog, one star per line
+const CATALOG: &str = include_str!("../data/bright_stars.csv");
+
+/// A single parsed line of the embedded catalog
+struct Star {
+    name: &'static str,
+    hip: i64,
+    ra: f64,
+    dec: f64,
+    pm_ra: f64,
+    pm_dec: f64,
+    parallax: f64,
+    rad_vel: f64,
+}
+
+impl Star {
+    fn entry(&self) -> super::Result<CatalogEntry> {
+        CatalogEntry::new(
+            self.name,
+            "HIP",
+            self.hip,
+            self.ra,
+            self.dec,
+            self.pm_ra,
+            self.pm_dec,
+            self.parallax,
+            self.rad_vel,
+        )
+    }
+}
+
+static STARS: LazyLock<Vec<Star>> = LazyLock::new(|| {
+    CATALOG
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.is_empty())
+        .map(|line| {
+            let cols = line.split(',').collect::<Vec<_>>();
+            Star {
+                name: cols[0],
+                hip: cols[1].parse().expect("invalid embedded HIP number"),
+                ra: cols[2].parse().expect("invalid embedded ra"),
+                dec: cols[3].parse().expect("invalid embedded dec"),
+                pm_ra: cols[4].parse().expect("invalid embedded pm_ra"),
+                pm_dec: cols[5].parse().expect("invalid embedded pm_dec"),
+                parallax: cols[6].parse().expect("invalid embedded parallax"),
+                rad_vel: cols[7].parse().expect("invalid embedded rad_vel"),
+            }
+        })
+        .collect()
+});
+
+/// The embedded bright-star catalog, as a [`CatalogSource`]
+///
+/// Names are matched case-insensitively, and stars can also be resolved by their Hipparcos
+/// number (e.g. "HIP 91262").
+#[derive(Debug, Default, Copy, Clone)]
+pub struct BrightStars;
+
+impl BrightStars {
+    /// All of the stars in the catalog
+    pub fn all(&self) -> super::Result<Vec<CatalogEntry>> {
+        STARS.iter().map(Star::entry).collect()
+    }
+}
+
+impl CatalogSource for BrightStars {
+    fn resolve(&self, name: &str) -> super::Result<CatalogEntry> {
+        let name = name.trim();
+        let hip = name
+            .strip_prefix("HIP")
+            .and_then(|num| num.trim().parse::<i64>().ok());
+        STARS
+            .iter()
+            .find(|star| star.name.eq_ignore_ascii_case(name) || Some(star.hip) == hip)
+            .ok_or_else(|| Error::NotFound(name.to_string()))?
+            .entry()
+    }
+
+    fn cone(&self, ra: f64, dec: f64, radius: f64) -> super::Result<Vec<CatalogEntry>> {
+        STARS
+            .iter()
+            .filter(|star| separation(ra, dec, star.ra, star.dec) <= radius)
+            .map(Star::entry)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn embedded_catalog() {
+        let stars = BrightStars.all().unwrap();
+        assert!(stars.len() >= 300);
+        let names = stars.iter().map(|star| star.name().to_lowercase());
+        assert_eq!(names.collect::<HashSet<_>>().len(), stars.len());
+        let numbers = stars.iter().map(CatalogEntry::number);
+        assert_eq!(numbers.collect::<HashSet<_>>().len(), stars.len());
+    }
+
+    #[test]
+    fn spot_checks() {
+        let vega = BrightStars.resolve("vega").unwrap();
+        assert_eq!(vega.number(), 91262);
+        assert!((vega.ra() - 18.615_649).abs() < 1e-6);
+        assert!((vega.dec() - 38.783_689).abs() < 1e-6);
+        assert_eq!(vega.parallax(), 130.23);
+
+        let sirius = BrightStars.resolve("HIP 32349").unwrap();
+        assert_eq!(sirius.name(), "Sirius");
+        assert_eq!(sirius.pm_dec(), -1223.07);
+        assert!(BrightStars.resolve("Krypton").is_err());
+
+        // The belt of Orion
+        let belt = BrightStars.cone(5.603_559, -1.201_919, 1.5).unwrap();
+        let names = belt.iter().map(|star| star.name()).collect::<Vec<_>>();
+        assert!(["Alnilam", "Alnitak", "Mintaka"]
+            .iter()
+            .all(|name| names.contains(name)));
+    }
+}
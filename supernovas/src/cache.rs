@@ -0,0 +1,219 @@
+//! In-memory memoization of name resolution
+//!
+//! Resolving the same identifier repeatedly (in scripts, or in retry loops around a larger job) only needs to hit the
+//! network once. [`Cached`] wraps any [`CatalogSource`] with a [`ResolutionCache`], and the SIMBAD shortcuts like
+//! [`CatalogEntry::from_simbad`] share the process-wide [`ResolutionCache::global`] cache.
+//!
+//! Only successful resolutions are cached, so identifiers that failed (e.g. from a network outage) are retried.
+
+use crate::{catalog::CatalogSource, positions::CatalogEntry};
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
+/// The default number of entries kept by a cache
+const DEFAULT_CAPACITY: usize = 1024;
+
+/// The process-wide cache used by the SIMBAD shortcuts
+static GLOBAL_CACHE: LazyLock<ResolutionCache> =
+    LazyLock::new(|| ResolutionCache::new(DEFAULT_CAPACITY));
+
+/// A cached entry, with the time it was resolved and when it was last used
+#[derive(Debug)]
+struct Slot {
+    entry: CatalogEntry,
+    resolved: Instant,
+    used: u64,
+}
+
+#[derive(Debug)]
+struct Store {
+    capacity: usize,
+    ttl: Option<Duration>,
+    slots: HashMap<String, Slot>,
+    /// A counter ordering the uses of the slots, for finding the least recently used one
+    clock: u64,
+}
+
+/// A least-recently-used cache of resolved catalog entries, with an optional time-to-live
+///
+/// Clones share the same entries, so one cache can be handed to several sources and threads.
+#[derive(Debug, Clone)]
+pub struct ResolutionCache {
+    store: Arc<Mutex<Store>>,
+}
+
+impl Default for ResolutionCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl ResolutionCache {
+    /// A new, empty cache holding at most `capacity` entries, which never expire
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            store: Arc::new(Mutex::new(Store {
+                capacity,
+                ttl: None,
+                slots: HashMap::new(),
+                clock: 0,
+            })),
+        }
+    }
+
+    /// Expire entries `ttl` after they were resolved
+    pub fn with_ttl(self, ttl: Duration) -> Self {
+        self.set_ttl(Some(ttl));
+        self
+    }
+
+    /// The process-wide cache shared by the SIMBAD shortcuts like [`CatalogEntry::from_simbad`]
+    ///
+    /// It holds 1024 entries which never expire, which can be changed with [`ResolutionCache::set_capacity`] and
+    /// [`ResolutionCache::set_ttl`].
+    pub fn global() -> &'static Self {
+        &GLOBAL_CACHE
+    }
+
+    /// Change the number of entries the cache holds, evicting the least recently used ones if needed
+    pub fn set_capacity(&self, capacity: usize) {
+        let mut store = self.store.lock().unwrap();
+        store.capacity = capacity;
+        store.evict();
+    }
+
+    /// Change the time after which entries expire (`None` never expires them)
+    pub fn set_ttl(&self, ttl: Option<Duration>) {
+        self.store.lock().unwrap().ttl = ttl;
+    }
+
+    /// The cached entry for the key, if it is present and hasn't expired
+    pub fn get(&self, key: &str) -> Option<CatalogEntry> {
+        let mut store = self.store.lock().unwrap();
+        let ttl = store.ttl;
+        store.clock += 1;
+        let clock = store.clock;
+        let slot = store.slots.get_mut(key)?;
+        if ttl.is_some_and(|ttl| slot.resolved.elapsed() > ttl) {
+            store.slots.remove(key);
+            return None;
+        }
+        slot.used = clock;
+        Some(slot.entry.clone())
+    }
+
+    /// Cache an entry under the key
+    pub fn insert(&self, key: &str, entry: CatalogEntry) {
+        let mut store = self.store.lock().unwrap();
+        store.clock += 1;
+        let used = store.clock;
+        store.slots.insert(
+            key.to_owned(),
+            Slot {
+                entry,
+                resolved: Instant::now(),
+                used,
+            },
+        );
+        store.evict();
+    }
+
+    /// The cached entry for the key, or else the result of `resolve`, caching it if it succeeded
+    pub fn get_or_resolve(
+        &self,
+        key: &str,
+        resolve: impl FnOnce() -> super::Result<CatalogEntry>,
+    ) -> super::Result<CatalogEntry> {
+        if let Some(entry) = self.get(key) {
+            return Ok(entry);
+        }
+        // Don't hold the lock during the (slow) resolution, so other threads can use the cache meanwhile
+        let entry = resolve()?;
+        self.insert(key, entry.clone());
+        Ok(entry)
+    }
+
+    /// The number of cached entries, including any that have expired but weren't looked up since
+    pub fn len(&self) -> usize {
+        self.store.lock().unwrap().slots.len()
+    }
+
+    /// Whether the cache has no entries
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove every entry
+    pub fn clear(&self) {
+        self.store.lock().unwrap().slots.clear();
+    }
+}
+
+impl Store {
+    /// Drop the least recently used entries until the cache is within its capacity
+    fn evict(&mut self) {
+        while self.slots.len() > self.capacity {
+            let Some(oldest) = self
+                .slots
+                .iter()
+                .min_by_key(|(_, slot)| slot.used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            self.slots.remove(&oldest);
+        }
+    }
+}
+
+/// A [`CatalogSource`] whose resolutions are memoized in a [`ResolutionCache`]
+///
+/// Cone searches are passed through uncached.
+#[derive(Debug, Clone)]
+pub struct Cached<S> {
+    source: S,
+    cache: ResolutionCache,
+}
+
+impl<S: CatalogSource> Cached<S> {
+    /// Wrap a source with a new cache of the default capacity (1024 entries)
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            cache: ResolutionCache::default(),
+        }
+    }
+
+    /// Use the given cache, e.g. one shared with other sources
+    ///
+    /// Entries are keyed by name alone, so only share a cache between sources configured alike (same catalog and
+    /// reference system).
+    pub fn with_cache(mut self, cache: ResolutionCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// The cache of the resolutions
+    pub fn cache(&self) -> &ResolutionCache {
+        &self.cache
+    }
+
+    /// The wrapped source
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+}
+
+impl<S: CatalogSource> CatalogSource for Cached<S> {
+    fn resolve(&self, name: &str) -> super::Result<CatalogEntry> {
+        self.cache
+            .get_or_resolve(name.trim(), || self.source.resolve(name))
+    }
+
+    fn cone(&self, ra: f64, dec: f64, radius: f64) -> super::Result<Vec<CatalogEntry>> {
+        self.source.cone(ra, dec, radius)
+    }
+}
@@ -0,0 +1,223 @@
+//! Abstractions over sources of astrometric catalog data, and a local collection of entries
+
+use crate::{
+    error::Error,
+    positions::{CatalogEntry, CatalogSystem, Frame, ReferenceSystem, SkyPosition, Transformation},
+    refraction::RefractionModel,
+};
+
+/// A database that can resolve objects into [`CatalogEntry`]s
+///
+/// This lets downstream code be written against any source of astrometry (remote services, local lists,
+/// or mocks in tests) instead of hard-coding a network query.
+pub trait CatalogSource {
+    /// Resolve a single object by its identifier
+    fn resolve(&self, name: &str) -> super::Result<CatalogEntry>;
+
+    /// Find all objects within a cone on the sky
+    ///
+    /// - ra: ICRS right ascension of the cone center in hours
+    /// - dec: ICRS declination of the cone center in degrees
+    /// - radius: Radius of the cone in degrees
+    fn cone(&self, ra: f64, dec: f64, radius: f64) -> super::Result<Vec<CatalogEntry>>;
+}
+
+/// A collection of catalog entries, e.g. the targets of a survey
+///
+/// Entries are kept in insertion order. A catalog is itself a [`CatalogSource`], resolving entries by name.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Catalog {
+    entries: Vec<CatalogEntry>,
+}
+
+impl Catalog {
+    /// Construct a new, empty catalog
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an entry to the end of the catalog
+    pub fn push(&mut self, entry: CatalogEntry) {
+        self.entries.push(entry);
+    }
+
+    /// The number of entries in the catalog
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the catalog has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// All of the entries, in order
+    pub fn entries(&self) -> &[CatalogEntry] {
+        &self.entries
+    }
+
+    /// Iterate over the entries in order
+    pub fn iter(&self) -> std::slice::Iter<'_, CatalogEntry> {
+        self.entries.iter()
+    }
+
+    /// The first entry with the given name (compared case-insensitively)
+    pub fn by_name(&self, name: &str) -> Option<&CatalogEntry> {
+        let name = name.trim();
+        self.entries
+            .iter()
+            .find(|entry| entry.name().eq_ignore_ascii_case(name))
+    }
+
+    /// The first entry with the given number in the given catalog
+    pub fn by_number(&self, catalog: &str, number: i64) -> Option<&CatalogEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.catalog() == catalog && entry.number() == number)
+    }
+
+    /// Apply a coordinate transformation to every entry, see [`CatalogEntry::transform`]
+    ///
+    /// The catalog is only updated if every entry transforms successfully, so it never ends up with mixed systems.
+    pub fn transform(
+        &mut self,
+        transformation: Transformation,
+        new_cat: Option<String>,
+    ) -> super::Result<()> {
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let mut entry = entry.clone();
+                entry.transform(transformation, new_cat.clone())?;
+                Ok(entry)
+            })
+            .collect::<super::Result<Vec<_>>>()?;
+        self.entries = entries;
+        Ok(())
+    }
+
+    /// A copy of the catalog with every entry converted into another reference system and epoch, see
+    /// [`CatalogEntry::to_system`]
+    pub fn to_system(&self, system: CatalogSystem) -> super::Result<Self> {
+        self.entries
+            .iter()
+            .map(|entry| entry.to_system(system))
+            .collect()
+    }
+
+    /// The place of every entry in the given frame, in order
+    pub fn place(
+        &self,
+        frame: &Frame,
+        ref_sys: ReferenceSystem,
+    ) -> super::Result<Vec<SkyPosition>> {
+        self.entries
+            .iter()
+            .map(|entry| SkyPosition::try_from_frame(entry, frame, ref_sys))
+            .collect()
+    }
+
+    /// The local coordinates (az,el in degrees) of every entry in the given frame, in order
+    pub fn apparent_local_coordinates(
+        &self,
+        frame: &Frame,
+        ref_sys: ReferenceSystem,
+        refraction: Option<&dyn RefractionModel>,
+    ) -> super::Result<Vec<(f64, f64)>> {
+        self.entries
+            .iter()
+            .map(|entry| frame.apparent_local_coordinates(ref_sys, entry, refraction))
+            .collect()
+    }
+}
+
+impl CatalogSource for Catalog {
+    fn resolve(&self, name: &str) -> super::Result<CatalogEntry> {
+        self.by_name(name)
+            .cloned()
+            .ok_or_else(|| Error::NotFound(name.to_string()))
+    }
+
+    fn cone(&self, ra: f64, dec: f64, radius: f64) -> super::Result<Vec<CatalogEntry>> {
+        Ok(self
+            .entries
+            .iter()
+            .filter(|entry| separation(ra, dec, entry.ra(), entry.dec()) <= radius)
+            .cloned()
+            .collect())
+    }
+}
+
+impl From<Vec<CatalogEntry>> for Catalog {
+    fn from(entries: Vec<CatalogEntry>) -> Self {
+        Self { entries }
+    }
+}
+
+impl FromIterator<CatalogEntry> for Catalog {
+    fn from_iter<I: IntoIterator<Item = CatalogEntry>>(iter: I) -> Self {
+        Self {
+            entries: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<CatalogEntry> for Catalog {
+    fn extend<I: IntoIterator<Item = CatalogEntry>>(&mut self, iter: I) {
+        self.entries.extend(iter);
+    }
+}
+
+impl IntoIterator for Catalog {
+    type Item = CatalogEntry;
+    type IntoIter = std::vec::IntoIter<CatalogEntry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Catalog {
+    type Item = &'a CatalogEntry;
+    type IntoIter = std::slice::Iter<'a, CatalogEntry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+/// Angular separation in degrees between two positions given in hours and degrees
+pub(crate) fn separation(ra1: f64, dec1: f64, ra2: f64, dec2: f64) -> f64 {
+    let (ra1, dec1) = ((ra1 * 15.0).to_radians(), dec1.to_radians());
+    let (ra2, dec2) = ((ra2 * 15.0).to_radians(), dec2.to_radians());
+    // Haversine, for numerical stability at small separations
+    let a = ((dec2 - dec1) / 2.0).sin().powi(2)
+        + dec1.cos() * dec2.cos() * ((ra2 - ra1) / 2.0).sin().powi(2);
+    (2.0 * a.sqrt().asin()).to_degrees()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use supernovas_sys::SIZE_OF_CAT_NAME;
+
+    #[test]
+    fn transform_all_or_nothing() {
+        let mut catalog = ["a", "b"]
+            .into_iter()
+            .map(|name| CatalogEntry::new(name, "", 0, 6.0, 20.0, 0.0, 0.0, 0.0, 0.0).unwrap())
+            .collect::<Catalog>();
+        let before = catalog.clone();
+        let long_name = "X".repeat(SIZE_OF_CAT_NAME as usize + 1);
+        assert!(catalog
+            .transform(Transformation::ICRSToJ2000, Some(long_name))
+            .is_err());
+        assert_eq!(catalog, before);
+
+        catalog
+            .transform(Transformation::ICRSToJ2000, Some("FK5".to_owned()))
+            .unwrap();
+        assert!(catalog.iter().all(|entry| entry.catalog() == "FK5"));
+    }
+}
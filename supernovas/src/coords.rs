@@ -0,0 +1,403 @@
+//! Conversions between ICRS equatorial coordinates and other celestial coordinate systems
+
+use crate::error::Error;
+use std::{fmt, str::FromStr};
+use supernovas_sys::{equ2gal, gal2equ};
+
+/// Galactic longitude of the supergalactic north pole in degrees (de Vaucouleurs et al. 1991)
+const SG_POLE_L: f64 = 47.37;
+/// Galactic latitude of the supergalactic north pole in degrees
+const SG_POLE_B: f64 = 6.32;
+/// Galactic longitude of the supergalactic origin (which lies in the galactic plane) in degrees
+const SG_ORIGIN_L: f64 = 137.37;
+
+/// E-terms of aberration of the FK4 system in radians
+const FK4_E_TERMS: [f64; 3] = [-1.62557e-6, -0.31919e-6, -0.13843e-6];
+
+/// The position (first three rows) and fictitious proper motion (last three rows, in arcseconds per century) parts
+/// of the FK4 to FK5 rotation (Standish 1982, as in SLALIB `sla_FK45Z`)
+const FK4_TO_FK5: [[f64; 3]; 6] = [
+    [0.9999256782, -0.0111820611, -0.0048579477],
+    [0.0111820610, 0.9999374784, -0.0000271765],
+    [0.0048579479, -0.0000271474, 0.9999881997],
+    [-0.000551, -0.238565, 0.435739],
+    [0.238514, -0.002667, -0.008541],
+    [-0.435623, 0.012254, 0.002117],
+];
+
+/// Scale of the fictitious proper motion rows (arcseconds per century) to radians per year
+const PMF: f64 = 100.0 * 206_264.806_247_096_36;
+
+/// Julian epoch of B1950.0 in years
+const B1950_JULIAN_EPOCH: f64 = 1949.999_789_6;
+
+/// Converts FK4 B1950.0 coordinates (ra in hours, dec in degrees) of a source without known proper motion to ICRS
+///
+/// This removes the E-terms of aberration and applies the FK4 to FK5 rotation, assuming zero proper motion in FK5
+/// (appropriate for e.g. extragalactic radio sources). FK5 J2000.0 is taken to coincide with the ICRS, which holds to
+/// a few tens of mas, well within the accuracy of FK4 positions.
+pub fn b1950_to_icrs(ra: f64, dec: f64) -> (f64, f64) {
+    let r0 = unit_vector(ra * 15.0, dec);
+    // Remove the E-terms
+    let w = dot(&r0, &FK4_E_TERMS);
+    let v1: [f64; 3] = std::array::from_fn(|i| r0[i] - FK4_E_TERMS[i] + w * r0[i]);
+    // Rotate into FK5, allowing for the fictitious proper motion of FK4 between B1950 and J2000
+    let v2: [f64; 6] = std::array::from_fn(|i| dot(&FK4_TO_FK5[i], &v1));
+    let w = (B1950_JULIAN_EPOCH - 2000.0) / PMF;
+    let (ra, dec) = angles(&std::array::from_fn(|i| v2[i] + w * v2[i + 3]));
+    (ra / 15.0, dec)
+}
+
+/// Converts ICRS coordinates (ra in hours, dec in degrees) to FK4 B1950.0 coordinates, the inverse of
+/// [`b1950_to_icrs`]
+pub fn icrs_to_b1950(ra: f64, dec: f64) -> (f64, f64) {
+    let target = unit_vector(ra * 15.0, dec);
+    // The forward transform is within ~1° of the identity, so a fixed-point iteration converges very quickly
+    let mut guess = target;
+    for _ in 0..10 {
+        let (ra, dec) = angles(&guess);
+        let (ra, dec) = b1950_to_icrs(ra / 15.0, dec);
+        let forward = unit_vector(ra * 15.0, dec);
+        let error: [f64; 3] = std::array::from_fn(|i| target[i] - forward[i]);
+        guess = std::array::from_fn(|i| guess[i] + error[i]);
+        if dot(&error, &error) < 1e-28 {
+            break;
+        }
+    }
+    let (ra, dec) = angles(&guess);
+    (ra / 15.0, dec)
+}
+
+/// Converts ICRS equatorial coordinates (ra in hours, dec in degrees) to galactic coordinates (l,b in degrees)
+pub fn icrs_to_galactic(ra: f64, dec: f64) -> super::Result<(f64, f64)> {
+    let mut l = 0.0;
+    let mut b = 0.0;
+    // Safety: The output pointers are valid
+    let ret = unsafe { equ2gal(ra, dec, &mut l, &mut b) };
+    if ret != 0 {
+        return Err(Error::LowerLevel(ret));
+    }
+    Ok((l, b))
+}
+
+/// Converts galactic coordinates (l,b in degrees) to ICRS equatorial coordinates (ra in hours, dec in degrees)
+pub fn galactic_to_icrs(l: f64, b: f64) -> super::Result<(f64, f64)> {
+    let mut ra = 0.0;
+    let mut dec = 0.0;
+    // Safety: The output pointers are valid
+    let ret = unsafe { gal2equ(l, b, &mut ra, &mut dec) };
+    if ret != 0 {
+        return Err(Error::LowerLevel(ret));
+    }
+    Ok((ra, dec))
+}
+
+/// Converts galactic coordinates (l,b in degrees) to supergalactic coordinates (SGL,SGB in degrees)
+pub fn galactic_to_supergalactic(l: f64, b: f64) -> (f64, f64) {
+    let [x, y, z] = supergalactic_axes();
+    let v = unit_vector(l, b);
+    angles(&[dot(&x, &v), dot(&y, &v), dot(&z, &v)])
+}
+
+/// Converts supergalactic coordinates (SGL,SGB in degrees) to galactic coordinates (l,b in degrees)
+pub fn supergalactic_to_galactic(sgl: f64, sgb: f64) -> (f64, f64) {
+    let [x, y, z] = supergalactic_axes();
+    let v = unit_vector(sgl, sgb);
+    // The axes are orthonormal, so the inverse rotation is the transpose
+    angles(&[
+        x[0] * v[0] + y[0] * v[1] + z[0] * v[2],
+        x[1] * v[0] + y[1] * v[1] + z[1] * v[2],
+        x[2] * v[0] + y[2] * v[1] + z[2] * v[2],
+    ])
+}
+
+/// Converts ICRS equatorial coordinates (ra in hours, dec in degrees) to supergalactic coordinates (SGL,SGB in
+/// degrees)
+pub fn icrs_to_supergalactic(ra: f64, dec: f64) -> super::Result<(f64, f64)> {
+    let (l, b) = icrs_to_galactic(ra, dec)?;
+    Ok(galactic_to_supergalactic(l, b))
+}
+
+/// Converts supergalactic coordinates (SGL,SGB in degrees) to ICRS equatorial coordinates (ra in hours, dec in
+/// degrees)
+pub fn supergalactic_to_icrs(sgl: f64, sgb: f64) -> super::Result<(f64, f64)> {
+    let (l, b) = supergalactic_to_galactic(sgl, sgb);
+    galactic_to_icrs(l, b)
+}
+
+/// The supergalactic x, y, and z axes expressed in galactic Cartesian coordinates
+fn supergalactic_axes() -> [[f64; 3]; 3] {
+    let x = unit_vector(SG_ORIGIN_L, 0.0);
+    let z = unit_vector(SG_POLE_L, SG_POLE_B);
+    let y = [
+        z[1] * x[2] - z[2] * x[1],
+        z[2] * x[0] - z[0] * x[2],
+        z[0] * x[1] - z[1] * x[0],
+    ];
+    [x, y, z]
+}
+
+/// The Cartesian unit vector for a longitude and latitude in degrees
+fn unit_vector(lon: f64, lat: f64) -> [f64; 3] {
+    let (lon, lat) = (lon.to_radians(), lat.to_radians());
+    [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()]
+}
+
+/// The longitude in [0, 360) and latitude in degrees of a Cartesian vector
+fn angles(v: &[f64; 3]) -> (f64, f64) {
+    let lon = v[1].atan2(v[0]).to_degrees().rem_euclid(360.0);
+    let lat = v[2].atan2(v[0].hypot(v[1])).to_degrees();
+    (lon, lat)
+}
+
+fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// A signed angle in degrees, arcminutes, and arcseconds, e.g. a declination
+///
+/// The sign is kept separately from the degrees so that angles between -1° and 0° (like `-00° 30'`) can be
+/// represented.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Dms {
+    /// Whether the angle is negative
+    pub negative: bool,
+    /// Whole degrees
+    pub deg: u16,
+    /// Whole arcminutes
+    pub min: u16,
+    /// Arcseconds
+    pub sec: f64,
+}
+
+impl Dms {
+    /// An angle of `sign` × (deg° min' sec")
+    pub fn new(negative: bool, deg: u16, min: u16, sec: f64) -> Self {
+        Self {
+            negative,
+            deg,
+            min,
+            sec,
+        }
+    }
+
+    /// The angle in decimal degrees
+    pub fn degrees(&self) -> f64 {
+        let magnitude = f64::from(self.deg) + f64::from(self.min) / 60.0 + self.sec / 3600.0;
+        if self.negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+}
+
+impl From<Dms> for f64 {
+    fn from(value: Dms) -> Self {
+        value.degrees()
+    }
+}
+
+impl FromStr for Dms {
+    type Err = Error;
+
+    /// Parses an optionally signed "dd mm ss.s" or "dd:mm:ss.s", where the minutes and seconds may be left off
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (negative, s) = match s.strip_prefix(['-', '\u{2212}']) {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let mut parts = s.split([' ', ':']).filter(|part| !part.is_empty());
+        let deg = parts.next().ok_or(Error::InvalidString)?;
+        let min = parts.next().unwrap_or("0");
+        let sec = parts.next().unwrap_or("0");
+        if parts.next().is_some() {
+            return Err(Error::InvalidString);
+        }
+        Ok(Self {
+            negative,
+            deg: deg.parse().map_err(|_| Error::InvalidString)?,
+            min: min.parse().map_err(|_| Error::InvalidString)?,
+            sec: sec.parse().map_err(|_| Error::InvalidString)?,
+        })
+    }
+}
+
+/// A right ascension, stored in hours within [0, 24)
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct RightAscension(pub(crate) f64);
+
+impl RightAscension {
+    /// A right ascension from fractional hours, wrapped into [0, 24)
+    pub fn from_hours(hours: f64) -> super::Result<Self> {
+        if !hours.is_finite() {
+            return Err(Error::InvalidValue("ra"));
+        }
+        // rem_euclid can round up to exactly 24 for tiny negative values
+        let hours = hours.rem_euclid(24.0);
+        Ok(Self(if hours >= 24.0 { 0.0 } else { hours }))
+    }
+
+    /// A right ascension from fractional degrees, wrapped into [0, 360)
+    pub fn from_degrees(degrees: f64) -> super::Result<Self> {
+        Self::from_hours(degrees / 15.0)
+    }
+
+    /// A right ascension from hours, minutes, and seconds of time
+    pub fn from_hms(h: u8, m: u8, s: f64) -> super::Result<Self> {
+        if h >= 24 || m >= 60 || !(0.0..60.0).contains(&s) {
+            return Err(Error::InvalidValue("ra"));
+        }
+        Ok(Self(f64::from(h) + f64::from(m) / 60.0 + s / 3600.0))
+    }
+
+    /// The right ascension in fractional hours
+    pub fn hours(&self) -> f64 {
+        self.0
+    }
+
+    /// The right ascension in fractional degrees
+    pub fn degrees(&self) -> f64 {
+        self.0 * 15.0
+    }
+
+    /// The right ascension as hours, minutes, and seconds of time
+    pub fn hms(&self) -> (u8, u8, f64) {
+        let (h, m, s) = sexagesimal(self.0);
+        (h as u8, m as u8, s)
+    }
+}
+
+impl From<RightAscension> for f64 {
+    fn from(value: RightAscension) -> Self {
+        value.hours()
+    }
+}
+
+impl FromStr for RightAscension {
+    type Err = Error;
+
+    /// Parses "hh mm ss.s" or "hh:mm:ss.s" (where the minutes and seconds may be left off), or "12h34m56.7s"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().replace(['h', 'm'], " ").replace('s', "");
+        let dms: Dms = s.parse()?;
+        if dms.negative || dms.min >= 60 || !(0.0..60.0).contains(&dms.sec) {
+            return Err(Error::InvalidString);
+        }
+        let hours = dms.degrees();
+        if hours >= 24.0 {
+            return Err(Error::InvalidString);
+        }
+        Ok(Self(hours))
+    }
+}
+
+impl fmt::Display for RightAscension {
+    /// Formats as "hh:mm:ss.sss", with the precision (default 3) setting the decimals of the seconds
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision().unwrap_or(3);
+        let (h, m, s) = rounded_sexagesimal(self.0, precision);
+        // Rounding up can carry all the way into the next day
+        write!(
+            f,
+            "{:02}:{m:02}:{s:0width$.precision$}",
+            h % 24,
+            width = seconds_width(precision)
+        )
+    }
+}
+
+/// A declination, stored in degrees within [-90, 90]
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct Declination(pub(crate) f64);
+
+impl Declination {
+    /// A declination from fractional degrees
+    pub fn from_degrees(degrees: f64) -> super::Result<Self> {
+        if !degrees.is_finite() {
+            return Err(Error::InvalidValue("dec"));
+        }
+        if !(-90.0..=90.0).contains(&degrees) {
+            return Err(Error::OutOfRange {
+                param: "dec",
+                value: degrees,
+            });
+        }
+        Ok(Self(degrees))
+    }
+
+    /// A declination from signed degrees, arcminutes, and arcseconds
+    pub fn from_dms(dms: Dms) -> super::Result<Self> {
+        if dms.min >= 60 || !(0.0..60.0).contains(&dms.sec) {
+            return Err(Error::InvalidValue("dec"));
+        }
+        Self::from_degrees(dms.degrees())
+    }
+
+    /// The declination in fractional degrees
+    pub fn degrees(&self) -> f64 {
+        self.0
+    }
+
+    /// The declination as signed degrees, arcminutes, and arcseconds
+    pub fn dms(&self) -> Dms {
+        let (deg, min, sec) = sexagesimal(self.0.abs());
+        Dms::new(self.0 < 0.0, deg as u16, min as u16, sec)
+    }
+}
+
+impl From<Declination> for f64 {
+    fn from(value: Declination) -> Self {
+        value.degrees()
+    }
+}
+
+impl FromStr for Declination {
+    type Err = Error;
+
+    /// Parses "±dd mm ss.s" or "±dd:mm:ss.s", where the minutes and seconds may be left off
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_dms(s.parse()?).map_err(|_| Error::InvalidString)
+    }
+}
+
+impl fmt::Display for Declination {
+    /// Formats as "±dd:mm:ss.ss", with the precision (default 2) setting the decimals of the arcseconds
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision().unwrap_or(2);
+        let (d, m, s) = rounded_sexagesimal(self.0.abs(), precision);
+        let sign = if self.0 < 0.0 { '-' } else { '+' };
+        write!(
+            f,
+            "{sign}{d:02}:{m:02}:{s:0width$.precision$}",
+            width = seconds_width(precision)
+        )
+    }
+}
+
+/// The width of a zero-padded seconds field with the given decimals
+fn seconds_width(decimals: usize) -> usize {
+    match decimals {
+        0 => 2,
+        _ => decimals + 3,
+    }
+}
+
+/// Splits a non-negative value into whole units, whole sixtieths, and the remaining 3600ths
+fn sexagesimal(value: f64) -> (u32, u32, f64) {
+    let units = value.trunc();
+    let minutes = ((value - units) * 60.0).trunc();
+    let seconds = ((value - units) * 60.0 - minutes) * 60.0;
+    (units as u32, minutes as u32, seconds)
+}
+
+/// Like [`sexagesimal`], but rounded to the given decimals of the last field, carrying into the others
+fn rounded_sexagesimal(value: f64, decimals: usize) -> (u32, u32, f64) {
+    let scale = 10f64.powi(decimals as i32);
+    let total = (value * 3600.0 * scale).round() / scale;
+    let units = (total / 3600.0).trunc();
+    let minutes = ((total - units * 3600.0) / 60.0).trunc();
+    let seconds = total - units * 3600.0 - minutes * 60.0;
+    (units as u32, minutes as u32, seconds)
+}
@@ -0,0 +1,165 @@
+//! Doppler predictions for tracking sources with a known state
+
+use crate::{
+    coords::galactic_to_icrs,
+    positions::{Frame, Placeable, ReferenceSystem, SkyPosition},
+};
+
+/// Speed of light in km/s
+pub const C: f64 = 299_792.458;
+
+/// Solar motion relative to the kinematic LSR: 20 km/s towards (B1900) 18h, +30°, here precessed to J2000
+const LSRK_APEX: (f64, f64, f64) = (18.063_969_4, 30.004_667, 20.0);
+
+/// Solar motion relative to the dynamical LSR: (U,V,W) = (9,12,7) km/s, towards l = 53.13°, b = 25.02°
+const LSRD_APEX: (f64, f64, f64) = (17.832_963_1, 28.117_767, 16.552_945);
+
+/// Solar motion relative to the CMB (Planck 2018) as galactic l, b in degrees and speed in km/s
+const CMB_APEX_GALACTIC: (f64, f64, f64) = (264.021, 48.253, 369.82);
+
+/// 2012 definition of the astronomical unit from the IAU in km
+pub(crate) const AU: f64 = 149_597_870.700;
+
+/// The topocentric distance and line-of-sight velocity of a source
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RangeRate {
+    /// Distance to the source in km
+    pub range: f64,
+    /// Rate of change of the distance in km/s, positive when receding
+    pub range_rate: f64,
+}
+
+impl RangeRate {
+    /// Compute the range and range-rate from a relative position (km) and velocity (km/s)
+    pub fn from_state(pos: &[f64; 3], vel: &[f64; 3]) -> Self {
+        let range = pos.iter().map(|x| x * x).sum::<f64>().sqrt();
+        let range_rate = pos.iter().zip(vel).map(|(x, v)| x * v).sum::<f64>() / range;
+        Self { range, range_rate }
+    }
+
+    /// The frequency that will be received for a signal transmitted by the source at `tx_freq`
+    pub fn received_frequency(&self, tx_freq: f64) -> f64 {
+        received_frequency(tx_freq, self.range_rate)
+    }
+}
+
+/// The (relativistic, line-of-sight) received frequency of a signal transmitted at `tx_freq` from a source
+/// moving with `range_rate` km/s (positive when receding)
+pub fn received_frequency(tx_freq: f64, range_rate: f64) -> f64 {
+    let beta = range_rate / C;
+    tx_freq * ((1.0 - beta) / (1.0 + beta)).sqrt()
+}
+
+/// Computes the geometric range and range-rate of a source (e.g. a spacecraft in an SPK kernel) relative to the
+/// frame's observer
+pub fn range_rate(frame: &Frame, target: &impl Placeable) -> super::Result<RangeRate> {
+    let (pos, vel) = frame.geom_posvel(target, ReferenceSystem::ICRS)?;
+    // Convert from AU and AU/day to km and km/s
+    let pos = pos.map(|x| x * AU);
+    let vel = vel.map(|v| v * AU / 86_400.0);
+    Ok(RangeRate::from_state(&pos, &vel))
+}
+
+/// The sky frequency at which a line with rest frequency `rest_freq` is observed towards a source, for setting the
+/// Doppler tracking of a spectrometer
+pub fn sky_frequency(frame: &Frame, target: &impl Placeable, rest_freq: f64) -> super::Result<f64> {
+    Ok(received_frequency(
+        rest_freq,
+        frame.radial_velocity(target)?,
+    ))
+}
+
+/// The standard rest frames that spectral velocities are referenced to
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VelocityFrame {
+    /// Relative to the observer
+    Topocentric,
+    /// Relative to the solar-system barycenter
+    Barycentric,
+    /// Relative to the kinematic local standard of rest
+    LSRK,
+    /// Relative to the dynamical local standard of rest
+    LSRD,
+    /// Relative to the cosmic microwave background
+    CMB,
+}
+
+/// The definitions of a velocity in terms of an observed and a rest frequency
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VelocityConvention {
+    /// v = c (f0 / f - 1), i.e. cz
+    Optical,
+    /// v = c (1 - f / f0)
+    Radio,
+    /// v = c (f0² - f²) / (f0² + f²)
+    Relativistic,
+}
+
+impl VelocityConvention {
+    /// The velocity in km/s of a line with rest frequency `rest_freq` observed at `freq`
+    pub fn velocity(&self, rest_freq: f64, freq: f64) -> f64 {
+        match self {
+            VelocityConvention::Optical => C * (rest_freq / freq - 1.0),
+            VelocityConvention::Radio => C * (1.0 - freq / rest_freq),
+            VelocityConvention::Relativistic => {
+                let (f0, f) = (rest_freq * rest_freq, freq * freq);
+                C * (f0 - f) / (f0 + f)
+            }
+        }
+    }
+
+    /// The frequency at which a line with rest frequency `rest_freq` is observed at velocity `v` in km/s
+    pub fn frequency(&self, rest_freq: f64, v: f64) -> f64 {
+        match self {
+            VelocityConvention::Optical => rest_freq / (1.0 + v / C),
+            VelocityConvention::Radio => rest_freq * (1.0 - v / C),
+            VelocityConvention::Relativistic => received_frequency(rest_freq, v),
+        }
+    }
+}
+
+/// The velocity in km/s to add to a topocentric velocity towards a source to reference it to the given frame
+///
+/// The corrections are the projections of the observer's barycentric velocity and of the solar motion relative to
+/// each standard of rest onto the line of sight, and are added linearly (which is accurate well below 1 m/s for
+/// these speeds).
+pub fn frame_correction(
+    frame: &Frame,
+    target: &impl Placeable,
+    to: VelocityFrame,
+) -> super::Result<f64> {
+    let solar_apex = match to {
+        VelocityFrame::Topocentric => return Ok(0.0),
+        VelocityFrame::Barycentric => None,
+        VelocityFrame::LSRK => Some(LSRK_APEX),
+        VelocityFrame::LSRD => Some(LSRD_APEX),
+        VelocityFrame::CMB => {
+            let (l, b, speed) = CMB_APEX_GALACTIC;
+            let (ra, dec) = galactic_to_icrs(l, b)?;
+            Some((ra, dec, speed))
+        }
+    };
+    let barycentric = frame.observer_velocity_toward(target)?;
+    let solar = match solar_apex {
+        None => 0.0,
+        Some((ra, dec, speed)) => {
+            let sky_pos = SkyPosition::try_from_frame(target, frame, ReferenceSystem::ICRS)?;
+            let (ra, dec) = ((ra * 15.0).to_radians(), dec.to_radians());
+            let apex = [dec.cos() * ra.cos(), dec.cos() * ra.sin(), dec.sin()];
+            let r_hat = sky_pos.r_hat();
+            speed * (apex[0] * r_hat[0] + apex[1] * r_hat[1] + apex[2] * r_hat[2])
+        }
+    };
+    Ok(barycentric + solar)
+}
+
+/// Converts a velocity towards a source in km/s from one rest frame to another
+pub fn convert_velocity(
+    frame: &Frame,
+    target: &impl Placeable,
+    v: f64,
+    from: VelocityFrame,
+    to: VelocityFrame,
+) -> super::Result<f64> {
+    Ok(v - frame_correction(frame, target, from)? + frame_correction(frame, target, to)?)
+}
@@ -0,0 +1,296 @@
+//! Earth orientation parameters (EOP)
+//!
+//! [`EopTable`] reads the daily IERS series (finals2000A or EOP C04) and interpolates them to supply UT1-UTC to
+//! [`Timespec`](crate::time::Timespec) and the polar offsets (dx, dy) to [`Frame`](crate::positions::Frame)
+//! without needing hifitime.
+//!
+//! The frame-based routines take the celestial pole offsets per frame, but the legacy routines such as
+//! [`SkyPosition::place`](crate::positions::SkyPosition::place) read them from global state in the C library. These
+//! settings are process-wide, so they should be configured once, before any calculations.
+
+use crate::error::Error;
+use std::{
+    io::BufRead,
+    sync::{Arc, RwLock},
+};
+use supernovas_sys::{cel_pole, novas_pole_offset_type};
+
+/// Observed offsets of the celestial pole from the IAU2000A/IAU2006 model, e.g. as published in IERS Bulletin A
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PoleOffsets {
+    /// Offsets in longitude and obliquity (dψ, dε) in mas, relative to the IAU1976/1980 model
+    DpsiDeps { dpsi: f64, deps: f64 },
+    /// Offsets (dx, dy) in mas of the Celestial Intermediate Pole in the GCRS
+    XY { dx: f64, dy: f64 },
+}
+
+/// Set the global celestial pole offsets used by the legacy routines
+///
+/// - jd_tt: Terrestrial Time (TT) Julian date the offsets were measured for (only used for [`PoleOffsets::DpsiDeps`])
+/// - offsets: The measured pole offsets
+pub fn set_celestial_pole(jd_tt: f64, offsets: PoleOffsets) -> super::Result<()> {
+    let (kind, d1, d2) = match offsets {
+        PoleOffsets::DpsiDeps { dpsi, deps } => {
+            (novas_pole_offset_type::POLE_OFFSETS_DPSI_DEPS, dpsi, deps)
+        }
+        PoleOffsets::XY { dx, dy } => (novas_pole_offset_type::POLE_OFFSETS_X_Y, dx, dy),
+    };
+    // Safety: This only sets global state in the C library
+    let ret = unsafe { cel_pole(jd_tt, kind, d1, d2) };
+    if ret != 0 {
+        return Err(Error::LowerLevel(ret));
+    }
+    Ok(())
+}
+
+/// Reset the global celestial pole offsets to zero
+pub fn clear_celestial_pole() {
+    // Safety: Zero X,Y offsets are always valid
+    let _ = unsafe { cel_pole(0.0, novas_pole_offset_type::POLE_OFFSETS_X_Y, 0.0, 0.0) };
+}
+
+/// The EOP table used by the automatic constructors, such as [`Frame::new_auto`](crate::positions::Frame::new_auto)
+static EOP_TABLE: RwLock<Option<Arc<EopTable>>> = RwLock::new(None);
+
+/// Set the global EOP table used by the automatic constructors
+pub fn set_eop_table(table: EopTable) {
+    *EOP_TABLE.write().unwrap() = Some(Arc::new(table));
+}
+
+/// Remove the global EOP table
+pub fn clear_eop_table() {
+    *EOP_TABLE.write().unwrap() = None;
+}
+
+/// The global EOP table, or an error if none was set
+pub fn eop_table() -> super::Result<Arc<EopTable>> {
+    EOP_TABLE
+        .read()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| Error::NotFound("EOP table".to_owned()))
+}
+
+/// Earth orientation parameters for a single instant
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Eop {
+    /// UTC Modified Julian Date
+    pub mjd: f64,
+    /// x coordinate of the pole in arcseconds
+    pub xp: f64,
+    /// y coordinate of the pole in arcseconds
+    pub yp: f64,
+    /// UT1-UTC in seconds
+    pub dut1: f64,
+    /// Celestial pole offset dX from the IAU2000A/IAU2006 model in mas
+    pub dx: f64,
+    /// Celestial pole offset dY from the IAU2000A/IAU2006 model in mas
+    pub dy: f64,
+}
+
+/// A daily series of Earth orientation parameters, as published by the IERS
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EopTable {
+    records: Vec<Eop>,
+}
+
+impl EopTable {
+    /// Parse an IERS Rapid Service `finals2000A` file (e.g. `finals2000A.all` or `finals2000A.daily`)
+    ///
+    /// Bulletin A values (including predictions) are used. Days without polar motion or UT1-UTC are skipped, and
+    /// missing celestial pole offsets are taken as zero.
+    pub fn parse_finals2000a<R: BufRead>(reader: R) -> super::Result<Self> {
+        let mut records = vec![];
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let field = |start: usize, end: usize| -> super::Result<Option<f64>> {
+                match line.get(start..end).map(str::trim) {
+                    None | Some("") => Ok(None),
+                    Some(s) => s
+                        .parse()
+                        .map(Some)
+                        .map_err(|_| Error::Eop(format!("Invalid number {s} in {line}"))),
+                }
+            };
+            let mjd = field(7, 15)?.ok_or_else(|| Error::Eop(format!("Missing MJD in {line}")))?;
+            let (Some(xp), Some(yp), Some(dut1)) = (field(18, 27)?, field(37, 46)?, field(58, 68)?)
+            else {
+                continue;
+            };
+            records.push(Eop {
+                mjd,
+                xp,
+                yp,
+                dut1,
+                dx: field(97, 106)?.unwrap_or(0.0),
+                dy: field(116, 125)?.unwrap_or(0.0),
+            });
+        }
+        Self::new(records)
+    }
+
+    /// Parse an IERS EOP C04 series in the current (`20u24`) format, e.g. `eopc04.1962-now`
+    ///
+    /// Each row is `YR MM DD HH MJD x y UT1-UTC dX dY ...`, with the angles in arcseconds.
+    pub fn parse_c04<R: BufRead>(reader: R) -> super::Result<Self> {
+        let mut records = vec![];
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields = line
+                .split_whitespace()
+                .skip(4)
+                .take(6)
+                .map(|s| s.parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| Error::Eop(format!("Invalid number in {line}")))?;
+            let [mjd, xp, yp, dut1, dx, dy] = fields[..] else {
+                return Err(Error::Eop(format!("Truncated record: {line}")));
+            };
+            records.push(Eop {
+                mjd,
+                xp,
+                yp,
+                dut1,
+                dx: dx * 1000.0,
+                dy: dy * 1000.0,
+            });
+        }
+        Self::new(records)
+    }
+
+    /// Construct a table from records, which must be in increasing order of date
+    pub fn new(records: Vec<Eop>) -> super::Result<Self> {
+        if records.windows(2).any(|pair| pair[1].mjd <= pair[0].mjd) {
+            return Err(Error::Eop("Records are not in order".to_owned()));
+        }
+        Ok(Self { records })
+    }
+
+    /// All of the records in the table
+    pub fn records(&self) -> &[Eop] {
+        &self.records
+    }
+
+    /// The parameters at a UTC Modified Julian Date, linearly interpolated between the daily values
+    ///
+    /// UT1-UTC is interpolated across leap seconds without the one second step, so it is continuous in UTC up to the
+    /// start of the day after the leap second.
+    pub fn get(&self, mjd: f64) -> super::Result<Eop> {
+        let not_found = || Error::NotFound(format!("EOP for MJD {mjd}"));
+        let after = self.records.partition_point(|r| r.mjd <= mjd);
+        if after == 0 {
+            return Err(not_found());
+        }
+        let a = &self.records[after - 1];
+        let Some(b) = self.records.get(after) else {
+            return if mjd == a.mjd {
+                Ok(*a)
+            } else {
+                Err(not_found())
+            };
+        };
+        let t = (mjd - a.mjd) / (b.mjd - a.mjd);
+        let lerp = |x: f64, y: f64| x + t * (y - x);
+        let leap = (b.dut1 - a.dut1).round();
+        Ok(Eop {
+            mjd,
+            xp: lerp(a.xp, b.xp),
+            yp: lerp(a.yp, b.yp),
+            dut1: lerp(a.dut1, b.dut1 - leap),
+            dx: lerp(a.dx, b.dx),
+            dy: lerp(a.dy, b.dy),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::{Timescale, Timespec};
+
+    /// A finals2000A record, with the fields in their columns
+    fn finals(mjd: f64, xp: f64, yp: f64, dut1: f64, dx: f64, dy: f64) -> String {
+        let mut line = vec![b' '; 188];
+        let mut put = |start: usize, text: String| {
+            line[start..start + text.len()].copy_from_slice(text.as_bytes());
+        };
+        put(7, format!("{mjd:8.2}"));
+        put(18, format!("{xp:9.6}"));
+        put(37, format!("{yp:9.6}"));
+        put(58, format!("{dut1:10.7}"));
+        put(97, format!("{dx:9.3}"));
+        put(116, format!("{dy:9.3}"));
+        String::from_utf8(line).unwrap()
+    }
+
+    #[test]
+    fn finals2000a() {
+        let file = [
+            finals(57_753.0, 0.02, 0.3, 0.3, 0.1, -0.2),
+            finals(57_754.0, 0.04, 0.28, -0.6, 0.3, -0.4),
+            // A prediction without polar motion or UT1-UTC
+            format!("{:<188}", "170103 57756.00"),
+        ]
+        .join("\n");
+        let table = EopTable::parse_finals2000a(file.as_bytes()).unwrap();
+        assert_eq!(table.records().len(), 2);
+        let eop = table.get(57_753.5).unwrap();
+        assert!((eop.xp - 0.03).abs() < 1e-12);
+        assert!((eop.dx - 0.2).abs() < 1e-12);
+        // Interpolated across the leap second at the start of MJD 57754
+        assert!((eop.dut1 - 0.35).abs() < 1e-12);
+        assert_eq!(table.get(57_754.0).unwrap().dut1, -0.6);
+        assert!(table.get(57_752.0).is_err());
+        assert!(table.get(57_755.0).is_err());
+    }
+
+    #[test]
+    fn c04() {
+        let file = "# header\n\
+            2023  2 25  0 60000  0.020000  0.300000 -0.0170000  0.000100 -0.000200\n\
+            2023  2 26  0 60001  0.021000  0.301000 -0.0180000  0.000100 -0.000200\n";
+        let table = EopTable::parse_c04(file.as_bytes()).unwrap();
+        assert_eq!(table.records().len(), 2);
+        let eop = table.records()[0];
+        assert_eq!(eop.mjd, 60_000.0);
+        assert!((eop.dx - 0.1).abs() < 1e-12);
+        assert!(EopTable::parse_c04("2023  2 25  0 60000  0.02\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn records_in_order() {
+        let eop = |mjd| Eop {
+            mjd,
+            xp: 0.0,
+            yp: 0.0,
+            dut1: 0.0,
+            dx: 0.0,
+            dy: 0.0,
+        };
+        assert!(EopTable::new(vec![eop(2.0), eop(1.0)]).is_err());
+        assert!(EopTable::new(vec![eop(1.0), eop(2.0)]).is_ok());
+    }
+
+    #[test]
+    fn utc_from_table() {
+        let file = [
+            finals(60_000.0, 0.02, 0.3, -0.017, 0.0, 0.0),
+            finals(60_001.0, 0.02, 0.3, -0.019, 0.0, 0.0),
+        ]
+        .join("\n");
+        let table = EopTable::parse_finals2000a(file.as_bytes()).unwrap();
+        let time = Timespec::from_utc(2_460_000, 0.5, &table).unwrap();
+        // TT - UTC is 32.184 s plus the 37 leap seconds
+        let tt_utc = (time.jd(Timescale::TT) - time.jd(Timescale::UTC)) * 86_400.0;
+        assert!((tt_utc - 69.184).abs() < 1e-3);
+        let ut1_utc = (time.jd(Timescale::UT1) - time.jd(Timescale::UTC)) * 86_400.0;
+        assert!((ut1_utc + 0.017).abs() < 1e-3);
+        assert!(Timespec::from_utc(2_460_010, 0.5, &table).is_err());
+    }
+}
@@ -1,48 +1,395 @@
 //! Module wrapping working with ephemeris
+//!
+//! Ephemeris files are held in an [`EphemerisSet`]. The free functions ([`provide_ephem`], [`add_ephem`],
+//! [`coverage`], and [`clear`]) work on a process-wide default set, which is used unless another set is made active
+//! on the current thread with [`EphemerisSet::scope`]. This allows, e.g., operational and test kernels to be used
+//! side by side in one process.
+//!
+//! Whenever the set in use has no files, the Sun and Earth come from the built-in low-precision calculations, so
+//! clearing one set never affects the threads using another.
 
+use crate::{
+    error::Error,
+    positions::{object_name, Placeable},
+};
 use calceph::{CalcephBin, PositionUnit, TimeUnit};
 use std::{
-    ffi::{c_char, c_double, c_int, c_long, c_short},
+    borrow::Cow,
+    cell::RefCell,
+    ffi::{c_char, c_double, c_int, c_long, c_short, CString},
+    mem::MaybeUninit,
     path::Path,
     slice,
-    sync::{LazyLock, Mutex},
+    sync::{Arc, LazyLock, Mutex},
 };
 use supernovas_sys::{
-    novas_origin, novas_planet, set_ephem_provider, set_planet_provider, set_planet_provider_hp,
+    earth_sun_calc, earth_sun_calc_hp, make_ephem_object, novas_origin, novas_planet, object,
+    set_ephem_provider, set_planet_provider, set_planet_provider_hp, SIZE_OF_OBJ_NAME,
 };
 
 /// 2012 definition of the astronomical unit from the IAU in km
 const AU: f64 = 149_597_870.700;
 
-static EPHEM_PROVIDER: LazyLock<Mutex<Option<CalcephBin>>> = LazyLock::new(|| Mutex::new(None));
+/// The process-wide default ephemeris
+static GLOBAL_EPHEM: LazyLock<EphemerisSet> = LazyLock::new(EphemerisSet::new);
+
+thread_local! {
+    /// The ephemeris made active on this thread by [`EphemerisSet::scope`], if any
+    static ACTIVE_EPHEM: RefCell<Option<EphemerisSet>> = const { RefCell::new(None) };
+}
+
+/// A loaded ephemeris file, along with the time spans of its position segments
+struct Kernel {
+    bin: CalcephBin,
+    /// A second handle to the file for the parts of the CALCEPH API the calceph crate doesn't expose
+    raw: *mut CalcephHandle,
+    /// The NAIF ID of the target and the first and last TDB Julian dates of each position segment
+    segments: Vec<(i32, f64, f64)>,
+}
+
+// Safety: The raw handle is owned by the kernel and only used while holding the lock of its set
+unsafe impl Send for Kernel {}
+
+impl Drop for Kernel {
+    fn drop(&mut self) {
+        // Safety: The handle was opened on load, is never null, and is not used after this
+        unsafe { calceph_close(self.raw) };
+    }
+}
+
+/// CALCEPH unit flags for AU, days, and NAIF IDs
+const CALCEPH_UNIT_AU_DAY_NAIF: c_int = 1 | 4 | 32;
+
+/// Opaque CALCEPH ephemeris handle
+#[repr(C)]
+struct CalcephHandle {
+    _private: [u8; 0],
+}
+
+// The record introspection and higher-order functions of the CALCEPH C library, which is linked by the calceph crate
+extern "C" {
+    fn calceph_open(filename: *const c_char) -> *mut CalcephHandle;
+    fn calceph_getpositionrecordcount(eph: *mut CalcephHandle) -> c_int;
+    fn calceph_getpositionrecordindex(
+        eph: *mut CalcephHandle,
+        index: c_int,
+        target: *mut c_int,
+        center: *mut c_int,
+        firsttime: *mut c_double,
+        lasttime: *mut c_double,
+        frame: *mut c_int,
+    ) -> c_int;
+    fn calceph_compute_order(
+        eph: *mut CalcephHandle,
+        jd0: c_double,
+        time: c_double,
+        target: c_int,
+        center: c_int,
+        unit: c_int,
+        order: c_int,
+        pvaj: *mut c_double,
+    ) -> c_int;
+    fn calceph_close(eph: *mut CalcephHandle);
+}
+
+impl Kernel {
+    fn load(file: &Path) -> super::Result<Self> {
+        let bin = CalcephBin::new(file)?;
+        let path = file
+            .to_str()
+            .and_then(|p| CString::new(p).ok())
+            .ok_or(Error::InvalidString)?;
+        let mut segments = vec![];
+        // Safety: The path is a valid C string and the handle is checked for null before use
+        let raw = unsafe {
+            let eph = calceph_open(path.as_ptr());
+            if eph.is_null() {
+                return Err(Error::InvalidValue("file"));
+            }
+            for index in 1..=calceph_getpositionrecordcount(eph) {
+                let (mut target, mut center, mut first, mut last, mut frame) = (0, 0, 0.0, 0.0, 0);
+                let found = calceph_getpositionrecordindex(
+                    eph,
+                    index,
+                    &mut target,
+                    &mut center,
+                    &mut first,
+                    &mut last,
+                    &mut frame,
+                );
+                if found != 0 {
+                    segments.push((target, first, last));
+                }
+            }
+            eph
+        };
+        Ok(Self { bin, raw, segments })
+    }
+}
+
+/// A set of loaded ephemeris files, searched from the most recently loaded
+///
+/// Sets are cheap to clone, and clones share the same files.
+#[derive(Clone, Default)]
+pub struct EphemerisSet {
+    kernels: Arc<Mutex<Vec<Kernel>>>,
+}
+
+impl EphemerisSet {
+    /// Construct a new, empty set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load an ephemeris file into the set, alongside those already loaded
+    ///
+    /// Bodies are looked up in the most recently loaded file that contains them.
+    pub fn load<P: AsRef<Path>>(&self, file: P) -> super::Result<()> {
+        let kernel = Kernel::load(file.as_ref())?;
+        self.kernels.lock().unwrap().push(kernel);
+        Ok(())
+    }
+
+    /// Unload all of the files in the set
+    pub fn clear(&self) {
+        self.kernels.lock().unwrap().clear();
+    }
+
+    /// The span of TDB Julian dates `(start, end)` covered by the files in this set for a body, or `None` if none of
+    /// them contain it
+    ///
+    /// Gaps between segments (or files) within the span are not reported.
+    pub fn coverage(&self, naif_id: i64) -> Option<(f64, f64)> {
+        span(&self.kernels.lock().unwrap(), naif_id)
+    }
+
+    /// Run `f` with this set providing all ephemeris lookups on the current thread, instead of the default set
+    ///
+    /// Frames must be both constructed and used within the scope for their positions to come from this set. Scopes
+    /// can be nested, and the previous set is restored afterwards.
+    ///
+    /// The scope only covers the current thread. Threads spawned within it (e.g. rayon workers) use the default set
+    /// unless they enter the scope themselves, e.g. with the set from [`EphemerisSet::active`]; the
+    /// [`batch`](crate::batch) functions do this already.
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        /// Restores the previously active set, even if `f` panics
+        struct Restore(Option<EphemerisSet>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                ACTIVE_EPHEM.with(|active| *active.borrow_mut() = self.0.take());
+            }
+        }
+        attach_providers();
+        let _restore = Restore(ACTIVE_EPHEM.with(|active| active.replace(Some(self.clone()))));
+        f()
+    }
+
+    /// The set made active on the current thread by [`EphemerisSet::scope`], if any
+    pub fn active() -> Option<Self> {
+        ACTIVE_EPHEM.with(|active| active.borrow().clone())
+    }
+
+    /// Whether the set has no files
+    fn is_empty(&self) -> bool {
+        self.kernels.lock().unwrap().is_empty()
+    }
+
+    fn lookup(
+        &self,
+        id: i32,
+        jd_tdb_high: f64,
+        jd_tdb_low: f64,
+        center: i32,
+    ) -> super::Result<[f64; 6]> {
+        let mut kernels = self.kernels.lock().unwrap();
+        // Use the first file (from the most recently loaded) that has the body at this time
+        let mut failure = None;
+        for kernel in kernels.iter_mut().rev() {
+            match kernel.bin.compute_position_units_naif(
+                jd_tdb_high,
+                jd_tdb_low,
+                id,
+                center,
+                PositionUnit::Kilometer,
+                TimeUnit::Day,
+            ) {
+                Ok(mut pv) => {
+                    // Convert result to AU and AU/s
+                    pv.iter_mut().for_each(|i| *i /= AU);
+                    return Ok(pv);
+                }
+                Err(e) => failure = Some(e),
+            }
+        }
+        Err(lookup_error(
+            &kernels,
+            id.into(),
+            jd_tdb_high + jd_tdb_low,
+            failure.map(Error::from),
+        ))
+    }
+
+    /// The position, velocity, acceleration, and jerk of a body relative to a center (both NAIF IDs, e.g. 0 for the
+    /// solar system barycenter or 399 for the Earth) at a split TDB Julian date
+    pub fn body_state(
+        &self,
+        naif_id: i64,
+        center: i64,
+        jd_tdb_high: f64,
+        jd_tdb_low: f64,
+    ) -> super::Result<BodyState> {
+        let target = i32::try_from(naif_id).map_err(|_| Error::InvalidValue("naif_id"))?;
+        let center = i32::try_from(center).map_err(|_| Error::InvalidValue("center"))?;
+        let kernels = self.kernels.lock().unwrap();
+        let mut failure = None;
+        for kernel in kernels.iter().rev() {
+            let mut pvaj = [0.0; 12];
+            // Safety: The handle is open while the kernel exists, and the output holds all four derivatives
+            let ret = unsafe {
+                calceph_compute_order(
+                    kernel.raw,
+                    jd_tdb_high,
+                    jd_tdb_low,
+                    target,
+                    center,
+                    CALCEPH_UNIT_AU_DAY_NAIF,
+                    3,
+                    pvaj.as_mut_ptr(),
+                )
+            };
+            if ret != 0 {
+                let part = |i: usize| [pvaj[3 * i], pvaj[3 * i + 1], pvaj[3 * i + 2]];
+                return Ok(BodyState {
+                    pos: part(0),
+                    vel: part(1),
+                    acc: part(2),
+                    jerk: part(3),
+                });
+            }
+            // CALCEPH only reports failure (as 0), so name the lookup that failed instead
+            failure = Some(Error::NotFound(format!(
+                "NAIF ID {naif_id} relative to {center} at JD {} TDB",
+                jd_tdb_high + jd_tdb_low
+            )));
+        }
+        Err(lookup_error(
+            &kernels,
+            naif_id,
+            jd_tdb_high + jd_tdb_low,
+            failure,
+        ))
+    }
+}
+
+/// The state of a body from the ephemeris, with its higher-order derivatives
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BodyState {
+    /// Position in AU
+    pub pos: [f64; 3],
+    /// Velocity in AU/day
+    pub vel: [f64; 3],
+    /// Acceleration in AU/day²
+    pub acc: [f64; 3],
+    /// Jerk in AU/day³
+    pub jerk: [f64; 3],
+}
+
+/// Explain why an ephemeris lookup failed, if it's a matter of coverage, or pass on the underlying failure
+fn lookup_error(kernels: &[Kernel], naif_id: i64, jd_tdb: f64, failure: Option<Error>) -> Error {
+    let Some(failure) = failure else {
+        return Error::EphemNotLoaded;
+    };
+    match span(kernels, naif_id) {
+        None => Error::EphemMissingBody(naif_id),
+        Some((start, end)) if jd_tdb < start || jd_tdb > end => {
+            Error::EphemNotCovered { naif_id, jd_tdb }
+        }
+        Some(_) => failure,
+    }
+}
+
+/// The span of TDB Julian dates covered by any of the kernels for a body
+fn span(kernels: &[Kernel], naif_id: i64) -> Option<(f64, f64)> {
+    kernels
+        .iter()
+        .flat_map(|k| &k.segments)
+        .filter(|(target, _, _)| i64::from(*target) == naif_id)
+        .map(|&(_, first, last)| (first, last))
+        .reduce(|a, b| (a.0.min(b.0), a.1.max(b.1)))
+}
+
+/// The position, velocity, acceleration, and jerk of a body from the default ephemeris
+///
+/// See [`EphemerisSet::body_state`].
+pub fn body_state(
+    naif_id: i64,
+    center: i64,
+    jd_tdb_high: f64,
+    jd_tdb_low: f64,
+) -> super::Result<BodyState> {
+    GLOBAL_EPHEM.body_state(naif_id, center, jd_tdb_high, jd_tdb_low)
+}
+
+/// The span of TDB Julian dates `(start, end)` covered by the default ephemeris for a body, or `None` if none of
+/// its files contain it
+///
+/// See [`EphemerisSet::coverage`].
+pub fn coverage(naif_id: i64) -> Option<(f64, f64)> {
+    GLOBAL_EPHEM.coverage(naif_id)
+}
+
+/// Natural satellites of the planets and their NAIF IDs
+const NATURAL_SATELLITES: [(&str, i64); 24] = [
+    ("Phobos", 401),
+    ("Deimos", 402),
+    ("Io", 501),
+    ("Europa", 502),
+    ("Ganymede", 503),
+    ("Callisto", 504),
+    ("Amalthea", 505),
+    ("Mimas", 601),
+    ("Enceladus", 602),
+    ("Tethys", 603),
+    ("Dione", 604),
+    ("Rhea", 605),
+    ("Titan", 606),
+    ("Hyperion", 607),
+    ("Iapetus", 608),
+    ("Phoebe", 609),
+    ("Ariel", 701),
+    ("Umbriel", 702),
+    ("Titania", 703),
+    ("Oberon", 704),
+    ("Miranda", 705),
+    ("Triton", 801),
+    ("Nereid", 802),
+    ("Charon", 901),
+];
 
+/// Look up the position and velocity (AU and AU/day) of a body in the active ephemeris set
 pub fn naif_ephem_lookup(
     id: i32,
     jd_tdb_high: f64,
     jd_tdb_low: f64,
     origin: novas_origin,
 ) -> super::Result<[f64; 6]> {
-    // Grab the global provider
-    let mut ceph = EPHEM_PROVIDER.lock().unwrap();
     let center = match origin {
         novas_origin::NOVAS_BARYCENTER => 0,   // NAIFID_SSB
         novas_origin::NOVAS_HELIOCENTER => 10, // NAIFID_SUN
         _ => unreachable!(),
     };
-    let mut pv = match &mut *ceph {
-        None => return Err(crate::error::Error::EphemNotLoaded),
-        Some(c) => c.compute_position_units_naif(
-            jd_tdb_high,
-            jd_tdb_low,
-            id,
-            center,
-            PositionUnit::Kilometer,
-            TimeUnit::Day,
-        )?,
-    };
-    // Convert result to AU and AU/s
-    pv.iter_mut().for_each(|i| *i /= AU);
-    Ok(pv)
+    match EphemerisSet::active() {
+        Some(set) => set.lookup(id, jd_tdb_high, jd_tdb_low, center),
+        None => GLOBAL_EPHEM.lookup(id, jd_tdb_high, jd_tdb_low, center),
+    }
+}
+
+/// Whether the ephemeris set in use on the current thread has no files, so the built-in calculations should be used
+fn using_builtin() -> bool {
+    match EphemerisSet::active() {
+        Some(set) => set.is_empty(),
+        None => GLOBAL_EPHEM.is_empty(),
+    }
 }
 
 unsafe extern "C" fn ceph_ephem_provider(
@@ -108,6 +455,9 @@ unsafe extern "C" fn ceph_planet_provider_hp(
     if jd_tdb.is_null() || pos.is_null() || vel.is_null() {
         return 3;
     }
+    if using_builtin() {
+        return earth_sun_calc_hp(jd_tdb, body, origin, pos, vel);
+    }
     let jd_tdb = slice::from_raw_parts(jd_tdb, 2);
     // Perfom the computation
     match naif_ephem_lookup(novas_planet_naif(body), jd_tdb[0], jd_tdb[1], origin) {
@@ -132,6 +482,9 @@ unsafe extern "C" fn ceph_planet_provider(
     if pos.is_null() || vel.is_null() {
         return 3;
     }
+    if using_builtin() {
+        return earth_sun_calc(jd_tdb, body, origin, pos, vel);
+    }
     // Perfom the computation
     match naif_ephem_lookup(novas_planet_naif(body), jd_tdb, 0.0, origin) {
         Err(_) => 3,
@@ -146,17 +499,114 @@ unsafe extern "C" fn ceph_planet_provider(
 }
 
 /// Provide high-precision ephemeris for the major planets, overriding the default behavior
+///
+/// This replaces any previously loaded ephemeris files.
 pub fn provide_ephem<P: AsRef<Path>>(file: P) -> super::Result<()> {
     // Try to load the file
-    let ceph = CalcephBin::new(file)?;
+    let kernel = Kernel::load(file.as_ref())?;
     // Update the gloabl provider
-    let mut provider = EPHEM_PROVIDER.lock().unwrap();
-    *provider = Some(ceph);
-    // Attach the provider to SuperNOVAS
+    *GLOBAL_EPHEM.kernels.lock().unwrap() = vec![kernel];
+    attach_providers();
+    Ok(())
+}
+
+/// Load an additional ephemeris file alongside those already loaded (e.g. a planetary satellite kernel such as
+/// `jup365.bsp` on top of `de440.bsp`)
+///
+/// Bodies are looked up in the most recently loaded file that contains them.
+pub fn add_ephem<P: AsRef<Path>>(file: P) -> super::Result<()> {
+    GLOBAL_EPHEM.load(file)?;
+    attach_providers();
+    Ok(())
+}
+
+/// Unload all files of the default ephemeris, restoring the default (low-precision Earth and Sun only) planet
+/// calculations
+///
+/// New files can then be loaded with [`provide_ephem`], e.g. to swap in an updated spacecraft kernel in a
+/// long-running process. The providers stay attached, so threads within an [`EphemerisSet::scope`] keep using
+/// their own set; lookups in the (now empty) default set fall back to the built-in calculations.
+pub fn clear() {
+    // Lookups hold the lock of the set, so the files are never dropped while in use
+    GLOBAL_EPHEM.clear();
+}
+
+/// Attach the calceph providers to SuperNOVAS
+fn attach_providers() {
+    // Safety: The providers only read the active or default ephemeris set, which handles its own locking
     unsafe {
         set_ephem_provider(Some(ceph_ephem_provider));
         set_planet_provider(Some(ceph_planet_provider));
         set_planet_provider_hp(Some(ceph_planet_provider_hp));
     }
-    Ok(())
+}
+
+/// A solar system body or spacecraft whose position is looked up by NAIF ID in the loaded ephemeris
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EphemObject {
+    name: String,
+    naif_id: i64,
+}
+
+impl EphemObject {
+    /// Construct a new ephemeris object
+    ///
+    /// - name: The object name
+    /// - naif_id: The NAIF ID of the object in the loaded ephemeris (e.g. -82 for Cassini)
+    pub fn new(name: &str, naif_id: i64) -> super::Result<Self> {
+        if name.len() as u32 > SIZE_OF_OBJ_NAME {
+            return Err(Error::InvalidString);
+        }
+        Ok(Self {
+            name: name.to_string(),
+            naif_id,
+        })
+    }
+
+    /// Construct a natural satellite of a planet by name (e.g. "Io" or "Titan"), case-insensitively
+    ///
+    /// Their positions need the corresponding satellite ephemeris (e.g. `jup365.bsp` or `sat441.bsp`) to be loaded
+    /// with [`add_ephem`].
+    pub fn satellite(name: &str) -> super::Result<Self> {
+        let (name, naif_id) = NATURAL_SATELLITES
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| Error::NotFound(name.to_string()))?;
+        Self::new(name, *naif_id)
+    }
+
+    /// Check that the loaded ephemeris files cover this object at the given TDB Julian date
+    ///
+    /// This returns [`Error::EphemMissingBody`] if none of the files contain the object, or
+    /// [`Error::EphemNotCovered`] if they don't at this time.
+    pub fn check_coverage(&self, jd_tdb: f64) -> super::Result<()> {
+        let id = i32::try_from(self.naif_id).map_err(|_| Error::InvalidValue("naif_id"))?;
+        naif_ephem_lookup(id, jd_tdb, 0.0, novas_origin::NOVAS_BARYCENTER).map(|_| ())
+    }
+
+    /// The object name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The NAIF ID of the object
+    pub fn naif_id(&self) -> i64 {
+        self.naif_id
+    }
+}
+
+impl Placeable for EphemObject {
+    fn object(&self) -> super::Result<Cow<'_, object>> {
+        let name = object_name(&self.name)?;
+        let mut obj = MaybeUninit::uninit();
+        // Safety: The name is null-terminated within the NOVAS name size and the object will not be null
+        let obj = unsafe {
+            let ret = make_ephem_object(name.as_ptr(), self.naif_id, obj.as_mut_ptr());
+            if ret != 0 {
+                return Err(Error::LowerLevel(ret));
+            }
+            obj.assume_init()
+        };
+        Ok(Cow::Owned(obj))
+    }
 }
@@ -7,11 +7,45 @@ pub enum Error {
     InvalidString,
     #[error("The underlying C library errored: {0}")]
     LowerLevel(i32),
+    #[error("Invalid value for parameter {0}")]
+    InvalidValue(&'static str),
+    #[error("Value {value} of parameter {param} is out of range")]
+    OutOfRange { param: &'static str, value: f64 },
+    #[error("No object found matching {0}")]
+    NotFound(String),
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid table: {0}")]
+    Table(String),
+    #[error("Target is unreachable: {0}")]
+    Unreachable(crate::telescope::LimitViolation),
 
-    #[cfg(feature = "hifitime")]
+    #[cfg(any(
+        feature = "simbad",
+        feature = "horizons",
+        feature = "mpc",
+        feature = "tokio"
+    ))]
     #[error("Error from the web requests")]
     Reqwest(#[from] reqwest::Error),
 
+    #[cfg(feature = "horizons")]
+    #[error("Unexpected response from JPL Horizons: {0}")]
+    Horizons(String),
+
+    #[error("Invalid Minor Planet Center record: {0}")]
+    Mpc(String),
+
+    #[error("Invalid Earth orientation record: {0}")]
+    Eop(String),
+
+    #[error("Self-test failed: {0}")]
+    Validation(String),
+
+    #[cfg(feature = "sgp4")]
+    #[error("Error from SGP4: {0}")]
+    Sgp4(String),
+
     #[cfg(feature = "calceph")]
     #[error("Error from CALCEPH")]
     Calceph(#[from] calceph::Error),
@@ -19,4 +53,12 @@ pub enum Error {
     #[cfg(feature = "calceph")]
     #[error("CALCEPH provider not loaded")]
     EphemNotLoaded,
+
+    #[cfg(feature = "calceph")]
+    #[error("No loaded ephemeris contains NAIF ID {0}")]
+    EphemMissingBody(i64),
+
+    #[cfg(feature = "calceph")]
+    #[error("No loaded ephemeris covers NAIF ID {naif_id} at JD {jd_tdb} (TDB)")]
+    EphemNotCovered { naif_id: i64, jd_tdb: f64 },
 }
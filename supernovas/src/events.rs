@@ -0,0 +1,307 @@
+//! Rise, set, and twilight event times
+
+use crate::{
+    error::Error,
+    planets::Planet,
+    positions::{Frame, Observer, Placeable, ReferenceSystem, SkyPosition},
+    refraction::RefractionModel,
+    time::{IntoTimespec, Timespec},
+    Accuracy,
+};
+
+/// Coarse sampling interval in seconds when bracketing events
+const SEARCH_STEP: f64 = 600.0;
+
+/// Time resolution in seconds of the located events
+const TOLERANCE: f64 = 1.0;
+
+/// The inverse golden ratio, for golden-section searches
+const INV_PHI: f64 = 0.618_033_988_749_894_8;
+
+/// Length of a day in seconds
+const DAY: f64 = 86_400.0;
+
+/// A time at which a source crosses an elevation threshold
+#[derive(Debug, Clone)]
+pub struct Crossing {
+    /// The time of the crossing
+    pub time: Timespec,
+    /// Whether the source is rising (rather than setting) through the threshold
+    pub rising: bool,
+}
+
+/// The rise and set times of a source within a day, if it crosses its threshold at all
+#[derive(Debug, Clone, Default)]
+pub struct RiseSet {
+    /// The first time the source rises
+    pub rise: Option<Timespec>,
+    /// The first time the source sets
+    pub set: Option<Timespec>,
+}
+
+impl RiseSet {
+    fn from_crossings(crossings: Vec<Crossing>) -> Self {
+        let mut events = Self::default();
+        for crossing in crossings {
+            let slot = if crossing.rising {
+                &mut events.rise
+            } else {
+                &mut events.set
+            };
+            slot.get_or_insert(crossing.time);
+        }
+        events
+    }
+}
+
+/// The solar altitudes defining sunrise/sunset and the twilights
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Twilight {
+    /// Sunrise and sunset, where the upper limb of the Sun touches the (refracted) horizon
+    Sunrise,
+    /// The Sun 6° below the horizon
+    Civil,
+    /// The Sun 12° below the horizon
+    Nautical,
+    /// The Sun 18° below the horizon
+    Astronomical,
+}
+
+impl Twilight {
+    /// The unrefracted altitude of the center of the Sun in degrees defining this event
+    pub fn altitude(&self) -> f64 {
+        match self {
+            // 34' of refraction plus 16' of semi-diameter
+            Twilight::Sunrise => -50.0 / 60.0,
+            Twilight::Civil => -6.0,
+            Twilight::Nautical => -12.0,
+            Twilight::Astronomical => -18.0,
+        }
+    }
+}
+
+/// The local coordinates (az,el in degrees) of a source at the given time in a reduced-accuracy frame
+pub(crate) fn az_el(
+    target: &impl Placeable,
+    observer: &Observer,
+    time: &Timespec,
+    refraction: Option<&dyn RefractionModel>,
+) -> super::Result<(f64, f64)> {
+    let frame = Frame::new(Accuracy::Reduced, observer, time, 0.0, 0.0)?;
+    frame.apparent_local_coordinates(ReferenceSystem::CIRS, target, refraction)
+}
+
+/// Finds every time in `[start, stop]` at which `height` changes sign
+///
+/// The interval is sampled every [`SEARCH_STEP`] and each sign change is refined by bisection, so events closer
+/// together than the step may be missed.
+pub(crate) fn sign_changes(
+    start: &Timespec,
+    stop: &Timespec,
+    height: impl Fn(&Timespec) -> super::Result<f64>,
+) -> super::Result<Vec<Crossing>> {
+    let span = stop.diff(start);
+    let steps = (span / SEARCH_STEP).ceil().max(1.0) as usize;
+    let mut crossings = vec![];
+    let mut lo = *start;
+    let mut h_lo = height(&lo)?;
+    for i in 1..=steps {
+        let hi = start.offset((i as f64 * SEARCH_STEP).min(span));
+        let h_hi = height(&hi)?;
+        if (h_lo < 0.0) != (h_hi < 0.0) {
+            let rising = h_hi >= 0.0;
+            // Bisect down to the tolerance, keeping the sign change bracketed
+            let (mut a, mut b) = (lo, hi);
+            while b.diff(&a) > TOLERANCE {
+                let mid = a.offset(b.diff(&a) / 2.0);
+                if (height(&mid)? >= 0.0) == rising {
+                    b = mid;
+                } else {
+                    a = mid;
+                }
+            }
+            crossings.push(Crossing { time: b, rising });
+        }
+        lo = hi;
+        h_lo = h_hi;
+    }
+    Ok(crossings)
+}
+
+/// Finds every time in `[start, stop]` a source crosses the observer's horizon
+///
+/// The horizon is the observer's horizon profile (or 0° without one) raised by `altitude` degrees.
+pub fn crossings(
+    target: &impl Placeable,
+    observer: &Observer,
+    start: impl IntoTimespec,
+    stop: impl IntoTimespec,
+    altitude: f64,
+    refraction: Option<&dyn RefractionModel>,
+) -> super::Result<Vec<Crossing>> {
+    let (start, stop) = (start.into_timespec()?, stop.into_timespec()?);
+    sign_changes(&start, &stop, |time| {
+        let (az, el) = az_el(target, observer, time, refraction)?;
+        Ok(el - observer.min_elevation(az) - altitude)
+    })
+}
+
+/// The rise and set times of a source over the observer's horizon in the day following `date`
+pub fn rise_set(
+    target: &impl Placeable,
+    observer: &Observer,
+    date: impl IntoTimespec,
+    refraction: Option<&dyn RefractionModel>,
+) -> super::Result<RiseSet> {
+    let date = date.into_timespec()?;
+    let stop = date.offset(DAY);
+    let found = crossings(target, observer, date, stop, 0.0, refraction)?;
+    Ok(RiseSet::from_crossings(found))
+}
+
+/// The times in the day following `date` at which the Sun crosses the altitude of the given twilight
+///
+/// The Sun rising through the altitude marks the morning event (e.g. sunrise or the start of astronomical
+/// twilight) and setting through it the evening event. These are against the astronomical horizon, ignoring any
+/// horizon profile of the observer.
+pub fn twilight(
+    observer: &Observer,
+    date: impl IntoTimespec,
+    kind: Twilight,
+) -> super::Result<RiseSet> {
+    let date = date.into_timespec()?;
+    let stop = date.offset(DAY);
+    let altitude = kind.altitude();
+    let found = sign_changes(&date, &stop, |time| {
+        let (_, el) = az_el(&Planet::Sun, observer, time, None)?;
+        Ok(el - altitude)
+    })?;
+    Ok(RiseSet::from_crossings(found))
+}
+
+/// A time of minimum angular separation between two sources
+#[derive(Debug, Clone)]
+pub struct Appulse {
+    /// The time of closest approach
+    pub time: Timespec,
+    /// The angular separation at closest approach in degrees
+    pub separation: f64,
+}
+
+/// Finds every local minimum of `f` in `[start, stop]`
+///
+/// The interval is sampled every `step` seconds and each bracketed minimum is refined with a golden-section search,
+/// so minima closer together than the step may be missed.
+pub(crate) fn local_minima(
+    start: &Timespec,
+    stop: &Timespec,
+    step: f64,
+    f: impl Fn(&Timespec) -> super::Result<f64>,
+) -> super::Result<Vec<(Timespec, f64)>> {
+    let span = stop.diff(start);
+    let steps = (span / step).ceil().max(2.0) as usize;
+    let step = span / steps as f64;
+    let samples = (0..=steps)
+        .map(|i| f(&start.offset(i as f64 * step)))
+        .collect::<super::Result<Vec<_>>>()?;
+    let mut minima = vec![];
+    for i in 1..steps {
+        if samples[i - 1] > samples[i] && samples[i] <= samples[i + 1] {
+            // Golden-section search over the bracketing samples
+            let (mut a, mut b) = ((i - 1) as f64 * step, (i + 1) as f64 * step);
+            let mut c = b - INV_PHI * (b - a);
+            let mut d = a + INV_PHI * (b - a);
+            let mut f_c = f(&start.offset(c))?;
+            let mut f_d = f(&start.offset(d))?;
+            while b - a > TOLERANCE {
+                if f_c < f_d {
+                    (b, d, f_d) = (d, c, f_c);
+                    c = b - INV_PHI * (b - a);
+                    f_c = f(&start.offset(c))?;
+                } else {
+                    (a, c, f_c) = (c, d, f_d);
+                    d = a + INV_PHI * (b - a);
+                    f_d = f(&start.offset(d))?;
+                }
+            }
+            let time = start.offset((a + b) / 2.0);
+            let value = f(&time)?;
+            minima.push((time, value));
+        }
+    }
+    Ok(minima)
+}
+
+/// Finds the conjunctions (times of minimum apparent angular separation) of two sources in `[start, stop]`
+///
+/// - step: Sampling interval in seconds, which must be shorter than the time between successive minima (a day is
+///   usually fine for planets, an hour for the Moon)
+pub fn conjunctions(
+    a: &impl Placeable,
+    b: &impl Placeable,
+    observer: &Observer,
+    start: impl IntoTimespec,
+    stop: impl IntoTimespec,
+    step: f64,
+) -> super::Result<Vec<Appulse>> {
+    let (start, stop) = (start.into_timespec()?, stop.into_timespec()?);
+    let minima = local_minima(&start, &stop, step, |time| {
+        let frame = Frame::new(Accuracy::Reduced, observer, time, 0.0, 0.0)?;
+        let pos_a = SkyPosition::try_from_frame(a, &frame, ReferenceSystem::TOD)?;
+        let pos_b = SkyPosition::try_from_frame(b, &frame, ReferenceSystem::TOD)?;
+        Ok(pos_a.separation(&pos_b))
+    })?;
+    Ok(minima
+        .into_iter()
+        .map(|(time, separation)| Appulse { time, separation })
+        .collect())
+}
+
+/// Finds the upper culmination (transit) of a source in the day following `date`
+///
+/// Returns the time of the transit and the maximum elevation in degrees.
+pub fn culmination(
+    target: &impl Placeable,
+    observer: &Observer,
+    date: impl IntoTimespec,
+    refraction: Option<&dyn RefractionModel>,
+) -> super::Result<(Timespec, f64)> {
+    let date = date.into_timespec()?;
+    // Pad the search by a step either side, since only maxima bracketed by samples are found, and a transit near
+    // either end of the day would otherwise be missed
+    let maxima = local_minima(
+        &date.offset(-SEARCH_STEP),
+        &date.offset(DAY + SEARCH_STEP),
+        SEARCH_STEP,
+        |time| Ok(-az_el(target, observer, time, refraction)?.1),
+    )?;
+    // A solar day is longer than a sidereal day, so there can be two transits; keep the higher one
+    maxima
+        .into_iter()
+        .filter(|(time, _)| (0.0..=DAY).contains(&time.diff(&date)))
+        .map(|(time, el)| (time, -el))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .ok_or_else(|| Error::NotFound("culmination".to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::positions::{CatalogEntry, Weather};
+
+    #[test]
+    fn culmination_near_the_ends_of_the_day() {
+        let observer = Observer::new_on_surface(37.0, 0.0, 0.0, Weather::default());
+        let star = CatalogEntry::new("star", "", 0, 18.7, 20.0, 0.0, 0.0, 0.0, 0.0).unwrap();
+        let (transit, el) =
+            culmination(&star, &observer, Timespec::J2000.offset(-DAY / 2.0), None).unwrap();
+        assert!((el - 73.0).abs() < 0.1);
+
+        // Transits within a search step of the start and of the end of the day
+        for start in [transit.offset(-100.0), transit.offset(100.0 - DAY)] {
+            let (found, _) = culmination(&star, &observer, start, None).unwrap();
+            let sidereal_days = found.diff(&transit) / 86_164.1;
+            assert!((sidereal_days - sidereal_days.round()).abs() * 86_164.1 < 2.0);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! Asynchronous (tokio) downloads of the data files used by the library
+//!
+//! These let services refresh their Earth orientation parameters and ephemeris kernels in the background, without
+//! blocking a control thread. They use the async [`reqwest::Client`] and the same [`RetryPolicy`] and
+//! [`RateLimiter`] as the blocking queries.
+//!
+//! Leap seconds come from the table built into [`time`](crate::time), so there is nothing to fetch for them; the
+//! IERS announces new ones at least six months ahead, which leaves time for a release.
+
+use crate::{
+    eop::{set_eop_table, EopTable},
+    net::{RateLimiter, RetryPolicy},
+};
+use reqwest::Client;
+use std::path::Path;
+use tokio::{fs, io::AsyncWriteExt};
+
+/// The IERS Rapid Service finals2000A series (1973 to a year of predictions)
+pub const FINALS2000A_URL: &str = "https://datacenter.iers.org/data/9/finals2000A.all";
+
+/// The JPL DE440 planetary ephemeris (1550 to 2650)
+pub const DE440_URL: &str =
+    "https://naif.jpl.nasa.gov/pub/naif/generic_kernels/spk/planets/de440.bsp";
+
+/// Download and parse a finals2000A EOP series, e.g. from [`FINALS2000A_URL`]
+pub async fn fetch_eop(
+    client: &Client,
+    retry: &RetryPolicy,
+    limiter: Option<&RateLimiter>,
+    url: &str,
+) -> super::Result<EopTable> {
+    let resp = retry.send_async(limiter, || client.get(url)).await?;
+    let text = resp.text().await?;
+    EopTable::parse_finals2000a(text.as_bytes())
+}
+
+/// Download the current finals2000A series from the IERS and make it the global EOP table (see
+/// [`set_eop_table`])
+///
+/// The global table is only replaced once the new one has been downloaded and parsed, so calculations on other
+/// threads keep using the old table until then, and keep it if the refresh fails.
+pub async fn refresh_eop_table(client: &Client, retry: &RetryPolicy) -> super::Result<()> {
+    set_eop_table(fetch_eop(client, retry, None, FINALS2000A_URL).await?);
+    Ok(())
+}
+
+/// Download a file (e.g. an ephemeris kernel from [`DE440_URL`]) to `path`, streaming it to disk
+///
+/// The file is written next to `path` under a temporary name and moved into place once complete, so readers never
+/// see a partial file and an existing copy is kept if the download fails. Retries only cover the start of the
+/// download; an interruption partway through fails.
+pub async fn fetch_file(
+    client: &Client,
+    retry: &RetryPolicy,
+    url: &str,
+    path: impl AsRef<Path>,
+) -> super::Result<()> {
+    let path = path.as_ref();
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".part");
+    let mut resp = retry.send_async(None, || client.get(url)).await?;
+    let mut file = fs::File::create(&partial).await?;
+    let written = async {
+        while let Some(chunk) = resp.chunk().await? {
+            file.write_all(&chunk).await?;
+        }
+        file.sync_all().await?;
+        Ok::<_, crate::error::Error>(())
+    }
+    .await;
+    if let Err(e) = written {
+        let _ = fs::remove_file(&partial).await;
+        return Err(e);
+    }
+    fs::rename(&partial, path).await?;
+    Ok(())
+}
@@ -0,0 +1,73 @@
+//! Conversions between geodetic and Earth-centered, Earth-fixed (ECEF) coordinates
+
+/// Reference ellipsoids for geodetic coordinates
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Ellipsoid {
+    /// The World Geodetic System 1984 ellipsoid (used by GPS)
+    #[default]
+    WGS84,
+    /// The Geodetic Reference System 1980 ellipsoid (used by ITRF)
+    GRS80,
+}
+
+impl Ellipsoid {
+    /// The equatorial radius in meters
+    pub fn semi_major_axis(&self) -> f64 {
+        6_378_137.0
+    }
+
+    /// The flattening
+    pub fn flattening(&self) -> f64 {
+        match self {
+            Ellipsoid::WGS84 => 1.0 / 298.257_223_563,
+            Ellipsoid::GRS80 => 1.0 / 298.257_222_101,
+        }
+    }
+
+    /// The square of the first eccentricity
+    fn e2(&self) -> f64 {
+        let f = self.flattening();
+        f * (2.0 - f)
+    }
+}
+
+/// Converts geodetic coordinates to ECEF XYZ in meters
+///
+/// - lat: Geodetic latitude in degrees; north positive
+/// - lon: Geodetic longitude in degrees; east positive
+/// - height: Height above the ellipsoid in meters
+pub fn geodetic_to_ecef(lat: f64, lon: f64, height: f64, ellipsoid: Ellipsoid) -> [f64; 3] {
+    let a = ellipsoid.semi_major_axis();
+    let e2 = ellipsoid.e2();
+    let (lat, lon) = (lat.to_radians(), lon.to_radians());
+    // Radius of curvature in the prime vertical
+    let n = a / (1.0 - e2 * lat.sin().powi(2)).sqrt();
+    [
+        (n + height) * lat.cos() * lon.cos(),
+        (n + height) * lat.cos() * lon.sin(),
+        (n * (1.0 - e2) + height) * lat.sin(),
+    ]
+}
+
+/// Converts ECEF XYZ in meters to geodetic coordinates (lat, lon in degrees, height above the ellipsoid in meters)
+pub fn ecef_to_geodetic(xyz: &[f64; 3], ellipsoid: Ellipsoid) -> (f64, f64, f64) {
+    let a = ellipsoid.semi_major_axis();
+    let e2 = ellipsoid.e2();
+    let [x, y, z] = *xyz;
+    let p = x.hypot(y);
+    let lon = y.atan2(x);
+    // Iterate on the latitude, which converges to sub-mm within a few iterations for terrestrial heights
+    let mut lat = z.atan2(p * (1.0 - e2));
+    for _ in 0..10 {
+        let n = a / (1.0 - e2 * lat.sin().powi(2)).sqrt();
+        let next = (z + e2 * n * lat.sin()).atan2(p);
+        if (next - lat).abs() < 1e-14 {
+            lat = next;
+            break;
+        }
+        lat = next;
+    }
+    // This form of the height is well conditioned at the poles too
+    let height = p * lat.cos() + z * lat.sin() - a * (1.0 - e2 * lat.sin().powi(2)).sqrt();
+    (lat.to_degrees(), lon.to_degrees(), height)
+}
@@ -0,0 +1,96 @@
+//! Site horizon profiles
+//!
+//! Mountain sites rarely have a flat 0° horizon, so an [`Observer`](crate::positions::Observer) can carry a
+//! horizon mask that visibility (and rise/set) calculations respect.
+
+use crate::error::Error;
+use std::io::BufRead;
+
+/// The minimum usable elevation of a site as a function of azimuth
+///
+/// This is implemented by [`HorizonProfile`] and by any closure taking the azimuth and returning the minimum
+/// elevation (both in degrees).
+pub trait HorizonMask: Send + Sync {
+    /// The minimum elevation in degrees at azimuth `az` in degrees (east of north)
+    fn min_elevation(&self, az: f64) -> f64;
+}
+
+impl<F> HorizonMask for F
+where
+    F: Fn(f64) -> f64 + Send + Sync,
+{
+    fn min_elevation(&self, az: f64) -> f64 {
+        self(az)
+    }
+}
+
+/// A tabulated horizon profile, linearly interpolated in azimuth (wrapping through north)
+#[derive(Debug, Clone, PartialEq)]
+pub struct HorizonProfile {
+    /// (az, min el) points in degrees, sorted by azimuth in [0, 360)
+    points: Vec<(f64, f64)>,
+}
+
+impl HorizonProfile {
+    /// Construct a new profile from (az, min el) points in degrees
+    pub fn new(points: impl IntoIterator<Item = (f64, f64)>) -> super::Result<Self> {
+        let mut points = points
+            .into_iter()
+            .map(|(az, el)| {
+                if !az.is_finite() || !el.is_finite() {
+                    return Err(Error::InvalidValue("horizon point"));
+                }
+                Ok((az.rem_euclid(360.0), el))
+            })
+            .collect::<super::Result<Vec<_>>>()?;
+        if points.is_empty() {
+            return Err(Error::InvalidValue("horizon profile"));
+        }
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Ok(Self { points })
+    }
+
+    /// Read a profile from a text file with one `az el` pair (in degrees) per line
+    ///
+    /// Values may be separated by whitespace or commas, and blank lines and lines starting with `#` are ignored.
+    pub fn read<R: BufRead>(reader: R) -> super::Result<Self> {
+        let mut points = vec![];
+        for line in reader.lines() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut values = line
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.parse::<f64>());
+            match (values.next(), values.next()) {
+                (Some(Ok(az)), Some(Ok(el))) => points.push((az, el)),
+                _ => return Err(Error::InvalidValue("horizon point")),
+            }
+        }
+        Self::new(points)
+    }
+
+    /// The (az, min el) points of the profile in degrees, sorted by azimuth
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+}
+
+impl HorizonMask for HorizonProfile {
+    fn min_elevation(&self, az: f64) -> f64 {
+        let az = az.rem_euclid(360.0);
+        let n = self.points.len();
+        // The first point above az, wrapping around to the start
+        let idx = self.points.partition_point(|p| p.0 <= az);
+        let (az0, el0) = self.points[(idx + n - 1) % n];
+        let (az1, el1) = self.points[idx % n];
+        let span = (az1 - az0).rem_euclid(360.0);
+        if span == 0.0 {
+            return el0;
+        }
+        el0 + (el1 - el0) * (az - az0).rem_euclid(360.0) / span
+    }
+}
@@ -0,0 +1,130 @@
+//! Utilities for querying small-body orbital elements from JPL Horizons
+
+use crate::{
+    error::Error,
+    net::{default_client, RateLimiter, RetryPolicy},
+    orbital::{truncate_name, OrbitalElements, OrbitalObject},
+};
+use reqwest::{blocking::Client, Url};
+
+/// The Horizons API endpoint
+const HORIZONS_URL: &str = "https://ssd.jpl.nasa.gov/api/horizons.api";
+
+/// The JPL Horizons on-line solar system data and ephemeris computation service
+#[derive(Debug, Clone)]
+pub struct Horizons {
+    /// The HTTP client used for the queries
+    client: Client,
+    /// Timeout and retry behavior of the queries
+    retry: RetryPolicy,
+    /// The rate limit of the queries, if any
+    limiter: Option<RateLimiter>,
+}
+
+impl Default for Horizons {
+    fn default() -> Self {
+        Self {
+            client: default_client(),
+            retry: RetryPolicy::default(),
+            limiter: None,
+        }
+    }
+}
+
+impl Horizons {
+    /// Construct a new Horizons client with a default HTTP client
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use the given HTTP client (with its proxy, TLS, and timeout configuration) for all queries
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Use the given timeout and retry policy for all queries
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Share the given rate limiter for all queries
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    /// Fetch the osculating heliocentric ecliptic (J2000) elements of a comet or asteroid at the given epoch
+    ///
+    /// - designation: The small-body designation, name, or number (e.g. "433", "Ceres", "2023 DW")
+    /// - jd_tdb: The epoch of the elements as a TDB Julian date
+    pub fn elements(&self, designation: &str, jd_tdb: f64) -> super::Result<OrbitalObject> {
+        let url = Url::parse_with_params(
+            HORIZONS_URL,
+            &[
+                ("format", "text"),
+                // The trailing semicolon restricts the search to small bodies
+                ("COMMAND", &format!("'{designation};'")),
+                ("OBJ_DATA", "'NO'"),
+                ("MAKE_EPHEM", "'YES'"),
+                ("EPHEM_TYPE", "'ELEMENTS'"),
+                ("CENTER", "'500@10'"),
+                ("REF_PLANE", "'ECLIPTIC'"),
+                ("REF_SYSTEM", "'ICRF'"),
+                ("OUT_UNITS", "'AU-D'"),
+                ("CSV_FORMAT", "'YES'"),
+                ("TLIST", &format!("'{jd_tdb}'")),
+            ],
+        )
+        .map_err(|_| Error::InvalidString)?;
+        let resp = self
+            .retry
+            .send(self.limiter.as_ref(), || self.client.get(url.clone()))?
+            .text()?;
+        parse_elements(designation, &resp)
+    }
+}
+
+/// Parse the text output of an ELEMENTS ephemeris query
+fn parse_elements(designation: &str, resp: &str) -> super::Result<OrbitalObject> {
+    // Horizons reports the resolved body in the header, e.g. "Target body name: 433 Eros (A898 PA)"
+    let name = resp
+        .lines()
+        .find_map(|line| line.strip_prefix("Target body name:"))
+        .map(|rest| rest.split('{').next().unwrap_or(rest).trim())
+        .unwrap_or(designation);
+    let name = truncate_name(name);
+    let number = designation.trim().parse().unwrap_or(0);
+
+    // The elements are listed between the start and end of ephemeris markers
+    let row = resp
+        .lines()
+        .skip_while(|line| !line.starts_with("$$SOE"))
+        .nth(1)
+        .filter(|line| !line.starts_with("$$EOE"))
+        .ok_or_else(|| Error::NotFound(designation.to_string()))?;
+
+    // JDTDB, Calendar Date (TDB), EC, QR, IN, OM, W, Tp, N, MA, TA, A, AD, PR
+    let columns = row.split(',').map(str::trim).collect::<Vec<_>>();
+    if columns.len() < 12 {
+        return Err(Error::Horizons(format!("Unexpected element row: {row}")));
+    }
+    let column = |i: usize| -> super::Result<f64> {
+        columns[i]
+            .parse()
+            .map_err(|_| Error::Horizons(format!("Invalid element value: {}", columns[i])))
+    };
+
+    let elements = OrbitalElements {
+        epoch: column(0)?,
+        e: column(2)?,
+        i: column(4)?,
+        node: column(5)?,
+        peri: column(6)?,
+        mean_motion: column(8)?,
+        mean_anomaly: column(9)?,
+        a: column(11)?,
+    };
+    OrbitalObject::new(name, number, elements)
+}
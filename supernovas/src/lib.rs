@@ -1,16 +1,77 @@
-use supernovas_sys::{novas_accuracy, novas_debug, novas_debug_mode};
+use nutation::BuiltinNutation;
+use supernovas_sys::{
+    novas_accuracy, novas_debug, novas_debug_mode, SUPERNOVAS_MAJOR_VERSION,
+    SUPERNOVAS_MINOR_VERSION, SUPERNOVAS_PATCHLEVEL,
+};
 
+pub mod airmass;
+pub mod batch;
+#[cfg(feature = "bright-stars")]
+pub mod bright_stars;
+pub mod cache;
+pub mod catalog;
+pub mod coords;
+pub mod doppler;
+pub mod eop;
 #[cfg(feature = "calceph")]
 pub mod ephem;
 pub mod error;
+pub mod events;
+#[cfg(feature = "tokio")]
+pub mod fetch;
+pub mod geodesy;
+pub mod horizon;
+#[cfg(feature = "horizons")]
+pub mod horizons;
+pub mod mpc;
+#[cfg(feature = "ned")]
+pub mod ned;
+#[cfg(any(
+    feature = "simbad",
+    feature = "horizons",
+    feature = "mpc",
+    feature = "tokio"
+))]
+pub mod net;
+pub mod nutation;
+pub mod obscodes;
+pub mod orbital;
+pub mod plan;
+pub mod planets;
+pub mod pointing;
 pub mod positions;
+#[cfg(feature = "simbad")]
+pub mod quick;
+pub mod realtime;
+pub mod refraction;
+#[cfg(feature = "sgp4")]
+pub mod satellite;
+pub mod schedule;
+#[cfg(feature = "simbad")]
+pub mod sesame;
 pub mod simbad;
+pub mod sites;
+pub mod solar;
+pub mod table;
+pub mod target;
+pub mod telescope;
 pub mod time;
+pub mod track;
+pub mod uncertainty;
+pub mod validation;
+#[cfg(feature = "simbad")]
+pub mod votable;
+pub mod wcs;
+
+#[cfg(feature = "simbad")]
+pub use quick::quick_azel;
 
 pub type Result<T> = std::result::Result<T, error::Error>;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 /// Constants to control the precision of NOVAS nutation calculations.
+///
+/// See the [`nutation`] module for the series used at each accuracy and their milliarcsecond-level differences.
 pub enum Accuracy {
     ///	Use full precision calculations to micro-arcsecond accuracy.
     /// It can be computationally intensive when using the dynamical equator.
@@ -19,6 +80,16 @@ pub enum Accuracy {
     Reduced,
 }
 
+impl From<novas_accuracy> for Accuracy {
+    fn from(value: novas_accuracy) -> Self {
+        if value == novas_accuracy::NOVAS_FULL_ACCURACY {
+            Accuracy::Full
+        } else {
+            Accuracy::Reduced
+        }
+    }
+}
+
 impl From<Accuracy> for novas_accuracy {
     fn from(value: Accuracy) -> Self {
         match value {
@@ -32,3 +103,65 @@ impl From<Accuracy> for novas_accuracy {
 pub fn set_debug(enable: bool) {
     unsafe { novas_debug(novas_debug_mode(enable as u32)) }
 }
+
+/// The computation core behind this crate, for stating in bug reports and logs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    /// The version of this crate
+    pub crate_version: &'static str,
+    /// The (major, minor, patch) version of the vendored SuperNOVAS C library
+    pub library: (u32, u32, u32),
+    /// The built-in series used for reduced-accuracy nutation, or `None` if a custom model is in use
+    pub nutation: Option<BuiltinNutation>,
+    /// The optional crate features this build was compiled with
+    pub features: Vec<&'static str>,
+}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (major, minor, patch) = self.library;
+        write!(
+            f,
+            "supernovas {} (SuperNOVAS {major}.{minor}.{patch}",
+            self.crate_version
+        )?;
+        match self.nutation {
+            Some(nutation) => write!(f, ", {nutation:?} nutation")?,
+            None => write!(f, ", custom nutation")?,
+        }
+        if !self.features.is_empty() {
+            write!(f, "; features: {}", self.features.join(", "))?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// The versions, reduced-accuracy nutation model, and enabled features of this build
+pub fn version() -> Version {
+    let features = [
+        ("bright-stars", cfg!(feature = "bright-stars")),
+        ("calceph", cfg!(feature = "calceph")),
+        ("cli", cfg!(feature = "cli")),
+        ("hifitime", cfg!(feature = "hifitime")),
+        ("horizons", cfg!(feature = "horizons")),
+        ("mpc", cfg!(feature = "mpc")),
+        ("ned", cfg!(feature = "ned")),
+        ("rayon", cfg!(feature = "rayon")),
+        ("sgp4", cfg!(feature = "sgp4")),
+        ("simbad", cfg!(feature = "simbad")),
+        ("tokio", cfg!(feature = "tokio")),
+    ];
+    Version {
+        crate_version: env!("CARGO_PKG_VERSION"),
+        library: (
+            SUPERNOVAS_MAJOR_VERSION,
+            SUPERNOVAS_MINOR_VERSION,
+            SUPERNOVAS_PATCHLEVEL,
+        ),
+        nutation: nutation::builtin_nutation(),
+        features: features
+            .into_iter()
+            .filter_map(|(name, enabled)| enabled.then_some(name))
+            .collect(),
+    }
+}
@@ -0,0 +1,291 @@
+//! Parsers for the Minor Planet Center's orbit files (`MPCORB.DAT` and `CometEls.txt`)
+
+use crate::{
+    error::Error,
+    orbital::{truncate_name, OrbitalElements, OrbitalObject},
+};
+use std::io::BufRead;
+use supernovas_sys::julian_date;
+
+/// The Gaussian gravitational constant in degrees/day
+const GAUSS_K_DEG: f64 = 0.985_607_668_6;
+
+/// Parse all the minor planet orbits in an `MPCORB.DAT` (or a subset like `NEA.txt`) file
+///
+/// Header lines before the dashed separator (if present) and blank lines are ignored.
+pub fn parse_mpcorb<R: BufRead>(reader: R) -> super::Result<Vec<OrbitalObject>> {
+    let lines = reader
+        .lines()
+        .collect::<std::io::Result<Vec<_>>>()
+        .map_err(|e| Error::Mpc(e.to_string()))?;
+    // Skip the header, if it exists
+    let start = lines
+        .iter()
+        .position(|line| line.starts_with("-----"))
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    lines[start..]
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| parse_mpcorb_line(line))
+        .collect()
+}
+
+/// Parse a single record of an `MPCORB.DAT` file
+pub fn parse_mpcorb_line(line: &str) -> super::Result<OrbitalObject> {
+    if line.len() < 103 {
+        return Err(Error::Mpc(format!("Truncated record: {line}")));
+    }
+    let elements = OrbitalElements {
+        epoch: unpack_epoch(field(line, 21, 25))?,
+        mean_anomaly: parse_f64(field(line, 27, 35))?,
+        peri: parse_f64(field(line, 38, 46))?,
+        node: parse_f64(field(line, 49, 57))?,
+        i: parse_f64(field(line, 60, 68))?,
+        e: parse_f64(field(line, 71, 79))?,
+        mean_motion: parse_f64(field(line, 81, 91))?,
+        a: parse_f64(field(line, 93, 103))?,
+    };
+
+    let packed = field(line, 1, 7);
+    let number = unpack_number(packed).unwrap_or(0);
+    // The readable designation looks like "(433) Eros" or "2015 AB"
+    let readable = field(line, 167, 194);
+    let name = match readable.split_once(") ") {
+        Some((_, name)) if readable.starts_with('(') => name,
+        _ if readable.is_empty() => packed,
+        _ => readable,
+    };
+    OrbitalObject::new(truncate_name(name), number, elements)
+}
+
+/// The comets of a `CometEls.txt` file
+#[derive(Debug, Clone, Default)]
+pub struct CometEls {
+    /// The comets on closed (elliptical) orbits
+    pub orbits: Vec<OrbitalObject>,
+    /// The names of the comets on parabolic or hyperbolic orbits, which can't be represented as
+    /// [`OrbitalElements`]
+    pub unbound: Vec<String>,
+}
+
+/// Parse all the comet orbits in a `CometEls.txt` file
+///
+/// Only closed (elliptical) orbits can be represented as [`OrbitalElements`], so parabolic and hyperbolic
+/// comets are listed by name in [`CometEls::unbound`] instead.
+pub fn parse_comet_els<R: BufRead>(reader: R) -> super::Result<CometEls> {
+    let mut comets = CometEls::default();
+    for line in reader.lines() {
+        let line = line.map_err(|e| Error::Mpc(e.to_string()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if is_unbound(&line)? {
+            comets.unbound.push(field(&line, 103, 158).to_owned());
+        } else {
+            comets.orbits.push(parse_comet_line(&line)?);
+        }
+    }
+    Ok(comets)
+}
+
+/// Whether a `CometEls.txt` record is of a parabolic or hyperbolic orbit
+fn is_unbound(line: &str) -> super::Result<bool> {
+    if line.len() < 102 {
+        return Err(Error::Mpc(format!("Truncated record: {line}")));
+    }
+    Ok(parse_f64(field(line, 42, 49))? >= 1.0)
+}
+
+/// Parse a single record of a `CometEls.txt` file
+///
+/// This errors for parabolic and hyperbolic orbits, which can't be represented as [`OrbitalElements`].
+pub fn parse_comet_line(line: &str) -> super::Result<OrbitalObject> {
+    if is_unbound(line)? {
+        return Err(Error::Mpc(format!(
+            "Unbound orbit of {}",
+            field(line, 103, 158)
+        )));
+    }
+    let e = parse_f64(field(line, 42, 49))?;
+    let q = parse_f64(field(line, 31, 39))?;
+    let a = q / (1.0 - e);
+
+    // The elements are referenced to the time of perihelion passage, where the mean anomaly is zero
+    let year = parse_f64(field(line, 15, 18))? as i16;
+    let month = parse_f64(field(line, 20, 21))? as i16;
+    let day = parse_f64(field(line, 23, 29))?;
+    // Safety: This is a pure function of its arguments
+    let jd_peri = unsafe { julian_date(year, month, day.trunc() as i16, day.fract() * 24.0) };
+
+    let elements = OrbitalElements {
+        epoch: jd_peri,
+        a,
+        e,
+        i: parse_f64(field(line, 72, 79))?,
+        node: parse_f64(field(line, 62, 69))?,
+        peri: parse_f64(field(line, 52, 59))?,
+        mean_anomaly: 0.0,
+        mean_motion: GAUSS_K_DEG / a.powf(1.5),
+    };
+
+    let number = field(line, 1, 4).parse().unwrap_or(0);
+    let name = field(line, 103, 158);
+    OrbitalObject::new(truncate_name(name), number, elements)
+}
+
+/// Extract the (trimmed) text in the 1-based, inclusive column range of a fixed-width record
+fn field(line: &str, start: usize, end: usize) -> &str {
+    let end = end.min(line.len());
+    line.get(start - 1..end).unwrap_or("").trim()
+}
+
+fn parse_f64(s: &str) -> super::Result<f64> {
+    s.parse()
+        .map_err(|_| Error::Mpc(format!("Invalid numeric field: {s}")))
+}
+
+/// Decode the MPC packed digits, where 0-9 are themselves, A-Z are 10-35, and a-z are 36-61
+fn unpack_digit(c: char) -> Option<u32> {
+    match c {
+        '0'..='9' => c.to_digit(10),
+        'A'..='Z' => Some(c as u32 - 'A' as u32 + 10),
+        'a'..='z' => Some(c as u32 - 'a' as u32 + 36),
+        _ => None,
+    }
+}
+
+/// Unpack a packed minor planet number (e.g. "00433" or "A0345"), returning `None` for provisional designations
+fn unpack_number(packed: &str) -> Option<i64> {
+    if packed.len() != 5 {
+        return None;
+    }
+    let mut chars = packed.chars();
+    let head = unpack_digit(chars.next()?)? as i64;
+    let tail: i64 = chars.as_str().parse().ok()?;
+    Some(head * 10_000 + tail)
+}
+
+/// Unpack a packed epoch (e.g. "K2455" for 2024 May 5.0 TT) into a Julian date
+fn unpack_epoch(packed: &str) -> super::Result<f64> {
+    let invalid = || Error::Mpc(format!("Invalid packed epoch: {packed}"));
+    let chars = packed.chars().collect::<Vec<_>>();
+    if chars.len() != 5 {
+        return Err(invalid());
+    }
+    let century = unpack_digit(chars[0]).ok_or_else(invalid)? as i16;
+    let year: i16 = packed[1..3].parse().map_err(|_| invalid())?;
+    let month = unpack_digit(chars[3]).ok_or_else(invalid)? as i16;
+    let day = unpack_digit(chars[4]).ok_or_else(invalid)? as i16;
+    // Safety: This is a pure function of its arguments
+    Ok(unsafe { julian_date(century * 100 + year, month, day, 0.0) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fixed-width record with the given texts starting at the given 1-based columns
+    fn record(len: usize, fields: &[(usize, &str)]) -> String {
+        let mut line = vec![b' '; len];
+        for (start, text) in fields {
+            line[start - 1..start - 1 + text.len()].copy_from_slice(text.as_bytes());
+        }
+        String::from_utf8(line).unwrap()
+    }
+
+    fn ceres() -> String {
+        record(
+            202,
+            &[
+                (1, "00001"),
+                (9, " 3.34"),
+                (21, "K2555"),
+                (27, "188.70269"),
+                (38, " 73.27343"),
+                (49, " 80.25221"),
+                (60, " 10.58780"),
+                (71, "0.0794013"),
+                (81, "0.21424651"),
+                (93, "2.7660512"),
+                (167, "(1) Ceres"),
+            ],
+        )
+    }
+
+    #[test]
+    fn mpcorb_record() {
+        let ceres = parse_mpcorb_line(&ceres()).unwrap();
+        assert_eq!(ceres.name(), "Ceres");
+        assert_eq!(ceres.number(), 1);
+        let elements = ceres.elements();
+        assert_eq!(elements.epoch, 2_460_800.5);
+        assert_eq!(elements.a, 2.766_051_2);
+        assert_eq!(elements.e, 0.079_401_3);
+        assert_eq!(elements.i, 10.5878);
+        assert_eq!(elements.mean_anomaly, 188.702_69);
+    }
+
+    #[test]
+    fn mpcorb_file_with_header() {
+        let file = format!(
+            "MINOR PLANET CENTER ORBIT DATABASE\n\n-----------\n{}\n\n",
+            ceres()
+        );
+        let orbits = parse_mpcorb(file.as_bytes()).unwrap();
+        assert_eq!(orbits.len(), 1);
+        assert!(parse_mpcorb_line("00001    3.34").is_err());
+    }
+
+    #[test]
+    fn packed_numbers_and_epochs() {
+        assert_eq!(unpack_number("00433"), Some(433));
+        assert_eq!(unpack_number("A0345"), Some(100_345));
+        assert_eq!(unpack_number("K15A01B"), None);
+        assert_eq!(unpack_epoch("J9611").unwrap(), 2_450_083.5);
+        assert!(unpack_epoch("K24").is_err());
+    }
+
+    /// A record of Halley's orbit, with the given eccentricity and name
+    fn comet(e: &str, name: &str) -> String {
+        record(
+            160,
+            &[
+                (1, "0001P"),
+                (15, "1986 02  9.4589"),
+                (31, " 0.574761"),
+                (42, e),
+                (52, "111.8581"),
+                (62, " 59.4189"),
+                (72, "162.1905"),
+                (103, name),
+            ],
+        )
+    }
+
+    #[test]
+    fn comet_record() {
+        let halley = comet("0.967983", "1P/Halley");
+        let halley = parse_comet_line(&halley).unwrap();
+        assert_eq!(halley.name(), "1P/Halley");
+        assert_eq!(halley.number(), 1);
+        let elements = halley.elements();
+        assert!((elements.epoch - 2_446_470.9589).abs() < 1e-6);
+        assert!((elements.a - 0.574_761 / (1.0 - 0.967_983)).abs() < 1e-12);
+        assert_eq!(elements.mean_anomaly, 0.0);
+    }
+
+    #[test]
+    fn unbound_comets() {
+        let oumuamua = comet("1.201133", "1I/'Oumuamua");
+        assert!(matches!(parse_comet_line(&oumuamua), Err(Error::Mpc(_))));
+        let file = format!(
+            "{}\n{oumuamua}\n{}\n",
+            comet("0.967983", "1P/Halley"),
+            comet("1.000000", "C/2020 F3 (NEOWISE)")
+        );
+        let comets = parse_comet_els(file.as_bytes()).unwrap();
+        assert_eq!(comets.orbits.len(), 1);
+        assert_eq!(comets.unbound, ["1I/'Oumuamua", "C/2020 F3 (NEOWISE)"]);
+    }
+}
@@ -0,0 +1,148 @@
+//! Utilities for querying the NASA/IPAC Extragalactic Database (NED)
+//!
+//! NED has far better coverage of faint galaxies than SIMBAD, along with their redshifts, so extragalactic
+//! identifiers can be resolved directly into [`Target::Redshifted`] targets.
+
+use crate::{
+    doppler::C,
+    error::Error,
+    net::{default_client, RateLimiter, RetryPolicy},
+    positions::CatalogEntry,
+    target::Target,
+    votable::VoTable,
+};
+use reqwest::{blocking::Client, Url};
+
+/// The NED object search endpoint
+const NED_URL: &str = "https://ned.ipac.caltech.edu/cgi-bin/objsearch";
+
+/// An object resolved by NED
+#[derive(Debug, Clone, PartialEq)]
+pub struct NedObject {
+    /// The preferred NED name of the object
+    pub name: String,
+    /// ICRS right ascension in hours
+    pub ra: f64,
+    /// ICRS declination in degrees
+    pub dec: f64,
+    /// The NED object type, e.g. `G` for a galaxy or `QSO` for a quasar
+    pub object_type: String,
+    /// The (heliocentric) redshift, if measured
+    pub redshift: Option<f64>,
+}
+
+impl NedObject {
+    /// The object as a target at its redshift (or zero, if it wasn't measured)
+    pub fn target(&self) -> Target {
+        Target::Redshifted {
+            name: self.name.clone(),
+            ra: self.ra,
+            dec: self.dec,
+            z: self.redshift.unwrap_or(0.0),
+        }
+    }
+
+    /// The object as a catalog entry, with its redshift as a (relativistic) radial velocity
+    pub fn entry(&self) -> super::Result<CatalogEntry> {
+        let rad_vel = match self.redshift {
+            Some(z) => {
+                // Relativistic Doppler: 1 + z = sqrt((1 + β) / (1 - β))
+                let s = (1.0 + z).powi(2);
+                (s - 1.0) / (s + 1.0) * C
+            }
+            None => 0.0,
+        };
+        CatalogEntry::new(
+            &self.name, "NED", 0, self.ra, self.dec, 0.0, 0.0, 0.0, rad_vel,
+        )
+    }
+}
+
+/// The NASA/IPAC Extragalactic Database
+#[derive(Debug, Clone)]
+pub struct Ned {
+    /// The HTTP client used for the queries
+    client: Client,
+    /// Timeout and retry behavior of the queries
+    retry: RetryPolicy,
+    /// The rate limit of the queries, if any
+    limiter: Option<RateLimiter>,
+}
+
+impl Default for Ned {
+    fn default() -> Self {
+        Self {
+            client: default_client(),
+            retry: RetryPolicy::default(),
+            limiter: None,
+        }
+    }
+}
+
+impl Ned {
+    /// Construct a new NED client with a default HTTP client
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use the given HTTP client (with its proxy, TLS, and timeout configuration) for all queries
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Use the given timeout and retry policy for all queries
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Share the given rate limiter for all queries
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    /// Resolve an object by name (e.g. "NGC 4993" or "2MASX J13095571-2323535")
+    pub fn resolve(&self, name: &str) -> super::Result<NedObject> {
+        let url = Url::parse_with_params(
+            NED_URL,
+            &[("objname", name), ("extend", "no"), ("of", "xml_main")],
+        )
+        .map_err(|_| Error::InvalidString)?;
+        let resp = self
+            .retry
+            .send(self.limiter.as_ref(), || self.client.get(url.clone()))?;
+        let table = VoTable::parse(resp)?;
+        let column = |names: &[&str], ucd: &str| table.column(names, Some(ucd));
+        let (Some(name_col), Some(ra_col), Some(dec_col)) = (
+            column(&["Object Name"], "meta.id;meta.main"),
+            column(&["RA(deg)", "RA"], "pos.eq.ra;meta.main"),
+            column(&["DEC(deg)", "DEC"], "pos.eq.dec;meta.main"),
+        ) else {
+            return Err(Error::Table(
+                "NED result is missing its name or position".to_string(),
+            ));
+        };
+        let type_col = column(&["Type"], "src.class");
+        let z_col = column(&["Redshift"], "src.redshift");
+        let row = table
+            .rows
+            .first()
+            .ok_or_else(|| Error::NotFound(name.to_string()))?;
+        let text = |col: Option<usize>| col.and_then(|i| row.get(i)).map(|s| s.trim());
+        let number = |col: usize| -> super::Result<f64> {
+            let value = text(Some(col)).unwrap_or_default();
+            value
+                .parse()
+                .map_err(|_| Error::Table(format!("Invalid NED value: {value}")))
+        };
+        Ok(NedObject {
+            name: text(Some(name_col)).unwrap_or_default().to_string(),
+            ra: number(ra_col)? / 15.0,
+            dec: number(dec_col)?,
+            object_type: text(type_col).unwrap_or_default().to_string(),
+            redshift: text(z_col).and_then(|z| z.parse().ok()),
+        })
+    }
+}
@@ -0,0 +1,269 @@
+//! Shared networking utilities for the remote query modules
+
+use crate::error::Error;
+#[cfg(any(feature = "simbad", feature = "horizons", feature = "mpc"))]
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// A limit on the rate of requests, shared by every client (and thread) holding a clone of it
+///
+/// Bulk operations (e.g. resolving thousands of identifiers) should share one limiter across all of the clients
+/// querying the same service, so the service's usage limits are respected no matter how the work is split up.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    /// The minimum time between the starts of consecutive requests
+    interval: Duration,
+    /// The earliest time the next request may start
+    next: Arc<Mutex<Instant>>,
+}
+
+impl RateLimiter {
+    /// Allow at most one request every `interval`
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            next: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Allow at most `rate` requests per second
+    ///
+    /// This errors unless the rate is positive, and not so small that the interval can't be represented.
+    pub fn per_second(rate: f64) -> super::Result<Self> {
+        if rate.is_nan() || rate <= 0.0 {
+            return Err(Error::InvalidValue("rate"));
+        }
+        let interval =
+            Duration::try_from_secs_f64(1.0 / rate).map_err(|_| Error::InvalidValue("rate"))?;
+        Ok(Self::new(interval))
+    }
+
+    /// Block until the next request may be sent
+    pub fn wait(&self) {
+        thread::sleep(self.reserve().saturating_duration_since(Instant::now()));
+    }
+
+    /// Wait (without blocking the thread) until the next request may be sent
+    #[cfg(feature = "tokio")]
+    pub async fn wait_async(&self) {
+        tokio::time::sleep_until(self.reserve().into()).await;
+    }
+
+    /// Reserve the next slot, returning when it starts
+    fn reserve(&self) -> Instant {
+        // Reserve a slot while holding the lock, but wait without it so other threads can reserve later slots
+        let mut next = self.next.lock().unwrap();
+        let slot = (*next).max(Instant::now());
+        *next = slot + self.interval;
+        slot
+    }
+}
+
+/// The timeout of the HTTP clients the remote query modules construct themselves
+#[cfg(any(feature = "simbad", feature = "horizons", feature = "mpc"))]
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The HTTP client used by the remote query modules unless another is given
+#[cfg(any(feature = "simbad", feature = "horizons", feature = "mpc"))]
+pub(crate) fn default_client() -> Client {
+    Client::builder()
+        .timeout(DEFAULT_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+}
+
+/// Timeout and retry-with-backoff configuration for remote queries
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Timeout for each individual attempt (`None` uses the client's timeout)
+    pub timeout: Option<Duration>,
+    /// Number of retries after the first failed attempt
+    pub max_retries: u32,
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+    /// Upper bound on the delay between retries
+    pub max_backoff: Duration,
+    /// Factor the delay grows by after each retry (a negative or NaN factor keeps the delay constant)
+    pub multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: None,
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2.0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that makes a single attempt with the given timeout
+    pub fn no_retry(timeout: Option<Duration>) -> Self {
+        Self {
+            timeout,
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Send the request built by `build` without blocking, retrying transient failures like [`RetryPolicy::send`]
+    #[cfg(feature = "tokio")]
+    pub(crate) async fn send_async(
+        &self,
+        limiter: Option<&RateLimiter>,
+        build: impl Fn() -> reqwest::RequestBuilder,
+    ) -> std::result::Result<reqwest::Response, reqwest::Error> {
+        let mut backoff = self.initial_backoff;
+        let mut attempt = 0;
+        loop {
+            if let Some(limiter) = limiter {
+                limiter.wait_async().await;
+            }
+            let mut req = build();
+            if let Some(timeout) = self.timeout {
+                req = req.timeout(timeout);
+            }
+            match req
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status)
+            {
+                Ok(resp) => return Ok(resp),
+                Err(e) if attempt < self.max_retries && is_transient(&e) => {
+                    tokio::time::sleep(backoff).await;
+                    backoff = self.next_backoff(backoff);
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Send the request built by `build`, retrying transient failures (connection errors, timeouts,
+    /// 429s, and server errors) according to this policy
+    ///
+    /// Every attempt (including retries) first waits for the rate limiter, if any.
+    #[cfg(any(feature = "simbad", feature = "horizons", feature = "mpc"))]
+    pub(crate) fn send(
+        &self,
+        limiter: Option<&RateLimiter>,
+        build: impl Fn() -> RequestBuilder,
+    ) -> std::result::Result<Response, reqwest::Error> {
+        let mut backoff = self.initial_backoff;
+        let mut attempt = 0;
+        loop {
+            if let Some(limiter) = limiter {
+                limiter.wait();
+            }
+            let mut req = build();
+            if let Some(timeout) = self.timeout {
+                req = req.timeout(timeout);
+            }
+            match req.send().and_then(Response::error_for_status) {
+                Ok(resp) => return Ok(resp),
+                Err(e) if attempt < self.max_retries && is_transient(&e) => {
+                    thread::sleep(backoff);
+                    backoff = self.next_backoff(backoff);
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// The delay before the retry following one after `backoff`
+    ///
+    /// This never panics, unlike [`Duration::mul_f64`], whatever the multiplier: delays too long to represent are
+    /// capped at the maximum, and invalid multipliers keep the delay as it is.
+    fn next_backoff(&self, backoff: Duration) -> Duration {
+        if self.multiplier.is_nan() || self.multiplier < 0.0 {
+            return backoff.min(self.max_backoff);
+        }
+        Duration::try_from_secs_f64(backoff.as_secs_f64() * self.multiplier)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+}
+
+/// Percent-encode everything but the unreserved characters of a URL, e.g. for an identifier in a query string
+///
+/// Characters like `+` (in "BD+20 307"), `&`, and spaces would otherwise change the meaning of the query.
+pub(crate) fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                char::from(b).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// Whether the error is worth retrying
+pub(crate) fn is_transient(e: &reqwest::Error) -> bool {
+    if e.is_timeout() || e.is_connect() {
+        return true;
+    }
+    match e.status() {
+        Some(status) => status.is_server_error() || status.as_u16() == 429,
+        None => e.is_request(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_encoding() {
+        assert_eq!(percent_encode("M31"), "M31");
+        assert_eq!(percent_encode("BD+20 307"), "BD%2B20%20307");
+        assert_eq!(percent_encode("a&b=c#d"), "a%26b%3Dc%23d");
+        assert_eq!(percent_encode("Barnard's"), "Barnard%27s");
+        assert_eq!(percent_encode("α Lyr"), "%CE%B1%20Lyr");
+    }
+
+    #[test]
+    fn rate_limits() {
+        assert_eq!(
+            RateLimiter::per_second(4.0).unwrap().interval,
+            Duration::from_millis(250)
+        );
+        assert_eq!(
+            RateLimiter::per_second(f64::INFINITY).unwrap().interval,
+            Duration::ZERO
+        );
+        for rate in [0.0, -1.0, f64::NAN, 1e-300] {
+            assert!(RateLimiter::per_second(rate).is_err(), "{rate}");
+        }
+    }
+
+    #[test]
+    fn backoff_multipliers() {
+        let policy = |multiplier| RetryPolicy {
+            multiplier,
+            ..Default::default()
+        };
+        let second = Duration::from_secs(1);
+        assert_eq!(policy(2.0).next_backoff(second), Duration::from_secs(2));
+        assert_eq!(policy(100.0).next_backoff(second), Duration::from_secs(10));
+        assert_eq!(
+            policy(f64::INFINITY).next_backoff(second),
+            Duration::from_secs(10)
+        );
+        assert_eq!(
+            policy(f64::MAX).next_backoff(second),
+            Duration::from_secs(10)
+        );
+        assert_eq!(policy(-1.0).next_backoff(second), second);
+        assert_eq!(policy(f64::NAN).next_backoff(second), second);
+        assert_eq!(policy(0.0).next_backoff(second), Duration::ZERO);
+    }
+}
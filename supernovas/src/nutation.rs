@@ -0,0 +1,132 @@
+//! Selecting the nutation model of reduced-accuracy calculations
+//!
+//! The choice of [`Accuracy`] is mostly a choice of nutation series. Full-accuracy calculations always use the
+//! complete IAU2000A series (1365 terms), but reduced-accuracy ones use a truncated series, which can be swapped out
+//! globally for speed or precision:
+//!
+//! | Series                        | Terms | Error vs. IAU2000A | Relative cost |
+//! |-------------------------------|-------|--------------------|---------------|
+//! | [`BuiltinNutation::IAU2000A`] | 1365  | —                  | 1             |
+//! | [`BuiltinNutation::NU2000K`]  | 488   | ~0.1 mas           | ~1/3          |
+//! | [`BuiltinNutation::IAU2000B`] | 77    | ~1 mas             | ~1/20         |
+//!
+//! The errors apply directly to apparent (TOD) positions and sidereal time; for arcsecond-class pointing any of them
+//! will do, while VLBI-class astrometry needs [`Accuracy::Full`]. A custom model (e.g. an even shorter truncation for
+//! a control loop) can be installed with [`set_nutation_model`].
+
+use crate::{error::Error, Accuracy};
+use std::{
+    ffi::{c_double, c_int},
+    sync::{LazyLock, RwLock},
+};
+use supernovas_sys::{iau2000a, iau2000b, nu2000k, set_nutation_lp_provider};
+
+/// A model of nutation in longitude and obliquity
+pub trait NutationModel: Send + Sync {
+    /// The nutation (dψ, dε) in radians at the split TT Julian date `jd_tt_high + jd_tt_low`
+    fn nutation(&self, jd_tt_high: f64, jd_tt_low: f64) -> (f64, f64);
+}
+
+impl<F> NutationModel for F
+where
+    F: Fn(f64, f64) -> (f64, f64) + Send + Sync,
+{
+    fn nutation(&self, jd_tt_high: f64, jd_tt_low: f64) -> (f64, f64) {
+        self(jd_tt_high, jd_tt_low)
+    }
+}
+
+/// The nutation series built into SuperNOVAS
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum BuiltinNutation {
+    /// The full IAU2000A series (accurate to ~0.1 μas, but slow)
+    IAU2000A,
+    /// The IAU2000B series (accurate to ~1 mas)
+    IAU2000B,
+    /// The NU2000K series (accurate to ~0.1 mas), the default
+    #[default]
+    NU2000K,
+}
+
+impl BuiltinNutation {
+    /// The approximate error of the series with respect to the full IAU2000A model, in milliarcseconds
+    pub fn error_mas(&self) -> f64 {
+        match self {
+            BuiltinNutation::IAU2000A => 0.0,
+            BuiltinNutation::IAU2000B => 1.0,
+            BuiltinNutation::NU2000K => 0.1,
+        }
+    }
+}
+
+/// The built-in series in use, or `None` if a custom model has been set
+static BUILTIN_NUTATION: RwLock<Option<BuiltinNutation>> =
+    RwLock::new(Some(BuiltinNutation::NU2000K));
+
+static NUTATION_MODEL: LazyLock<RwLock<Option<Box<dyn NutationModel>>>> =
+    LazyLock::new(|| RwLock::new(None));
+
+unsafe extern "C" fn nutation_trampoline(
+    jd_tt_high: c_double,
+    jd_tt_low: c_double,
+    dpsi: *mut c_double,
+    deps: *mut c_double,
+) -> c_int {
+    if dpsi.is_null() || deps.is_null() {
+        return -1;
+    }
+    let model = NUTATION_MODEL.read().unwrap();
+    match &*model {
+        None => -1,
+        Some(model) => {
+            let (psi, eps) = model.nutation(jd_tt_high, jd_tt_low);
+            *dpsi = psi;
+            *deps = eps;
+            0
+        }
+    }
+}
+
+/// Use one of the built-in series for reduced-accuracy nutation
+///
+/// Full-accuracy calculations are unaffected, and always use IAU2000A.
+pub fn set_builtin_nutation(series: BuiltinNutation) -> super::Result<()> {
+    let provider = match series {
+        BuiltinNutation::IAU2000A => iau2000a,
+        BuiltinNutation::IAU2000B => iau2000b,
+        BuiltinNutation::NU2000K => nu2000k,
+    };
+    // Safety: The built-in providers are valid for the lifetime of the program
+    let ret = unsafe { set_nutation_lp_provider(Some(provider)) };
+    if ret != 0 {
+        return Err(Error::LowerLevel(ret));
+    }
+    *NUTATION_MODEL.write().unwrap() = None;
+    *BUILTIN_NUTATION.write().unwrap() = Some(series);
+    Ok(())
+}
+
+/// Use a custom model for reduced-accuracy nutation, e.g. a further truncated series for speed-critical paths
+pub fn set_nutation_model(model: impl NutationModel + 'static) -> super::Result<()> {
+    *NUTATION_MODEL.write().unwrap() = Some(Box::new(model));
+    // Safety: The trampoline only reads the global model, which is now set
+    let ret = unsafe { set_nutation_lp_provider(Some(nutation_trampoline)) };
+    if ret != 0 {
+        return Err(Error::LowerLevel(ret));
+    }
+    *BUILTIN_NUTATION.write().unwrap() = None;
+    Ok(())
+}
+
+/// The nutation series used by calculations at the given accuracy, or `None` if a custom model is in use
+pub fn nutation_for(accuracy: Accuracy) -> Option<BuiltinNutation> {
+    match accuracy {
+        Accuracy::Full => Some(BuiltinNutation::IAU2000A),
+        Accuracy::Reduced => builtin_nutation(),
+    }
+}
+
+/// The built-in series currently used for reduced-accuracy nutation, or `None` if a custom model is in use
+pub fn builtin_nutation() -> Option<BuiltinNutation> {
+    *BUILTIN_NUTATION.read().unwrap()
+}
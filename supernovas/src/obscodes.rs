@@ -0,0 +1,155 @@
+//! Minor Planet Center observatory codes
+//!
+//! The MPC list gives each station's longitude and geocentric parallax constants (ρ cos φ', ρ sin φ', in units of
+//! the Earth's equatorial radius), which are converted here to geodetic coordinates. The list can be parsed from a
+//! local copy of `ObsCodes.html`, or downloaded with the `mpc` feature.
+
+#[cfg(feature = "mpc")]
+use crate::net::RetryPolicy;
+use crate::{
+    error::Error,
+    geodesy::{ecef_to_geodetic, Ellipsoid},
+    positions::{Observer, Weather},
+};
+#[cfg(feature = "mpc")]
+use reqwest::blocking::Client;
+use std::io::BufRead;
+
+/// The MPC observatory code list
+#[cfg(feature = "mpc")]
+const OBSCODES_URL: &str = "https://minorplanetcenter.net/iau/lists/ObsCodes.html";
+
+/// A single station of the MPC observatory code list
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObservatoryCode {
+    code: String,
+    name: String,
+    /// Longitude (degrees east) and parallax constants, absent for space-based and roving observers
+    parallax: Option<(f64, f64, f64)>,
+}
+
+impl ObservatoryCode {
+    /// The three-character observatory code (e.g. "G96")
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// The station name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The geodetic latitude and longitude in degrees and the height above the ellipsoid in meters, if this is a
+    /// fixed ground station
+    pub fn geodetic(&self) -> Option<(f64, f64, f64)> {
+        let (lon, rho_cos, rho_sin) = self.parallax?;
+        let a = Ellipsoid::WGS84.semi_major_axis();
+        let lon_rad = lon.to_radians();
+        let xyz = [
+            rho_cos * a * lon_rad.cos(),
+            rho_cos * a * lon_rad.sin(),
+            rho_sin * a,
+        ];
+        let (lat, lon, height) = ecef_to_geodetic(&xyz, Ellipsoid::WGS84);
+        Some((lat, lon, height))
+    }
+
+    /// An observer at the station with the given weather
+    pub fn observer(&self, weather: Weather) -> super::Result<Observer> {
+        let (lat, lon, height) = self
+            .geodetic()
+            .ok_or(Error::InvalidValue("observatory code"))?;
+        Ok(Observer::new_on_surface(lat, lon, height, weather))
+    }
+
+    /// Parse a single line of the code list, returning `None` for lines that are not stations (headers, markup)
+    fn parse_line(line: &str) -> Option<Self> {
+        let code = line.get(0..3)?;
+        if !code.chars().all(|c| c.is_ascii_alphanumeric()) || line.get(3..4)? != " " {
+            return None;
+        }
+        let num = |start: usize, end: usize| line.get(start..end).map(str::trim);
+        let parallax = match (num(4, 13)?, num(13, 21)?, num(21, 30)?) {
+            ("", "", "") => None,
+            (lon, cos, sin) => Some((lon.parse().ok()?, cos.parse().ok()?, sin.parse().ok()?)),
+        };
+        Some(Self {
+            code: code.to_string(),
+            name: line.get(30..).unwrap_or_default().trim().to_string(),
+            parallax,
+        })
+    }
+}
+
+/// The list of MPC observatory codes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ObservatoryCodes {
+    codes: Vec<ObservatoryCode>,
+}
+
+impl ObservatoryCodes {
+    /// Parse the code list (e.g. a local copy of `ObsCodes.html`), skipping any lines that are not stations
+    pub fn parse<R: BufRead>(reader: R) -> super::Result<Self> {
+        let mut codes = vec![];
+        for line in reader.lines() {
+            if let Some(code) = ObservatoryCode::parse_line(&line?) {
+                codes.push(code);
+            }
+        }
+        Ok(Self { codes })
+    }
+
+    /// Download the current code list from the Minor Planet Center
+    #[cfg(feature = "mpc")]
+    pub fn download(client: &Client, retry: &RetryPolicy) -> super::Result<Self> {
+        let resp = retry.send(None, || client.get(OBSCODES_URL))?.text()?;
+        Self::parse(resp.as_bytes())
+    }
+
+    /// Look up a station by its code
+    pub fn get(&self, code: &str) -> super::Result<&ObservatoryCode> {
+        let code = code.trim();
+        self.codes
+            .iter()
+            .find(|c| c.code.eq_ignore_ascii_case(code))
+            .ok_or_else(|| Error::NotFound(code.to_string()))
+    }
+
+    /// All of the stations in the list
+    pub fn all(&self) -> &[ObservatoryCode] {
+        &self.codes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODES: &str = "<pre>
+Code  Long.   cos      sin    Name
+000   0.0000 0.62411 +0.77873 Greenwich
+245                           Spitzer Space Telescope
+G96 249.211280.845111+0.533614Mt. Lemmon Survey
+</pre>";
+
+    #[test]
+    fn parse_stations() {
+        let codes = ObservatoryCodes::parse(CODES.as_bytes()).unwrap();
+        assert_eq!(codes.all().len(), 3);
+        assert_eq!(codes.get("000").unwrap().name(), "Greenwich");
+        assert_eq!(codes.get(" g96 ").unwrap().name(), "Mt. Lemmon Survey");
+        assert!(codes.get("XXX").is_err());
+    }
+
+    #[test]
+    fn geodetic_coordinates() {
+        let codes = ObservatoryCodes::parse(CODES.as_bytes()).unwrap();
+        let (lat, lon, height) = codes.get("G96").unwrap().geodetic().unwrap();
+        assert!((lat - 32.442_75).abs() < 1e-4);
+        assert!((lon.rem_euclid(360.0) - 249.211_28).abs() < 1e-6);
+        assert!((height - 2788.8).abs() < 1.0);
+        let spitzer = codes.get("245").unwrap();
+        assert_eq!(spitzer.geodetic(), None);
+        assert!(spitzer.observer(Weather::default()).is_err());
+    }
+}
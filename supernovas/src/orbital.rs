@@ -0,0 +1,127 @@
+//! Solar system bodies described by Keplerian orbital elements
+
+use crate::{
+    error::Error,
+    positions::{object_name, Placeable},
+};
+use std::{borrow::Cow, mem::MaybeUninit};
+use supernovas_sys::{
+    make_orbital_object, novas_orbital, novas_planet, novas_reference_plane,
+    novas_reference_system, object, SIZE_OF_OBJ_NAME,
+};
+
+/// Osculating heliocentric orbital elements, referenced to the ecliptic of J2000
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OrbitalElements {
+    /// Epoch of the elements as a TDB Julian date
+    pub epoch: f64,
+    /// Semi-major axis in AU
+    pub a: f64,
+    /// Eccentricity
+    pub e: f64,
+    /// Inclination in degrees
+    pub i: f64,
+    /// Longitude of the ascending node in degrees
+    pub node: f64,
+    /// Argument of perihelion in degrees
+    pub peri: f64,
+    /// Mean anomaly at the epoch in degrees
+    pub mean_anomaly: f64,
+    /// Mean daily motion in degrees/day
+    pub mean_motion: f64,
+}
+
+impl From<&OrbitalElements> for novas_orbital {
+    fn from(value: &OrbitalElements) -> Self {
+        // Safety: This is a plain C struct for which all zeros is valid (and is the C default for the unused fields)
+        let mut orbit: novas_orbital = unsafe { MaybeUninit::zeroed().assume_init() };
+        orbit.system.center = novas_planet::NOVAS_SUN;
+        orbit.system.plane = novas_reference_plane::NOVAS_ECLIPTIC_PLANE;
+        orbit.system.type_ = novas_reference_system::NOVAS_GCRS;
+        orbit.jd_tdb = value.epoch;
+        orbit.a = value.a;
+        orbit.e = value.e;
+        orbit.i = value.i;
+        orbit.Omega = value.node;
+        orbit.omega = value.peri;
+        orbit.M0 = value.mean_anomaly;
+        orbit.n = value.mean_motion;
+        orbit
+    }
+}
+
+/// A solar system body (asteroid, comet, ...) whose position is computed from its orbital elements
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrbitalObject {
+    name: String,
+    number: i64,
+    elements: OrbitalElements,
+}
+
+impl OrbitalObject {
+    /// Construct a new orbital object
+    ///
+    /// - name: The object name (or designation)
+    /// - number: The object number (e.g. the minor planet number), or 0 if unnumbered
+    /// - elements: The orbital elements of the body
+    pub fn new(name: &str, number: i64, elements: OrbitalElements) -> super::Result<Self> {
+        if name.len() as u32 > SIZE_OF_OBJ_NAME {
+            return Err(Error::InvalidString);
+        }
+        Ok(Self {
+            name: name.to_string(),
+            number,
+            elements,
+        })
+    }
+
+    /// The object name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The object number (0 if unnumbered)
+    pub fn number(&self) -> i64 {
+        self.number
+    }
+
+    /// The orbital elements of the body
+    pub fn elements(&self) -> &OrbitalElements {
+        &self.elements
+    }
+}
+
+impl Placeable for OrbitalObject {
+    fn object(&self) -> super::Result<Cow<'_, object>> {
+        let name = object_name(&self.name)?;
+        let orbit = novas_orbital::from(&self.elements);
+        let mut obj = MaybeUninit::uninit();
+        // Safety: The name is null-terminated within the NOVAS name size and the orbit struct is copied into the object
+        let obj = unsafe {
+            let ret = make_orbital_object(
+                name.as_ptr(),
+                self.number,
+                &orbit as *const _,
+                obj.as_mut_ptr(),
+            );
+            if ret != 0 {
+                return Err(Error::LowerLevel(ret));
+            }
+            obj.assume_init()
+        };
+        Ok(Cow::Owned(obj))
+    }
+}
+
+/// Truncate a name so that it fits in a NOVAS object (with its terminating null), respecting character boundaries
+pub(crate) fn truncate_name(name: &str) -> &str {
+    let len = SIZE_OF_OBJ_NAME as usize - 1;
+    if name.len() <= len {
+        return name;
+    }
+    let mut idx = len;
+    while !name.is_char_boundary(idx) {
+        idx -= 1;
+    }
+    &name[..idx]
+}
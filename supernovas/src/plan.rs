@@ -0,0 +1,229 @@
+//! Observation planning
+
+use crate::{
+    airmass::{airmass, AirmassModel},
+    events::{az_el, local_minima, sign_changes},
+    planets::Planet,
+    positions::{Frame, Observer, Placeable, ReferenceSystem, SkyPosition},
+    time::Timespec,
+    Accuracy,
+};
+
+/// An additional condition for a target to be observable
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Constraint {
+    /// The Sun must be below the given elevation in degrees (e.g. -18 for astronomical darkness)
+    SunBelow(f64),
+    /// The target must be at least this many degrees away from the Sun (e.g. a solar keep-out for daytime radio
+    /// observations or spacecraft)
+    SunSeparation(f64),
+    /// The target must be at least `min_deg` degrees away from the Moon, whenever more than `max_illumination`
+    /// (0-1) of the Moon's disk is illuminated
+    MoonSeparation { min_deg: f64, max_illumination: f64 },
+}
+
+impl Constraint {
+    /// How far (in degrees) the target is from violating the constraint in the given frame; negative if it does
+    pub fn margin(&self, frame: &Frame, target: &impl Placeable) -> super::Result<f64> {
+        Ok(match self {
+            Constraint::SunBelow(max) => {
+                let (_, sun_el) =
+                    frame.apparent_local_coordinates(ReferenceSystem::TOD, &Planet::Sun, None)?;
+                max - sun_el
+            }
+            Constraint::SunSeparation(min) => separation(frame, target, &Planet::Sun)? - min,
+            Constraint::MoonSeparation {
+                min_deg,
+                max_illumination,
+            } => {
+                // The illuminated fraction (1 + cos phase) / 2 falls monotonically with the phase angle, so compare
+                // phase angles to keep the margin in degrees
+                let min_phase = (2.0 * max_illumination.clamp(0.0, 1.0) - 1.0)
+                    .acos()
+                    .to_degrees();
+                let phase = frame.phase_angle(&Planet::Moon)?;
+                (separation(frame, target, &Planet::Moon)? - min_deg).max(phase - min_phase)
+            }
+        })
+    }
+
+    /// The time intervals in `[start, stop]` during which the constraint is violated for a target
+    ///
+    /// This works for any observer, including those in space. Violations shorter than about ten minutes may be
+    /// missed.
+    pub fn violations(
+        &self,
+        target: &impl Placeable,
+        observer: &Observer,
+        start: &Timespec,
+        stop: &Timespec,
+    ) -> super::Result<Vec<Window>> {
+        windows(start, stop, |time| {
+            let frame = Frame::new(Accuracy::Reduced, observer, time, 0.0, 0.0)?;
+            Ok(-self.margin(&frame, target)?)
+        })
+    }
+}
+
+/// The apparent angular separation in degrees between a target and a solar system body
+fn separation(frame: &Frame, target: &impl Placeable, body: &Planet) -> super::Result<f64> {
+    let pos = SkyPosition::try_from_frame(target, frame, ReferenceSystem::TOD)?;
+    let body = SkyPosition::try_from_frame(body, frame, ReferenceSystem::TOD)?;
+    Ok(pos.separation(&body))
+}
+
+/// An interval of time in which a target is observable (or a constraint is violated)
+#[derive(Debug, Clone)]
+pub struct Window {
+    /// The start of the window
+    pub start: Timespec,
+    /// The end of the window
+    pub stop: Timespec,
+}
+
+impl Window {
+    /// The length of the window in seconds
+    pub fn duration(&self) -> f64 {
+        self.stop.diff(&self.start)
+    }
+}
+
+/// How far (in degrees) a target is from violating the elevation limit and constraints; negative if it does
+fn margin(
+    target: &impl Placeable,
+    observer: &Observer,
+    time: &Timespec,
+    min_el: f64,
+    constraints: &[Constraint],
+) -> super::Result<f64> {
+    let (az, el) = az_el(target, observer, time, None)?;
+    // Without a horizon profile, the elevation limit may be below the astronomical horizon
+    let limit = match observer.horizon() {
+        Some(mask) => min_el.max(mask.min_elevation(az)),
+        None => min_el,
+    };
+    let mut margin = el - limit;
+    if constraints.is_empty() {
+        return Ok(margin);
+    }
+    let frame = Frame::new(Accuracy::Reduced, observer, time, 0.0, 0.0)?;
+    for constraint in constraints {
+        margin = margin.min(constraint.margin(&frame, target)?);
+    }
+    Ok(margin)
+}
+
+/// The time intervals in `[start, stop]` during which `f` is non-negative
+fn windows(
+    start: &Timespec,
+    stop: &Timespec,
+    f: impl Fn(&Timespec) -> super::Result<f64>,
+) -> super::Result<Vec<Window>> {
+    let mut window_start = (f(start)? >= 0.0).then_some(*start);
+    let mut windows = vec![];
+    for crossing in sign_changes(start, stop, f)? {
+        match (crossing.rising, window_start.take()) {
+            (true, _) => window_start = Some(crossing.time),
+            (false, Some(begin)) => windows.push(Window {
+                start: begin,
+                stop: crossing.time,
+            }),
+            (false, None) => (),
+        }
+    }
+    if let Some(begin) = window_start {
+        windows.push(Window {
+            start: begin,
+            stop: *stop,
+        });
+    }
+    Ok(windows)
+}
+
+/// The time intervals in `[start, stop]` during which a target is observable
+///
+/// A target is observable when it is above `min_el` (in degrees) and the observer's horizon profile, and all of
+/// the constraints are met. Windows shorter than about ten minutes may be missed.
+pub fn visibility(
+    target: &impl Placeable,
+    observer: &Observer,
+    start: &Timespec,
+    stop: &Timespec,
+    min_el: f64,
+    constraints: &[Constraint],
+) -> super::Result<Vec<Window>> {
+    windows(start, stop, |time| {
+        margin(target, observer, time, min_el, constraints)
+    })
+}
+
+/// Coarse sampling interval in seconds when searching for the best time
+const BEST_TIME_STEP: f64 = 600.0;
+
+/// The best time to observe a target within a window
+#[derive(Debug, Clone)]
+pub struct BestTime {
+    /// The time of maximum elevation
+    pub time: Timespec,
+    /// The (unrefracted) elevation at that time in degrees
+    pub el: f64,
+    /// The (Kasten & Young) airmass at that time, which is the minimum over the window
+    pub airmass: f64,
+}
+
+/// Finds the time of maximum elevation (equivalently, minimum airmass) in `[start, stop]` for each target
+///
+/// The maximum may be at either end of the window, if the target is rising or setting throughout.
+pub fn best_times<T: Placeable>(
+    targets: &[T],
+    observer: &Observer,
+    start: &Timespec,
+    stop: &Timespec,
+) -> super::Result<Vec<BestTime>> {
+    targets
+        .iter()
+        .map(|target| {
+            let elevation = |time: &Timespec| -> super::Result<f64> {
+                Ok(az_el(target, observer, time, None)?.1)
+            };
+            let mut candidates = local_minima(start, stop, BEST_TIME_STEP, |time| {
+                elevation(time).map(|el| -el)
+            })?
+            .into_iter()
+            .map(|(time, el)| (time, -el))
+            .collect::<Vec<_>>();
+            candidates.push((*start, elevation(start)?));
+            candidates.push((*stop, elevation(stop)?));
+            let (time, el) = candidates
+                .into_iter()
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .expect("the window ends are always candidates");
+            Ok(BestTime {
+                time,
+                el,
+                airmass: airmass(el, AirmassModel::KastenYoung),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::positions::{CatalogEntry, Weather};
+
+    #[test]
+    fn negative_elevation_limit() {
+        let observer = Observer::new_on_surface(37.0, 0.0, 0.0, Weather::default());
+        let star = CatalogEntry::new("star", "", 0, 6.0, 0.0, 0.0, 0.0, 0.0, 0.0).unwrap();
+        let time = Timespec::J2000;
+        let (_, el) = az_el(&star, &observer, &time, None).unwrap();
+        let below = margin(&star, &observer, &time, -5.0, &[]).unwrap();
+        assert!((below - (el + 5.0)).abs() < 1e-9);
+
+        // A horizon profile still raises the limit
+        let observer = observer.with_horizon(|_| 10.0);
+        let masked = margin(&star, &observer, &time, -5.0, &[]).unwrap();
+        assert!((masked - (el - 10.0)).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,104 @@
+//! Major solar-system bodies
+
+use crate::{error::Error, positions::Placeable};
+use std::{borrow::Cow, mem::MaybeUninit, str::FromStr};
+use supernovas_sys::{make_planet, novas_planet, object};
+
+/// The major planets, the Sun, the Moon, and the solar-system barycenter
+///
+/// Positions come from the configured planet ephemeris provider (the low-precision built-in one by default, or a
+/// JPL ephemeris through [`crate::ephem`]).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Planet {
+    /// The solar-system barycenter
+    SSB,
+    Mercury,
+    Venus,
+    Earth,
+    Mars,
+    Jupiter,
+    Saturn,
+    Uranus,
+    Neptune,
+    Pluto,
+    Sun,
+    Moon,
+}
+
+impl Planet {
+    /// The (IAU 2015 nominal) equatorial radius of the body in km, if it is a physical body
+    pub fn radius(&self) -> Option<f64> {
+        match self {
+            Planet::SSB => None,
+            Planet::Mercury => Some(2_440.53),
+            Planet::Venus => Some(6_051.8),
+            Planet::Earth => Some(6_378.137),
+            Planet::Mars => Some(3_396.19),
+            Planet::Jupiter => Some(71_492.0),
+            Planet::Saturn => Some(60_268.0),
+            Planet::Uranus => Some(25_559.0),
+            Planet::Neptune => Some(24_764.0),
+            Planet::Pluto => Some(1_188.3),
+            Planet::Sun => Some(695_700.0),
+            Planet::Moon => Some(1_737.4),
+        }
+    }
+}
+
+impl FromStr for Planet {
+    type Err = Error;
+
+    /// Parse a body from its (case-insensitive) English name
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let planet = match s.trim().to_ascii_lowercase().as_str() {
+            "ssb" | "barycenter" => Planet::SSB,
+            "mercury" => Planet::Mercury,
+            "venus" => Planet::Venus,
+            "earth" => Planet::Earth,
+            "mars" => Planet::Mars,
+            "jupiter" => Planet::Jupiter,
+            "saturn" => Planet::Saturn,
+            "uranus" => Planet::Uranus,
+            "neptune" => Planet::Neptune,
+            "pluto" => Planet::Pluto,
+            "sun" => Planet::Sun,
+            "moon" => Planet::Moon,
+            _ => return Err(Error::NotFound(s.to_string())),
+        };
+        Ok(planet)
+    }
+}
+
+impl From<Planet> for novas_planet {
+    fn from(value: Planet) -> Self {
+        match value {
+            Planet::SSB => novas_planet::NOVAS_SSB,
+            Planet::Mercury => novas_planet::NOVAS_MERCURY,
+            Planet::Venus => novas_planet::NOVAS_VENUS,
+            Planet::Earth => novas_planet::NOVAS_EARTH,
+            Planet::Mars => novas_planet::NOVAS_MARS,
+            Planet::Jupiter => novas_planet::NOVAS_JUPITER,
+            Planet::Saturn => novas_planet::NOVAS_SATURN,
+            Planet::Uranus => novas_planet::NOVAS_URANUS,
+            Planet::Neptune => novas_planet::NOVAS_NEPTUNE,
+            Planet::Pluto => novas_planet::NOVAS_PLUTO,
+            Planet::Sun => novas_planet::NOVAS_SUN,
+            Planet::Moon => novas_planet::NOVAS_MOON,
+        }
+    }
+}
+
+impl Placeable for Planet {
+    fn object(&self) -> super::Result<Cow<'_, object>> {
+        let mut obj = MaybeUninit::uninit();
+        // Safety: The planet number is always valid and the object pointer is not null
+        let obj = unsafe {
+            let ret = make_planet((*self).into(), obj.as_mut_ptr());
+            if ret != 0 {
+                return Err(Error::LowerLevel(ret));
+            }
+            obj.assume_init()
+        };
+        Ok(Cow::Owned(obj))
+    }
+}
@@ -0,0 +1,162 @@
+//! Telescope pointing models
+//!
+//! A [`PointingModel`] holds the coefficients of the standard (TPOINT-style) alt-az pointing terms. It maps the
+//! observed local coordinates of a source (e.g. from
+//! [`Frame::apparent_local_coordinates`](crate::positions::Frame::apparent_local_coordinates) with refraction) to
+//! the mount coordinates that actually point at it, and can be fit from a set of pointing observations.
+
+use crate::error::Error;
+
+/// Arcseconds per degree
+const ARCSEC: f64 = 3600.0;
+
+/// Number of terms in the model
+const N_TERMS: usize = 7;
+
+/// The coefficients (in arcseconds) of an alt-az pointing model
+///
+/// The corrections in azimuth (ΔA) and elevation (ΔE) that are added to the observed coordinates are
+/// - IA: Azimuth index error, ΔA = -IA
+/// - IE: Elevation index error, ΔE = +IE
+/// - NPAE: Non-perpendicularity of the azimuth and elevation axes, ΔA = -NPAE tan E
+/// - CA: Collimation error (non-perpendicularity of the optical and elevation axes), ΔA = -CA sec E
+/// - AN: North-south misalignment of the azimuth axis, ΔA = -AN sin A tan E, ΔE = -AN cos A
+/// - AW: East-west misalignment of the azimuth axis, ΔA = -AW cos A tan E, ΔE = +AW sin A
+/// - TF: Tube flexure, ΔE = -TF cos E
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct PointingModel {
+    pub ia: f64,
+    pub ie: f64,
+    pub npae: f64,
+    pub ca: f64,
+    pub an: f64,
+    pub aw: f64,
+    pub tf: f64,
+}
+
+/// A single pointing observation: where a source was predicted to be, and where the mount found it
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PointingSample {
+    /// The observed (refracted) azimuth of the source in degrees
+    pub az: f64,
+    /// The observed (refracted) elevation of the source in degrees
+    pub el: f64,
+    /// The mount azimuth in degrees at which the source was centered
+    pub mount_az: f64,
+    /// The mount elevation in degrees at which the source was centered
+    pub mount_el: f64,
+}
+
+impl PointingModel {
+    /// The partial derivatives of (ΔA, ΔE) with respect to each coefficient, in the order of the struct fields
+    fn design(az: f64, el: f64) -> ([f64; N_TERMS], [f64; N_TERMS]) {
+        let (a, e) = (az.to_radians(), el.to_radians());
+        let (tan_e, sec_e) = (e.tan(), 1.0 / e.cos());
+        (
+            [
+                -1.0,
+                0.0,
+                -tan_e,
+                -sec_e,
+                -a.sin() * tan_e,
+                -a.cos() * tan_e,
+                0.0,
+            ],
+            [0.0, 1.0, 0.0, 0.0, -a.cos(), a.sin(), -e.cos()],
+        )
+    }
+
+    fn coefficients(&self) -> [f64; N_TERMS] {
+        [
+            self.ia, self.ie, self.npae, self.ca, self.an, self.aw, self.tf,
+        ]
+    }
+
+    /// The corrections (ΔA, ΔE) in degrees at the given coordinates
+    pub fn offsets(&self, az: f64, el: f64) -> (f64, f64) {
+        let (da, de) = Self::design(az, el);
+        let c = self.coefficients();
+        let dot = |d: &[f64; N_TERMS]| d.iter().zip(&c).map(|(d, c)| d * c).sum::<f64>();
+        (dot(&da) / ARCSEC, dot(&de) / ARCSEC)
+    }
+
+    /// The mount coordinates (az,el in degrees) that point at a source at the given observed coordinates
+    pub fn apply(&self, az: f64, el: f64) -> (f64, f64) {
+        let (da, de) = self.offsets(az, el);
+        ((az + da).rem_euclid(360.0), el + de)
+    }
+
+    /// The observed coordinates (az,el in degrees) a mount at the given coordinates points at, the inverse of
+    /// [`PointingModel::apply`]
+    pub fn remove(&self, mount_az: f64, mount_el: f64) -> (f64, f64) {
+        // The corrections vary slowly, so evaluating them at the current estimate converges quickly
+        let (mut az, mut el) = (mount_az, mount_el);
+        for _ in 0..5 {
+            let (da, de) = self.offsets(az, el);
+            az = (mount_az - da).rem_euclid(360.0);
+            el = mount_el - de;
+        }
+        (az, el)
+    }
+
+    /// Fit the model coefficients to a set of pointing observations by linear least squares
+    ///
+    /// At least four well-distributed observations are needed (each gives two equations).
+    pub fn fit(samples: &[PointingSample]) -> super::Result<Self> {
+        if samples.len() < N_TERMS.div_ceil(2) {
+            return Err(Error::InvalidValue("pointing samples"));
+        }
+        // Accumulate the normal equations
+        let mut ata = [[0.0; N_TERMS]; N_TERMS];
+        let mut atb = [0.0; N_TERMS];
+        for sample in samples {
+            let (da, de) = Self::design(sample.az, sample.el);
+            // Unwrap the azimuth residual across north
+            let res_a = ((sample.mount_az - sample.az + 540.0).rem_euclid(360.0) - 180.0) * ARCSEC;
+            let res_e = (sample.mount_el - sample.el) * ARCSEC;
+            for (row, res) in [(da, res_a), (de, res_e)] {
+                for (i, r_i) in row.iter().enumerate() {
+                    atb[i] += r_i * res;
+                    for (j, r_j) in row.iter().enumerate() {
+                        ata[i][j] += r_i * r_j;
+                    }
+                }
+            }
+        }
+        let c = solve(ata, atb).ok_or(Error::InvalidValue("pointing samples"))?;
+        Ok(Self {
+            ia: c[0],
+            ie: c[1],
+            npae: c[2],
+            ca: c[3],
+            an: c[4],
+            aw: c[5],
+            tf: c[6],
+        })
+    }
+}
+
+/// Solve a small linear system by Gaussian elimination with partial pivoting, returning `None` if it is singular
+fn solve<const N: usize>(mut a: [[f64; N]; N], mut b: [f64; N]) -> Option<[f64; N]> {
+    for col in 0..N {
+        let pivot = (col..N).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in col + 1..N {
+            let factor = a[row][col] / a[col][col];
+            for k in col..N {
+                a[row][k] -= factor * a[col][k];
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+    let mut x = [0.0; N];
+    for row in (0..N).rev() {
+        let sum = (row + 1..N).map(|k| a[row][k] * x[k]).sum::<f64>();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
@@ -1,24 +1,71 @@
 //! Routines for computing positions of local and astronomical objects
 
-use crate::{error::Error, time::Timespec, Accuracy};
+#[cfg(feature = "sgp4")]
+use crate::satellite::Satellite;
+use crate::{
+    airmass::{airmass, AirmassModel},
+    coords::{Declination, Dms, RightAscension},
+    doppler::AU,
+    eop::{eop_table, EopTable},
+    error::Error,
+    geodesy::{ecef_to_geodetic, Ellipsoid},
+    horizon::HorizonMask,
+    obscodes::ObservatoryCodes,
+    planets::Planet,
+    refraction::RefractionModel,
+    sites,
+    time::{IntoTimespec, Timescale, Timespec, MJD0},
+    uncertainty::{error_ellipse, ErrorEllipse, Uncertainty},
+    Accuracy,
+};
 use std::{
-    ffi::{CStr, CString},
+    borrow::Cow,
+    ffi::{c_char, CStr, CString},
     fmt::Debug,
-    marker::PhantomData,
     mem::MaybeUninit,
     ptr::null,
+    sync::Arc,
 };
 use supernovas_sys::{
-    cat_entry, make_cat_entry, make_cat_object, make_observer_at_geocenter, make_observer_in_space,
-    make_observer_on_surface, novas_accuracy, novas_app_to_hor, novas_frame, novas_make_frame,
-    novas_reference_system, novas_sky_pos, novas_transform_type, observer, place_star, sky_pos,
-    transform_cat, SIZE_OF_CAT_NAME, SIZE_OF_OBJ_NAME,
+    cat_entry, geo_posvel, limb_angle, make_cat_entry, make_cat_object, make_observer_at_geocenter,
+    make_observer_in_space, make_observer_on_surface, novas_accuracy, novas_app_to_geom,
+    novas_app_to_hor, novas_frame, novas_geom_posvel, novas_geom_to_app, novas_hor_to_app,
+    novas_invert_transform, novas_make_frame, novas_make_transform, novas_reference_system,
+    novas_sky_pos, novas_transform, novas_transform_sky_pos, novas_transform_type,
+    novas_transform_vector, object, observer, place_star, sky_pos, starvectors, terra,
+    transform_cat, NOVAS_JD_J2000, SIZE_OF_CAT_NAME, SIZE_OF_OBJ_NAME,
 };
 
+/// The rotation rate of the Earth in degrees per (SI) second
+const EARTH_ROTATION_RATE: f64 = 7.292_115_146_7e-5 * 180.0 / std::f64::consts::PI;
+
 /// An observer position
 pub struct Observer {
     location: ObserverLocation,
-    inner: observer,
+    horizon: Option<Arc<dyn HorizonMask>>,
+    pub(crate) inner: observer,
+}
+
+/// The local weather at a surface observer, as used by the refraction models
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Weather {
+    /// Temperature in celsius
+    pub temperature_c: f64,
+    /// Pressure in mBar
+    pub pressure_mbar: f64,
+    /// Relative humidity in percent
+    pub humidity: f64,
+}
+
+impl Default for Weather {
+    /// Standard conditions: 10 °C, 1010 mBar, and 50% humidity
+    fn default() -> Self {
+        Self {
+            temperature_c: 10.0,
+            pressure_mbar: 1010.0,
+            humidity: 50.0,
+        }
+    }
 }
 
 /// The position an observer can be
@@ -37,21 +84,52 @@ impl Observer {
     /// - lat: Geodetic (ITRS) latitude in degrees; north positive
     /// - lon: Geodetic (ITRS) longitude in degrees; east positive
     /// - elev: Altidude above sea level in meters
-    /// - temp: Temperature in celsius
-    /// - pressure: Pressure in mBar
-    pub fn new_on_surface(lat: f64, lon: f64, elev: f64, temp: f64, pressure: f64) -> Self {
+    /// - weather: The local weather, used by the refraction models
+    pub fn new_on_surface(lat: f64, lon: f64, elev: f64, weather: Weather) -> Self {
         let mut obs_loc = MaybeUninit::uninit();
         // Safety: The pointer to the obs_loc will never be null, and that is the only situation where this would error
         let _ = unsafe {
-            make_observer_on_surface(lat, lon, elev, temp, pressure, obs_loc.as_mut_ptr())
+            make_observer_on_surface(
+                lat,
+                lon,
+                elev,
+                weather.temperature_c,
+                weather.pressure_mbar,
+                obs_loc.as_mut_ptr(),
+            )
         };
         // Safety: The above initialization is garunteed to succeed, so this is init
+        let mut inner: observer = unsafe { obs_loc.assume_init() };
+        inner.on_surf.humidity = weather.humidity;
         Self {
             location: ObserverLocation::Surface,
-            inner: unsafe { obs_loc.assume_init() },
+            horizon: None,
+            inner,
         }
     }
 
+    /// Construct a new [`Observer`] at an observatory from the built-in site database, with standard weather
+    ///
+    /// Sites are looked up by key (e.g. "OVRO") or full name, case-insensitively.
+    pub fn from_site(name: &str) -> super::Result<Self> {
+        Ok(sites::lookup(name)?.observer(Weather::default()))
+    }
+
+    /// Construct a new [`Observer`] at a Minor Planet Center observatory code (e.g. "G96"), with standard weather
+    pub fn from_mpc_code(code: &str, codes: &ObservatoryCodes) -> super::Result<Self> {
+        codes.get(code)?.observer(Weather::default())
+    }
+
+    /// Construct a new [`Observer`] on the surface of the earth from its ECEF coordinates
+    ///
+    /// - xyz: Earth-centered, Earth-fixed (X,Y,Z) position in meters
+    /// - ellipsoid: The reference ellipsoid the coordinates are given for
+    /// - weather: The local weather, used by the refraction models
+    pub fn from_ecef(xyz: &[f64; 3], ellipsoid: Ellipsoid, weather: Weather) -> Self {
+        let (lat, lon, height) = ecef_to_geodetic(xyz, ellipsoid);
+        Self::new_on_surface(lat, lon, height, weather)
+    }
+
     /// Construct a new [`Observer`] at the Earth's geocenter
     ///
     /// - pos: (x,y,z) position in km
@@ -63,10 +141,21 @@ impl Observer {
         // Safety: The above initialization is garunteed to succeed, so this is init
         Self {
             location: ObserverLocation::Space,
+            horizon: None,
             inner: unsafe { obs_loc.assume_init() },
         }
     }
 
+    /// Construct a new near-Earth [`Observer`] in space, on board a satellite described by a two-line element set
+    ///
+    /// The geocentric position and velocity are propagated with SGP4 to the given time, so the observer is only valid
+    /// for frames near that time.
+    #[cfg(feature = "sgp4")]
+    pub fn from_tle(line1: &str, line2: &str, time: &Timespec) -> super::Result<Self> {
+        let (pos, vel) = Satellite::from_tle(None, line1, line2)?.gcrs_state(time)?;
+        Ok(Self::new_in_space(&pos, &vel))
+    }
+
     /// Construct a new [`Observer`] at the Earth's geocenter
     pub fn new_at_geocenter() -> Self {
         let mut obs_loc = MaybeUninit::uninit();
@@ -75,9 +164,118 @@ impl Observer {
         // Safety: The above initialization is garunteed to succeed, so this is init
         Self {
             location: ObserverLocation::Geocenter,
+            horizon: None,
             inner: unsafe { obs_loc.assume_init() },
         }
     }
+
+    /// Geodetic latitude in degrees of a surface observer
+    pub fn latitude(&self) -> f64 {
+        self.inner.on_surf.latitude
+    }
+
+    /// Geodetic longitude in degrees of a surface observer
+    pub fn longitude(&self) -> f64 {
+        self.inner.on_surf.longitude
+    }
+
+    /// Altitude above sea level in meters of a surface observer
+    pub fn height(&self) -> f64 {
+        self.inner.on_surf.height
+    }
+
+    /// The local weather of a surface observer
+    pub fn weather(&self) -> Option<Weather> {
+        self.is_on_surface().then(|| Weather {
+            temperature_c: self.inner.on_surf.temperature,
+            pressure_mbar: self.inner.on_surf.pressure,
+            humidity: self.inner.on_surf.humidity,
+        })
+    }
+
+    /// Update the local weather of a surface observer (which is ignored for other observers)
+    pub fn set_weather(&mut self, weather: Weather) {
+        if self.is_on_surface() {
+            self.inner.on_surf.temperature = weather.temperature_c;
+            self.inner.on_surf.pressure = weather.pressure_mbar;
+            self.inner.on_surf.humidity = weather.humidity;
+        }
+    }
+
+    /// Whether this observer is on the surface of the Earth (and so subject to refraction)
+    pub fn is_on_surface(&self) -> bool {
+        matches!(self.location, ObserverLocation::Surface)
+    }
+
+    /// The position (AU) and velocity (AU/day) of a surface observer relative to the geocenter, in the true equator
+    /// and equinox of date frame, given the apparent sidereal time at the Greenwich meridian (in hours)
+    ///
+    /// The observer's longitude is added to `lst` internally, so with `lst` set to the Greenwich apparent sidereal
+    /// time this is the TOD position, and with `lst = 0` it is the Earth-fixed (ITRS, without polar motion)
+    /// position. The velocity is that due to the Earth's rotation.
+    pub fn terra(&self, lst: f64) -> super::Result<([f64; 3], [f64; 3])> {
+        if !self.is_on_surface() {
+            return Err(Error::InvalidValue("observer"));
+        }
+        let mut pos = [0.0; 3];
+        let mut vel = [0.0; 3];
+        // Safety: The observer is initialized and the outputs are valid 3-vectors
+        let ret = unsafe {
+            terra(
+                &self.inner.on_surf as *const _,
+                lst,
+                pos.as_mut_ptr(),
+                vel.as_mut_ptr(),
+            )
+        };
+        if ret != 0 {
+            return Err(Error::LowerLevel(ret));
+        }
+        Ok((pos, vel))
+    }
+
+    /// The geocentric GCRS position (AU) and velocity (AU/day) of the observer at the given time
+    pub fn geo_posvel(
+        &self,
+        time: &Timespec,
+        acc: Accuracy,
+    ) -> super::Result<([f64; 3], [f64; 3])> {
+        let mut pos = [0.0; 3];
+        let mut vel = [0.0; 3];
+        // Safety: The observer is initialized and the outputs are valid 3-vectors
+        let ret = unsafe {
+            geo_posvel(
+                time.jd(Timescale::TT),
+                time.0.ut1_to_tt,
+                acc.into(),
+                &self.inner as *const _,
+                pos.as_mut_ptr(),
+                vel.as_mut_ptr(),
+            )
+        };
+        if ret != 0 {
+            return Err(Error::LowerLevel(ret));
+        }
+        Ok((pos, vel))
+    }
+
+    /// Attach a site horizon profile, which visibility calculations will respect
+    pub fn with_horizon(mut self, mask: impl HorizonMask + 'static) -> Self {
+        self.horizon = Some(Arc::new(mask));
+        self
+    }
+
+    /// The attached horizon profile, if any
+    pub fn horizon(&self) -> Option<&dyn HorizonMask> {
+        self.horizon.as_deref()
+    }
+
+    /// The minimum elevation in degrees at azimuth `az` in degrees, i.e. 0° without a horizon profile
+    pub fn min_elevation(&self, az: f64) -> f64 {
+        self.horizon
+            .as_ref()
+            .map_or(0.0, |mask| mask.min_elevation(az))
+    }
 }
 
 // Spoof the debug print for the inner struct
@@ -89,11 +287,12 @@ impl Debug for Observer {
             .field("elevation", &self.inner.on_surf.height)
             .field("temperature", &self.inner.on_surf.temperature)
             .field("pressure", &self.inner.on_surf.pressure)
+            .field("humidity", &self.inner.on_surf.humidity)
             .finish()
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone)]
 /// Coordinate transformations for [`CatEntry::transform`]
 pub enum Transformation {
     /// Update the catalog entry to account for proper motion between two dates in a fixed frame
@@ -120,8 +319,82 @@ impl From<Transformation> for novas_transform_type {
     }
 }
 
+/// Sources that can be placed on the sky within a [`Frame`]
+pub trait Placeable {
+    /// Construct the underlying NOVAS object for this source
+    #[doc(hidden)]
+    fn object(&self) -> super::Result<Cow<'_, object>>;
+
+    /// The 1σ error ellipse of the source's place in the frame, if its astrometric uncertainties are known
+    fn error_ellipse(&self, _frame: &Frame) -> Option<ErrorEllipse> {
+        None
+    }
+}
+
+/// A source whose NOVAS object is built once up front, for placing repeatedly in tight tracking loops
+pub struct CatObject {
+    inner: object,
+}
+
+impl CatObject {
+    /// Build (and cache) the NOVAS object for a source
+    pub fn new(target: &impl Placeable) -> super::Result<Self> {
+        Ok(Self {
+            inner: target.object()?.into_owned(),
+        })
+    }
+}
+
+impl Placeable for CatObject {
+    fn object(&self) -> super::Result<Cow<'_, object>> {
+        Ok(Cow::Borrowed(&self.inner))
+    }
+}
+
+/// The reference system and epoch that the coordinates of a [`CatalogEntry`] are given in
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CatalogSystem {
+    /// The ICRS, with the positions at the given epoch (TT Julian date)
+    ICRS { epoch: f64 },
+    /// The dynamical mean equator and equinox of `equinox` (e.g. FK5 J2000), with the positions at `epoch` (both TT
+    /// Julian dates)
+    MeanEquinox { equinox: f64, epoch: f64 },
+}
+
+impl Default for CatalogSystem {
+    /// ICRS at J2000.0, which is what most modern catalogs (and SIMBAD) provide
+    fn default() -> Self {
+        Self::ICRS {
+            epoch: NOVAS_JD_J2000,
+        }
+    }
+}
+
+impl CatalogSystem {
+    /// The epoch of the positions as a TT Julian date
+    pub fn epoch(&self) -> f64 {
+        match self {
+            Self::ICRS { epoch } | Self::MeanEquinox { epoch, .. } => *epoch,
+        }
+    }
+
+    /// The same reference system with the positions at another epoch
+    fn with_epoch(self, epoch: f64) -> Self {
+        match self {
+            Self::ICRS { .. } => Self::ICRS { epoch },
+            Self::MeanEquinox { equinox, .. } => Self::MeanEquinox { equinox, epoch },
+        }
+    }
+}
+
 /// Astronmetric data for any sidereal object located outside the solar system
-pub struct CatalogEntry(pub cat_entry);
+#[derive(Clone)]
+pub struct CatalogEntry {
+    pub(crate) inner: cat_entry,
+    system: CatalogSystem,
+    /// The astrometric uncertainties and the epoch (TT Julian date) the position uncertainties apply at
+    pub(crate) uncertainty: Option<(Uncertainty, f64)>,
+}
 
 impl CatalogEntry {
     /// Construct a new catalog entry
@@ -135,6 +408,8 @@ impl CatalogEntry {
     /// - pm_dec: Proper motion in declination in mas/yr
     /// - parallax: Parallax in mas
     /// - rad_vel: Radial velocity of the object in km/s
+    ///
+    /// All values must be finite, with the RA in [0, 24), the declination in [-90, 90], and a non-negative parallax.
     pub fn new(
         name: &str,
         catalog: &str,
@@ -146,6 +421,12 @@ impl CatalogEntry {
         parallax: f64,
         rad_vel: f64,
     ) -> super::Result<Self> {
+        let ra = check_ra(ra)?;
+        let dec = check_dec(dec)?;
+        let pm_ra = finite("pm_ra", pm_ra)?;
+        let pm_dec = finite("pm_dec", pm_dec)?;
+        let parallax = check_parallax(parallax)?;
+        let rad_vel = finite("rad_vel", rad_vel)?;
         // Check string sizes
         if name.len() as u32 > SIZE_OF_OBJ_NAME {
             return Err(Error::InvalidString);
@@ -174,28 +455,168 @@ impl CatalogEntry {
             );
             entry.assume_init()
         };
-        Ok(Self(entry))
+        Ok(Self {
+            inner: entry,
+            system: CatalogSystem::default(),
+            uncertainty: None,
+        })
+    }
+
+    /// Start building a catalog entry at the given position (ra in hours, dec in degrees)
+    ///
+    /// Everything else defaults to zero (no catalog, no space motion), in the ICRS at J2000.0.
+    pub fn builder(name: &str, ra: f64, dec: f64) -> CatalogEntryBuilder<'_> {
+        CatalogEntryBuilder {
+            name,
+            catalog: "",
+            num: 0,
+            ra,
+            dec,
+            pm_ra: 0.0,
+            pm_dec: 0.0,
+            parallax: 0.0,
+            rad_vel: 0.0,
+            system: CatalogSystem::default(),
+            uncertainty: None,
+        }
+    }
+
+    /// Start building a catalog entry at the given position, see [`CatalogEntry::builder`]
+    pub fn builder_at(name: &str, ra: RightAscension, dec: Declination) -> CatalogEntryBuilder<'_> {
+        Self::builder(name, ra.hours(), dec.degrees())
     }
 
     /// Construct a new CatalogEntry from ra and dec in HMS, DMS instead of fracional hour and degree
+    ///
+    /// The declination carries its own sign, see [`Dms`].
     pub fn new_hms(
         name: &str,
         catalog: &str,
         num: i64,
         ra: (u8, u8, f64),
-        dec: (u16, u16, f64),
+        dec: Dms,
         pm_ra: f64,
         pm_dec: f64,
         parallax: f64,
         rad_vel: f64,
     ) -> super::Result<Self> {
         let ra = (ra.0 as f64) + (ra.1 as f64) / 60.0 + (ra.2 as f64) / 3600.0;
-        let dec = (dec.0 as f64) + (dec.1 as f64) / 60.0 + (dec.2 as f64) / 3600.0;
+        let dec = dec.degrees();
         Self::new(
             name, catalog, num, ra, dec, pm_ra, pm_dec, parallax, rad_vel,
         )
     }
 
+    /// A copy of this entry propagated to the epoch `jd_tt` (TT Julian date) with its space motion
+    ///
+    /// The entry is propagated from the epoch of its [`CatalogSystem`], and the proper motion, parallax, and radial
+    /// velocity are all accounted for. The original entry is left unchanged.
+    pub fn at_epoch(&self, jd_tt: f64) -> super::Result<Self> {
+        let mut out = MaybeUninit::uninit();
+        // Safety: The entry is initialized and a null catalog name keeps the original one
+        let ret = unsafe {
+            transform_cat(
+                novas_transform_type::PROPER_MOTION,
+                self.system.epoch(),
+                &self.inner as *const _,
+                jd_tt,
+                null(),
+                out.as_mut_ptr(),
+            )
+        };
+        if ret != 0 {
+            return Err(Error::LowerLevel(ret));
+        }
+        // Safety: The transformation succeeded, so the output is initialized
+        Ok(Self {
+            inner: unsafe { out.assume_init() },
+            system: self.system.with_epoch(jd_tt),
+            uncertainty: self.uncertainty,
+        })
+    }
+
+    /// The reference system and epoch of the coordinates
+    pub fn system(&self) -> CatalogSystem {
+        self.system
+    }
+
+    /// Record the reference system and epoch the coordinates are given in, without changing them
+    ///
+    /// Entries default to ICRS at J2000.0.
+    pub fn with_system(mut self, system: CatalogSystem) -> Self {
+        self.system = system;
+        self
+    }
+
+    /// Record the 1σ astrometric uncertainties of the entry, with the position uncertainties at the epoch of its
+    /// current [`CatalogSystem`]
+    ///
+    /// The uncertainties follow the entry through epoch changes, and are propagated into the
+    /// [error ellipse](SkyPosition::error_ellipse) of its places.
+    pub fn with_uncertainty(mut self, uncertainty: Uncertainty) -> Self {
+        self.uncertainty = Some((uncertainty, self.system.epoch()));
+        self
+    }
+
+    /// The 1σ astrometric uncertainties, with the position uncertainties propagated to the epoch of the entry's
+    /// [`CatalogSystem`]
+    pub fn uncertainty(&self) -> Option<Uncertainty> {
+        self.uncertainty
+            .map(|(uncertainty, epoch)| uncertainty.at_epoch(epoch, self.system.epoch()))
+    }
+
+    /// A copy of this entry converted into another reference system and epoch
+    ///
+    /// The conversion goes through the ICRS: the entry is precessed to J2000 and rotated into the ICRS (if needed),
+    /// propagated to the new epoch, then rotated and precessed into the new system.
+    pub fn to_system(&self, system: CatalogSystem) -> super::Result<Self> {
+        let mut entry = self.clone();
+        if let CatalogSystem::MeanEquinox { equinox, .. } = entry.system {
+            if equinox != NOVAS_JD_J2000 {
+                entry.transform(
+                    Transformation::Precession {
+                        jd_tt_in: equinox,
+                        jd_tt_out: NOVAS_JD_J2000,
+                    },
+                    None,
+                )?;
+            }
+            entry.transform(Transformation::J2000ToICRS, None)?;
+        }
+        if entry.system.epoch() != system.epoch() {
+            entry = entry.at_epoch(system.epoch())?;
+        }
+        if let CatalogSystem::MeanEquinox { equinox, .. } = system {
+            entry.transform(Transformation::ICRSToJ2000, None)?;
+            if equinox != NOVAS_JD_J2000 {
+                entry.transform(
+                    Transformation::Precession {
+                        jd_tt_in: NOVAS_JD_J2000,
+                        jd_tt_out: equinox,
+                    },
+                    None,
+                )?;
+            }
+        }
+        Ok(entry)
+    }
+
+    /// The Cartesian barycentric position (AU) and space velocity (AU/day) of the star, in the reference system of
+    /// the catalog
+    ///
+    /// Without a parallax, the star is placed at a large fixed distance (as NOVAS does).
+    pub fn star_vectors(&self) -> super::Result<([f64; 3], [f64; 3])> {
+        let mut pos = [0.0; 3];
+        let mut vel = [0.0; 3];
+        // Safety: The entry is initialized and the output vectors are valid 3-vectors
+        let ret =
+            unsafe { starvectors(&self.inner as *const _, pos.as_mut_ptr(), vel.as_mut_ptr()) };
+        if ret != 0 {
+            return Err(Error::LowerLevel(ret));
+        }
+        Ok((pos, vel))
+    }
+
     /// Transform this catalog entry into another coordinate system with an optional new catalog name
     ///
     /// See docs on constraints [here](https://smithsonian.github.io/SuperNOVAS/apidoc/html/novas_8h.html#a59caeca70d1fdd02e41ed62f20675e6c)
@@ -204,6 +625,25 @@ impl CatalogEntry {
         transformation: Transformation,
         new_cat: Option<String>,
     ) -> super::Result<()> {
+        // Keep track of the resulting reference system
+        let system = match transformation {
+            Transformation::ProperMotion { jd_tt_out, .. } => self.system.with_epoch(jd_tt_out),
+            Transformation::Precession { jd_tt_out, .. } => CatalogSystem::MeanEquinox {
+                equinox: jd_tt_out,
+                epoch: self.system.epoch(),
+            },
+            Transformation::ChangeEpoch { jd_tt_out, .. } => CatalogSystem::MeanEquinox {
+                equinox: jd_tt_out,
+                epoch: jd_tt_out,
+            },
+            Transformation::J2000ToICRS => CatalogSystem::ICRS {
+                epoch: self.system.epoch(),
+            },
+            Transformation::ICRSToJ2000 => CatalogSystem::MeanEquinox {
+                equinox: NOVAS_JD_J2000,
+                epoch: self.system.epoch(),
+            },
+        };
         // Create the jd_tt_in and out values
         let (jd_tt_in, jd_tt_out) = match transformation {
             Transformation::ProperMotion {
@@ -221,67 +661,378 @@ impl CatalogEntry {
             Transformation::J2000ToICRS => (0.0, 0.0),
             Transformation::ICRSToJ2000 => (0.0, 0.0),
         };
-        // Deal with catalog name (if it exists)
-        let out_id;
-        if let Some(catalog) = new_cat {
-            if catalog.len() as u32 > SIZE_OF_CAT_NAME {
-                return Err(Error::InvalidString);
-            } else {
-                let new_cat_c = CString::new(catalog).map_err(|_| Error::InvalidString)?;
-                out_id = new_cat_c.as_ptr();
+        // Deal with catalog name (if it exists), keeping the C string alive until the call
+        let new_cat = match new_cat {
+            Some(catalog) if catalog.len() as u32 > SIZE_OF_CAT_NAME => {
+                return Err(Error::InvalidString)
             }
-        } else {
-            out_id = null();
-        }
-        // Safety: We've checked the length of the string already and the arguments will not be null
-        unsafe {
-            let _ = transform_cat(
+            Some(catalog) => Some(CString::new(catalog).map_err(|_| Error::InvalidString)?),
+            None => None,
+        };
+        let out_id = new_cat.as_ref().map_or(null(), |catalog| catalog.as_ptr());
+        // The entry is transformed in place, which NOVAS allows
+        let inner = &mut self.inner as *mut _;
+        // Safety: We've checked the length of the string already, it outlives the call, and the entry is initialized
+        let ret = unsafe {
+            transform_cat(
                 transformation.into(),
                 jd_tt_in,
-                &self.0 as *const _,
+                inner as *const _,
                 jd_tt_out,
                 out_id,
-                &mut self.0 as *mut _,
-            );
+                inner,
+            )
+        };
+        if ret != 0 {
+            return Err(Error::LowerLevel(ret));
         }
+        // Only relabel the entry once its coordinates are actually in the new system
+        self.system = system;
         Ok(())
     }
 }
 
+impl CatalogEntry {
+    /// The object name
+    pub fn name(&self) -> &str {
+        // Safety: We only ever write null-terminated strings into this field
+        let name = unsafe { CStr::from_ptr(self.inner.starname.as_ptr()) };
+        name.to_str().unwrap_or_default()
+    }
+
+    /// The catalog identifier
+    pub fn catalog(&self) -> &str {
+        // Safety: We only ever write null-terminated strings into this field
+        let catalog = unsafe { CStr::from_ptr(self.inner.catalog.as_ptr()) };
+        catalog.to_str().unwrap_or_default()
+    }
+
+    /// The object number in the catalog
+    pub fn number(&self) -> i64 {
+        self.inner.starnumber
+    }
+
+    /// Right ascension in hours
+    pub fn ra(&self) -> f64 {
+        self.inner.ra
+    }
+
+    /// Declination in degrees
+    pub fn dec(&self) -> f64 {
+        self.inner.dec
+    }
+
+    /// Right ascension as a typed angle
+    pub fn right_ascension(&self) -> RightAscension {
+        // Entries are validated on construction, so this is always in range
+        RightAscension(self.inner.ra)
+    }
+
+    /// Declination as a typed angle
+    pub fn declination(&self) -> Declination {
+        // Entries are validated on construction, so this is always in range
+        Declination(self.inner.dec)
+    }
+
+    /// Proper motion in right ascension in mas/yr
+    pub fn pm_ra(&self) -> f64 {
+        self.inner.promora
+    }
+
+    /// Proper motion in declination in mas/yr
+    pub fn pm_dec(&self) -> f64 {
+        self.inner.promodec
+    }
+
+    /// Parallax in mas
+    pub fn parallax(&self) -> f64 {
+        self.inner.parallax
+    }
+
+    /// Radial velocity in km/s
+    pub fn rad_vel(&self) -> f64 {
+        self.inner.radialvelocity
+    }
+
+    /// Set the object name
+    pub fn set_name(&mut self, name: &str) -> super::Result<()> {
+        copy_c_str(&mut self.inner.starname, name)
+    }
+
+    /// Set the catalog identifier
+    pub fn set_catalog(&mut self, catalog: &str) -> super::Result<()> {
+        copy_c_str(&mut self.inner.catalog, catalog)
+    }
+
+    /// Set the object number in the catalog
+    pub fn set_number(&mut self, num: i64) {
+        self.inner.starnumber = num;
+    }
+
+    /// Set the right ascension in hours, in [0, 24)
+    pub fn set_ra(&mut self, ra: f64) -> super::Result<()> {
+        self.inner.ra = check_ra(ra)?;
+        Ok(())
+    }
+
+    /// Set the declination in degrees, in [-90, 90]
+    pub fn set_dec(&mut self, dec: f64) -> super::Result<()> {
+        self.inner.dec = check_dec(dec)?;
+        Ok(())
+    }
+
+    /// Set the proper motion in right ascension in mas/yr
+    pub fn set_pm_ra(&mut self, pm_ra: f64) -> super::Result<()> {
+        self.inner.promora = finite("pm_ra", pm_ra)?;
+        Ok(())
+    }
+
+    /// Set the proper motion in declination in mas/yr
+    pub fn set_pm_dec(&mut self, pm_dec: f64) -> super::Result<()> {
+        self.inner.promodec = finite("pm_dec", pm_dec)?;
+        Ok(())
+    }
+
+    /// Set the (non-negative) parallax in mas
+    pub fn set_parallax(&mut self, parallax: f64) -> super::Result<()> {
+        self.inner.parallax = check_parallax(parallax)?;
+        Ok(())
+    }
+
+    /// Set the radial velocity in km/s
+    pub fn set_rad_vel(&mut self, rad_vel: f64) -> super::Result<()> {
+        self.inner.radialvelocity = finite("rad_vel", rad_vel)?;
+        Ok(())
+    }
+}
+
+/// Copy a string into a fixed-size, null-terminated C buffer
+fn copy_c_str(dst: &mut [c_char], src: &str) -> super::Result<()> {
+    // We need the extra byte for the \0
+    if src.len() >= dst.len() || src.bytes().any(|b| b == 0) {
+        return Err(Error::InvalidString);
+    }
+    dst.fill(0);
+    for (d, s) in dst.iter_mut().zip(src.bytes()) {
+        *d = s as c_char;
+    }
+    Ok(())
+}
+
+/// A null-terminated object name on the stack, so that placing a source doesn't allocate
+pub(crate) fn object_name(name: &str) -> super::Result<[c_char; SIZE_OF_OBJ_NAME as usize]> {
+    let mut buf = [0; SIZE_OF_OBJ_NAME as usize];
+    copy_c_str(&mut buf, name)?;
+    Ok(buf)
+}
+
+/// Reject NaN and infinite values for the named parameter
+fn finite(param: &'static str, value: f64) -> super::Result<f64> {
+    if value.is_finite() {
+        Ok(value)
+    } else {
+        Err(Error::InvalidValue(param))
+    }
+}
+
+/// Check that a value is finite and within `[min, max]` (or `[min, max)` if `exclusive`)
+fn in_range(
+    param: &'static str,
+    value: f64,
+    min: f64,
+    max: f64,
+    exclusive: bool,
+) -> super::Result<f64> {
+    let value = finite(param, value)?;
+    if value < min || value > max || (exclusive && value == max) {
+        return Err(Error::OutOfRange { param, value });
+    }
+    Ok(value)
+}
+
+/// Validate a right ascension in hours
+fn check_ra(ra: f64) -> super::Result<f64> {
+    in_range("ra", ra, 0.0, 24.0, true)
+}
+
+/// Validate a declination in degrees
+fn check_dec(dec: f64) -> super::Result<f64> {
+    in_range("dec", dec, -90.0, 90.0, false)
+}
+
+/// Validate a parallax in mas
+fn check_parallax(parallax: f64) -> super::Result<f64> {
+    in_range("parallax", parallax, 0.0, f64::INFINITY, false)
+}
+
+impl Placeable for CatalogEntry {
+    fn object(&self) -> super::Result<Cow<'_, object>> {
+        let mut obj = MaybeUninit::uninit();
+        // Safety: Nothing here is null and names and numbers are valid
+        // This is copying data into the object, so lifetimes here are ok
+        let obj = unsafe {
+            let _ = make_cat_object(&self.inner as *const _, obj.as_mut_ptr());
+            obj.assume_init()
+        };
+        Ok(Cow::Owned(obj))
+    }
+
+    fn error_ellipse(&self, frame: &Frame) -> Option<ErrorEllipse> {
+        let (uncertainty, epoch) = self.uncertainty.as_ref()?;
+        Some(error_ellipse(
+            uncertainty,
+            *epoch,
+            frame.time.jd(Timescale::TT),
+            self.ra(),
+            self.dec(),
+            &frame.inner.obs_pos,
+        ))
+    }
+}
+
+/// A builder for a [`CatalogEntry`], see [`CatalogEntry::builder`]
+#[derive(Debug, Clone)]
+pub struct CatalogEntryBuilder<'a> {
+    name: &'a str,
+    catalog: &'a str,
+    num: i64,
+    ra: f64,
+    dec: f64,
+    pm_ra: f64,
+    pm_dec: f64,
+    parallax: f64,
+    rad_vel: f64,
+    system: CatalogSystem,
+    uncertainty: Option<Uncertainty>,
+}
+
+impl<'a> CatalogEntryBuilder<'a> {
+    /// The catalog identifier and the object number in it
+    pub fn catalog(mut self, catalog: &'a str, num: i64) -> Self {
+        self.catalog = catalog;
+        self.num = num;
+        self
+    }
+
+    /// The proper motion (in right ascension and in declination) in mas/yr
+    pub fn pm(mut self, pm_ra: f64, pm_dec: f64) -> Self {
+        self.pm_ra = pm_ra;
+        self.pm_dec = pm_dec;
+        self
+    }
+
+    /// The parallax in mas
+    pub fn parallax(mut self, parallax: f64) -> Self {
+        self.parallax = parallax;
+        self
+    }
+
+    /// The radial velocity in km/s
+    pub fn radial_velocity(mut self, rad_vel: f64) -> Self {
+        self.rad_vel = rad_vel;
+        self
+    }
+
+    /// The reference system and epoch the coordinates are given in
+    pub fn system(mut self, system: CatalogSystem) -> Self {
+        self.system = system;
+        self
+    }
+
+    /// The 1σ astrometric uncertainties, with the position uncertainties at the epoch of the system
+    pub fn uncertainty(mut self, uncertainty: Uncertainty) -> Self {
+        self.uncertainty = Some(uncertainty);
+        self
+    }
+
+    /// Construct the catalog entry
+    pub fn build(self) -> super::Result<CatalogEntry> {
+        let entry = CatalogEntry::new(
+            self.name,
+            self.catalog,
+            self.num,
+            self.ra,
+            self.dec,
+            self.pm_ra,
+            self.pm_dec,
+            self.parallax,
+            self.rad_vel,
+        )?
+        .with_system(self.system);
+        Ok(match self.uncertainty {
+            Some(uncertainty) => entry.with_uncertainty(uncertainty),
+            None => entry,
+        })
+    }
+}
+
+/// Entries are equal when all of their fields are, comparing the name and catalog as strings
+impl PartialEq for CatalogEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name()
+            && self.catalog() == other.catalog()
+            && self.number() == other.number()
+            && self.ra() == other.ra()
+            && self.dec() == other.dec()
+            && self.pm_ra() == other.pm_ra()
+            && self.pm_dec() == other.pm_dec()
+            && self.parallax() == other.parallax()
+            && self.rad_vel() == other.rad_vel()
+            && self.system == other.system
+            && self.uncertainty == other.uncertainty
+    }
+}
+
 impl Debug for CatalogEntry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // Safety: We created these strings and checked for validity then, so they *should* still be valid here
-        let name = unsafe { CStr::from_ptr(self.0.starname.as_ptr()) };
-        let catalog = unsafe { CStr::from_ptr(self.0.catalog.as_ptr()) };
         f.debug_struct("CatalogEntry")
-            .field("name", &name)
-            .field("catalog", &catalog)
-            .field("number", &self.0.starnumber)
-            .field("ra", &self.0.ra)
-            .field("dec", &self.0.dec)
-            .field("pm_ra", &self.0.promora)
-            .field("pm_dec", &self.0.promodec)
-            .field("parallax", &self.0.parallax)
-            .field("rad_vel", &self.0.radialvelocity)
+            .field("name", &self.name())
+            .field("catalog", &self.catalog())
+            .field("number", &self.number())
+            .field("ra", &self.ra())
+            .field("dec", &self.dec())
+            .field("pm_ra", &self.pm_ra())
+            .field("pm_dec", &self.pm_dec())
+            .field("parallax", &self.parallax())
+            .field("rad_vel", &self.rad_vel())
+            .field("system", &self.system)
+            .field("uncertainty", &self.uncertainty())
             .finish()
     }
 }
 
 /// A set of parameters that uniquely define the place and time of observation
+///
+/// Frames are plain values, so they can be cloned cheaply and shared across threads, e.g. to place different
+/// targets for the same epoch on worker threads.
+#[derive(Clone)]
 pub struct Frame<'a> {
-    inner: novas_frame,
-    _marker: PhantomData<&'a ()>,
+    pub(crate) inner: novas_frame,
+    pub(crate) observer: &'a Observer,
+    pub(crate) time: Timespec,
 }
 
+// Frames are shared with worker threads (e.g. in `batch`), so make sure they stay thread-safe
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Frame<'static>>();
+};
+
 impl<'a> Frame<'a> {
+    /// Construct a new observing frame
+    ///
+    /// The time may be given in any of the forms accepted by [`IntoTimespec`], e.g. a [`Timespec`] or an ISO 8601 UTC
+    /// string. The polar offsets (dx, dy) are in mas.
     pub fn new(
         acc: Accuracy,
         obs: &'a Observer,
-        time: &'a Timespec,
+        time: impl IntoTimespec,
         dx: f64,
         dy: f64,
     ) -> super::Result<Self> {
-        // NOTE: This structure holds on to references to the observer and time, so it must capture their lifetimes
+        let time = time.into_timespec()?;
+        // NOTE: This structure holds on to a reference to the observer (for refraction), so it must capture its
+        // lifetime. The time is small enough to keep by value.
         let mut frame = MaybeUninit::uninit();
         let frame = unsafe {
             let ret = novas_make_frame(
@@ -300,20 +1051,105 @@ impl<'a> Frame<'a> {
         };
         Ok(Frame {
             inner: frame,
-            _marker: PhantomData,
+            observer: obs,
+            time,
         })
     }
 
-    /// Computes the local coordinates (az,el in degrees) of a catalog (sidereal) source in the given ReferenceSystem
+    /// Construct a new observing frame, with the Earth orientation taken from the global EOP table (see
+    /// [`set_eop_table`](crate::eop::set_eop_table))
+    ///
+    /// See [`Frame::with_eop`]. This errors if no EOP table was set, or it doesn't cover the time.
+    pub fn new_auto(
+        acc: Accuracy,
+        obs: &'a Observer,
+        time: impl IntoTimespec,
+    ) -> super::Result<Self> {
+        Self::with_eop(acc, obs, time, &eop_table()?)
+    }
+
+    /// Construct a new observing frame, with the Earth orientation taken from an EOP table
+    ///
+    /// The time is rebuilt from its UTC date with the built-in leap seconds and the interpolated UT1-UTC of the
+    /// table, replacing whatever the time was constructed with, and the polar offsets (dx, dy) are interpolated from
+    /// the table. This errors if the table doesn't cover the time.
+    pub fn with_eop(
+        acc: Accuracy,
+        obs: &'a Observer,
+        time: impl IntoTimespec,
+        eop: &EopTable,
+    ) -> super::Result<Self> {
+        let (ijd, fjd) = time.into_timespec()?.split_jd(Timescale::UTC);
+        let time = Timespec::from_utc(ijd, fjd, eop)?;
+        let pole = eop.get((ijd as f64 - MJD0) + fjd)?;
+        // The table has the pole coordinates in arcseconds, the frame wants them in mas
+        Self::new(acc, obs, time, pole.xp * 1000.0, pole.yp * 1000.0)
+    }
+
+    /// The observer of the frame
+    ///
+    /// This is the reference the frame was made with, so it outlives the frame itself.
+    pub fn observer(&self) -> &'a Observer {
+        self.observer
+    }
+
+    /// The time of observation of the frame
+    pub fn time(&self) -> &Timespec {
+        &self.time
+    }
+
+    /// Greenwich (apparent) sidereal time in hours
+    pub fn gst(&self) -> f64 {
+        self.inner.gst
+    }
+
+    /// Earth rotation angle in degrees
+    pub fn era(&self) -> f64 {
+        self.inner.era
+    }
+
+    /// Mean obliquity of the ecliptic in degrees
+    pub fn mean_obliquity(&self) -> f64 {
+        self.inner.mobl.to_degrees()
+    }
+
+    /// True obliquity of the ecliptic in degrees
+    pub fn true_obliquity(&self) -> f64 {
+        self.inner.tobl.to_degrees()
+    }
+
+    /// Equation of the equinoxes in seconds of time
+    pub fn equation_of_equinoxes(&self) -> f64 {
+        // Stored in radians
+        self.inner.ee.to_degrees() * 3600.0 / 15.0
+    }
+
+    /// Equation of the origins (ERA - GST) in degrees, wrapped to [-180, 180)
+    pub fn equation_of_origins(&self) -> f64 {
+        (self.inner.era - 15.0 * self.inner.gst + 180.0).rem_euclid(360.0) - 180.0
+    }
+
+    /// Barycentric position (AU) and velocity (AU/day) of the Sun
+    pub fn sun_state(&self) -> ([f64; 3], [f64; 3]) {
+        (self.inner.sun_pos, self.inner.sun_vel)
+    }
+
+    /// Barycentric position (AU) and velocity (AU/day) of the Earth
+    pub fn earth_state(&self) -> ([f64; 3], [f64; 3]) {
+        (self.inner.earth_pos, self.inner.earth_vel)
+    }
+
+    /// Computes the local coordinates (az,el in degrees) of a source in the given ReferenceSystem
+    ///
+    /// If a refraction model is given (and the observer is on the surface), the elevation is refracted.
     pub fn apparent_local_coordinates(
         &self,
         ref_sys: ReferenceSystem,
-        entry: &CatalogEntry,
+        target: &impl Placeable,
+        refraction: Option<&dyn RefractionModel>,
     ) -> super::Result<(f64, f64)> {
-        // Ignore refraction for now
-
         // Compute the apparent position
-        let sky_pos = SkyPosition::try_from_frame_entry(entry, self, ref_sys)?;
+        let sky_pos = SkyPosition::try_from_frame(target, self, ref_sys)?;
 
         let mut az = MaybeUninit::uninit();
         let mut el = MaybeUninit::uninit();
@@ -331,8 +1167,280 @@ impl<'a> Frame<'a> {
             (az.assume_init(), el.assume_init())
         };
 
+        // Refraction is computed on our side so any RefractionModel can be used
+        let el = match refraction {
+            Some(model) if self.observer.is_on_surface() => {
+                model.refract(self.time.jd(Timescale::TT), self.observer, el)
+            }
+            _ => el,
+        };
+
         Ok((az, el))
     }
+
+    /// The relative airmass towards a source using the given formula
+    ///
+    /// [`AirmassModel::Pickering`] is evaluated at the refracted elevation (using the given refraction model),
+    /// the other formulas at the unrefracted elevation.
+    pub fn airmass(
+        &self,
+        ref_sys: ReferenceSystem,
+        target: &impl Placeable,
+        model: AirmassModel,
+        refraction: Option<&dyn RefractionModel>,
+    ) -> super::Result<f64> {
+        let refraction = match model {
+            AirmassModel::Pickering => refraction,
+            _ => None,
+        };
+        let (_, el) = self.apparent_local_coordinates(ref_sys, target, refraction)?;
+        Ok(airmass(el, model))
+    }
+
+    /// The apparent angular diameter in degrees of a solar-system body, from its true distance and equatorial radius
+    pub fn angular_diameter(&self, planet: &Planet) -> super::Result<f64> {
+        let radius = planet.radius().ok_or(Error::InvalidValue("planet"))?;
+        let sky_pos = SkyPosition::try_from_frame(planet, self, ReferenceSystem::ICRS)?;
+        let distance = sky_pos.distance().ok_or(Error::InvalidValue("planet"))? * AU;
+        Ok(2.0 * (radius / distance).min(1.0).asin().to_degrees())
+    }
+
+    /// The geometric position (AU) and velocity (AU/day) of a source relative to the observer, without light-time,
+    /// aberration, or deflection corrections
+    pub fn geom_posvel(
+        &self,
+        target: &impl Placeable,
+        ref_sys: ReferenceSystem,
+    ) -> super::Result<([f64; 3], [f64; 3])> {
+        let obj = target.object()?;
+        let mut pos = [0.0; 3];
+        let mut vel = [0.0; 3];
+        // Safety: The object and frame are initialized and the output vectors are valid 3-vectors
+        let ret = unsafe {
+            novas_geom_posvel(
+                &*obj as *const _,
+                &self.inner as *const _,
+                ref_sys.into(),
+                pos.as_mut_ptr(),
+                vel.as_mut_ptr(),
+            )
+        };
+        if ret != 0 {
+            return Err(Error::LowerLevel(ret));
+        }
+        Ok((pos, vel))
+    }
+
+    /// Converts a geometric position (AU, relative to the observer) in the given reference system to an apparent
+    /// place, applying aberration and gravitational deflection
+    pub fn geom_to_app(
+        &self,
+        pos: &[f64; 3],
+        ref_sys: ReferenceSystem,
+    ) -> super::Result<SkyPosition> {
+        let mut out = MaybeUninit::uninit();
+        // Safety: The frame is initialized and the position is a valid 3-vector
+        let ret = unsafe {
+            novas_geom_to_app(
+                &self.inner as *const _,
+                pos.as_ptr(),
+                ref_sys.into(),
+                out.as_mut_ptr(),
+            )
+        };
+        if ret != 0 {
+            return Err(Error::LowerLevel(ret));
+        }
+        // Safety: The conversion succeeded, so the output is initialized
+        Ok(SkyPosition(unsafe { out.assume_init() }, None))
+    }
+
+    /// Converts an apparent place (ra in hours, dec in degrees, distance in AU) in the given reference system back
+    /// to a geometric ICRS position (AU, relative to the observer), removing aberration and gravitational deflection
+    pub fn app_to_geom(
+        &self,
+        ref_sys: ReferenceSystem,
+        ra: f64,
+        dec: f64,
+        distance: f64,
+    ) -> super::Result<[f64; 3]> {
+        let mut pos = [0.0; 3];
+        // Safety: The frame is initialized and the output is a valid 3-vector
+        let ret = unsafe {
+            novas_app_to_geom(
+                &self.inner as *const _,
+                ref_sys.into(),
+                ra,
+                dec,
+                distance,
+                pos.as_mut_ptr(),
+            )
+        };
+        if ret != 0 {
+            return Err(Error::LowerLevel(ret));
+        }
+        Ok(pos)
+    }
+
+    /// The angle in degrees of a source above the Earth's limb (negative if occulted by the Earth), and the
+    /// source's nadir angle in units of the angular radius of the Earth (below 1 when occulted)
+    ///
+    /// This is mainly of interest for observers in space, where it shows whether a target is blocked by, or too
+    /// close to, the Earth.
+    pub fn limb_angle(&self, target: &impl Placeable) -> super::Result<(f64, f64)> {
+        let (pos, _) = self.geom_posvel(target, ReferenceSystem::GCRS)?;
+        let (obs, _) = self
+            .observer
+            .geo_posvel(&self.time, self.inner.accuracy.into())?;
+        // limb_angle wants both positions relative to the geocenter
+        let src = [pos[0] + obs[0], pos[1] + obs[1], pos[2] + obs[2]];
+        let mut limb = 0.0;
+        let mut nadir = 0.0;
+        // Safety: Both positions are valid 3-vectors and the outputs are valid pointers
+        let ret = unsafe { limb_angle(src.as_ptr(), obs.as_ptr(), &mut limb, &mut nadir) };
+        if ret != 0 {
+            return Err(Error::LowerLevel(ret));
+        }
+        Ok((limb, nadir))
+    }
+
+    /// The topocentric radial velocity of a source in km/s (positive when receding), including the source's own
+    /// motion and the observer's motion around the barycenter
+    pub fn radial_velocity(&self, target: &impl Placeable) -> super::Result<f64> {
+        Ok(SkyPosition::try_from_frame(target, self, ReferenceSystem::ICRS)?.rad_vel())
+    }
+
+    /// The component of the observer's barycentric velocity towards a source in km/s
+    ///
+    /// This is the projection used by Doppler tracking to remove the observer's motion.
+    pub fn observer_velocity_toward(&self, target: &impl Placeable) -> super::Result<f64> {
+        let sky_pos = SkyPosition::try_from_frame(target, self, ReferenceSystem::ICRS)?;
+        let r_hat = sky_pos.r_hat();
+        let v = self.inner.obs_vel;
+        // AU/day to km/s
+        Ok((v[0] * r_hat[0] + v[1] * r_hat[1] + v[2] * r_hat[2]) * AU / 86_400.0)
+    }
+
+    /// The phase angle in degrees (Sun–body–observer) of a solar-system body
+    pub fn phase_angle(&self, body: &impl Placeable) -> super::Result<f64> {
+        let (pos, _) = self.geom_posvel(body, ReferenceSystem::ICRS)?;
+        let (sun, _) = self.geom_posvel(&Planet::Sun, ReferenceSystem::ICRS)?;
+        let to_observer = pos.map(|x| -x);
+        let to_sun = [sun[0] - pos[0], sun[1] - pos[1], sun[2] - pos[2]];
+        Ok(vector_angle(&to_observer, &to_sun))
+    }
+
+    /// The solar elongation in degrees (Sun–observer–body) of a source
+    pub fn elongation(&self, body: &impl Placeable) -> super::Result<f64> {
+        let (pos, _) = self.geom_posvel(body, ReferenceSystem::ICRS)?;
+        let (sun, _) = self.geom_posvel(&Planet::Sun, ReferenceSystem::ICRS)?;
+        Ok(vector_angle(&pos, &sun))
+    }
+
+    /// The parallactic angle in degrees of a source (the position angle of the zenith, east of north)
+    pub fn parallactic_angle(&self, target: &impl Placeable) -> super::Result<f64> {
+        if !self.observer.is_on_surface() {
+            return Err(Error::InvalidValue("observer"));
+        }
+        let (az, el) = self.apparent_local_coordinates(ReferenceSystem::CIRS, target, None)?;
+        let (az, el) = (az.to_radians(), el.to_radians());
+        let lat = self.observer.latitude().to_radians();
+        Ok((-lat.cos() * az.sin())
+            .atan2(lat.sin() * el.cos() - lat.cos() * el.sin() * az.cos())
+            .to_degrees())
+    }
+
+    /// The rate of field rotation in degrees/s of a source for an alt-az telescope, i.e. the rate a derotator
+    /// must turn at to hold the field fixed
+    ///
+    /// This diverges as the source approaches the zenith.
+    pub fn field_rotation_rate(&self, target: &impl Placeable) -> super::Result<f64> {
+        if !self.observer.is_on_surface() {
+            return Err(Error::InvalidValue("observer"));
+        }
+        let (az, el) = self.apparent_local_coordinates(ReferenceSystem::CIRS, target, None)?;
+        let lat = self.observer.latitude().to_radians();
+        Ok(-EARTH_ROTATION_RATE * lat.cos() * az.to_radians().cos() / el.to_radians().cos())
+    }
+
+    /// Whether a source is above the observer's horizon (respecting its horizon profile, if any)
+    pub fn is_visible(
+        &self,
+        ref_sys: ReferenceSystem,
+        target: &impl Placeable,
+        refraction: Option<&dyn RefractionModel>,
+    ) -> super::Result<bool> {
+        let (az, el) = self.apparent_local_coordinates(ref_sys, target, refraction)?;
+        Ok(el >= self.observer.min_elevation(az))
+    }
+
+    /// Converts local coordinates (az,el in degrees) to an apparent place (ra in hours, dec in degrees) in the given
+    /// ReferenceSystem
+    ///
+    /// If a refraction model is given (and the observer is on the surface), `el` is taken to be an observed
+    /// (refracted) elevation and the refraction is removed first.
+    pub fn hor_to_app(
+        &self,
+        az: f64,
+        el: f64,
+        refraction: Option<&dyn RefractionModel>,
+        ref_sys: ReferenceSystem,
+    ) -> super::Result<(f64, f64)> {
+        let el = match refraction {
+            Some(model) if self.observer.is_on_surface() => {
+                model.unrefract(self.time.jd(Timescale::TT), self.observer, el)
+            }
+            _ => el,
+        };
+
+        let mut ra = 0.0;
+        let mut dec = 0.0;
+        // Safety: The frame is initialized and the outputs are valid pointers
+        let ret = unsafe {
+            novas_hor_to_app(
+                &self.inner as *const _,
+                az,
+                el,
+                None,
+                ref_sys.into(),
+                &mut ra,
+                &mut dec,
+            )
+        };
+        if ret != 0 {
+            return Err(Error::LowerLevel(ret));
+        }
+        Ok((ra, dec))
+    }
+
+    /// The apparent topocentric right ascension and declination of date ("JNow") of a source, as expected by most
+    /// GoTo mount protocols (e.g. LX200 and SynScan)
+    ///
+    /// This is the place in the true equator and equinox of date ([`ReferenceSystem::TOD`]) as seen by the observer.
+    /// If a refraction model is given (and the observer is on the surface), the place is shifted up by the refraction
+    /// to where the source is observed, for mounts that don't model refraction themselves.
+    pub fn jnow(
+        &self,
+        target: &impl Placeable,
+        refraction: Option<&dyn RefractionModel>,
+    ) -> super::Result<(RightAscension, Declination)> {
+        let (ra, dec) = match refraction {
+            Some(_) if self.observer.is_on_surface() => {
+                let (az, el) =
+                    self.apparent_local_coordinates(ReferenceSystem::TOD, target, refraction)?;
+                // The elevation is already refracted, so convert it back as is
+                self.hor_to_app(az, el, None, ReferenceSystem::TOD)?
+            }
+            _ => {
+                let pos = SkyPosition::try_from_frame(target, self, ReferenceSystem::TOD)?;
+                (pos.ra(), pos.dec())
+            }
+        };
+        Ok((
+            RightAscension::from_hours(ra.rem_euclid(24.0) % 24.0)?,
+            Declination::from_degrees(dec)?,
+        ))
+    }
 }
 
 /// Positional coordinaate reference systems
@@ -373,8 +1481,84 @@ impl From<ReferenceSystem> for novas_reference_system {
     }
 }
 
-/// A celestial object's place on the sky
-pub struct SkyPosition(sky_pos);
+/// A rotation between two reference systems at the epoch of a [`Frame`]
+///
+/// This moves already-computed positions between systems without recomputing the full place.
+pub struct Transform {
+    inner: novas_transform,
+}
+
+impl Transform {
+    /// Construct the transform from `from` to `to` at the epoch and observer of the frame
+    pub fn new(frame: &Frame, from: ReferenceSystem, to: ReferenceSystem) -> super::Result<Self> {
+        let mut transform = MaybeUninit::uninit();
+        // Safety: The frame is initialized and the output is not null
+        let ret = unsafe {
+            novas_make_transform(
+                &frame.inner as *const _,
+                from.into(),
+                to.into(),
+                transform.as_mut_ptr(),
+            )
+        };
+        if ret != 0 {
+            return Err(Error::LowerLevel(ret));
+        }
+        // Safety: The construction succeeded, so the transform is initialized
+        Ok(Self {
+            inner: unsafe { transform.assume_init() },
+        })
+    }
+
+    /// The inverse transform (from `to` back to `from`)
+    pub fn inverse(&self) -> super::Result<Self> {
+        let mut transform = MaybeUninit::uninit();
+        // Safety: The transform is initialized and the output is not null
+        let ret =
+            unsafe { novas_invert_transform(&self.inner as *const _, transform.as_mut_ptr()) };
+        if ret != 0 {
+            return Err(Error::LowerLevel(ret));
+        }
+        // Safety: The inversion succeeded, so the transform is initialized
+        Ok(Self {
+            inner: unsafe { transform.assume_init() },
+        })
+    }
+
+    /// Rotates a sky position into the target reference system
+    pub fn sky_pos(&self, pos: &SkyPosition) -> super::Result<SkyPosition> {
+        let mut out = MaybeUninit::uninit();
+        // Safety: The inputs are initialized and the output is not null
+        let ret = unsafe {
+            novas_transform_sky_pos(
+                &pos.0 as *const _,
+                &self.inner as *const _,
+                out.as_mut_ptr(),
+            )
+        };
+        if ret != 0 {
+            return Err(Error::LowerLevel(ret));
+        }
+        // Safety: The transformation succeeded, so the output is initialized
+        Ok(SkyPosition(unsafe { out.assume_init() }, pos.1))
+    }
+
+    /// Rotates a position or velocity vector into the target reference system
+    pub fn vector(&self, v: &[f64; 3]) -> super::Result<[f64; 3]> {
+        let mut out = [0.0; 3];
+        // Safety: The transform is initialized and both vectors are valid 3-vectors
+        let ret = unsafe {
+            novas_transform_vector(v.as_ptr(), &self.inner as *const _, out.as_mut_ptr())
+        };
+        if ret != 0 {
+            return Err(Error::LowerLevel(ret));
+        }
+        Ok(out)
+    }
+}
+
+/// A celestial object's place on the sky, with its error ellipse if the source's uncertainties are known
+pub struct SkyPosition(sky_pos, Option<ErrorEllipse>);
 
 impl SkyPosition {
     /// Apparent, topocentric, or astrometric declination in degrees
@@ -387,11 +1571,28 @@ impl SkyPosition {
         self.0.ra
     }
 
+    /// Right ascension as a typed angle
+    pub fn right_ascension(&self) -> RightAscension {
+        // NOVAS returns right ascensions in [0, 24)
+        RightAscension(self.0.ra)
+    }
+
+    /// Declination as a typed angle
+    pub fn declination(&self) -> Declination {
+        Declination(self.0.dec)
+    }
+
     /// Radial velocity in km/s
     pub fn rad_vel(&self) -> f64 {
         self.0.rv
     }
 
+    /// The 1σ error ellipse of the position, propagated from the catalog uncertainties of the source (see
+    /// [`CatalogEntry::with_uncertainty`])
+    pub fn error_ellipse(&self) -> Option<ErrorEllipse> {
+        self.1
+    }
+
     /// True (geometric, Euclidian) distance to solar system body (if it is a solar system body)
     pub fn distance(&self) -> Option<f64> {
         if self.0.dis != 0.0 {
@@ -406,6 +1607,11 @@ impl SkyPosition {
         &self.0.r_hat
     }
 
+    /// Angular separation in degrees from another position (which should be in the same reference system)
+    pub fn separation(&self, other: &SkyPosition) -> f64 {
+        vector_angle(self.r_hat(), other.r_hat())
+    }
+
     /// Calculates an apparent location on the sky for a CatalogEntry
     ///
     /// This takes into account proper motion
@@ -414,28 +1620,33 @@ impl SkyPosition {
         frame: &Frame,
         ref_sys: ReferenceSystem,
     ) -> super::Result<Self> {
-        // First we need to make the `object` structure from the catalog entry
-        let mut obj = MaybeUninit::uninit();
-        // Safety: Nothing here is null and names and numbers are valid
-        // This is copying data into the object, so lifetimes here are ok
-        let obj = unsafe {
-            let _ = make_cat_object(&entry.0 as *const _, obj.as_mut_ptr());
-            obj.assume_init()
-        };
+        Self::try_from_frame(entry, frame, ref_sys)
+    }
+
+    /// Calculates an apparent location on the sky for any [`Placeable`] source
+    pub fn try_from_frame(
+        target: &impl Placeable,
+        frame: &Frame,
+        ref_sys: ReferenceSystem,
+    ) -> super::Result<Self> {
+        // First we need to make the `object` structure from the source
+        let obj = target.object()?;
         // The compute the sky position
         let mut sky_pos = MaybeUninit::uninit();
         let sky_pos = unsafe {
             let ret = novas_sky_pos(
-                &obj as *const _,
+                &*obj as *const _,
                 &frame.inner as *const _,
                 ref_sys.into(),
                 sky_pos.as_mut_ptr(),
             );
-            assert_eq!(ret, 0);
+            if ret != 0 {
+                return Err(Error::LowerLevel(ret));
+            }
             sky_pos.assume_init()
         };
 
-        Ok(Self(sky_pos))
+        Ok(Self(sky_pos, target.error_ellipse(frame)))
     }
 
     pub fn place(
@@ -450,7 +1661,7 @@ impl SkyPosition {
         let sky_pos = unsafe {
             let ret = place_star(
                 jd_tt,
-                &entry.0 as *const _,
+                &entry.inner as *const _,
                 &obs.inner as *const _,
                 ut1_to_tt,
                 ref_sys.into(),
@@ -461,12 +1672,159 @@ impl SkyPosition {
             sky_pos.assume_init()
         };
 
-        Ok(Self(sky_pos))
+        Ok(Self(sky_pos, None))
     }
 }
 
 impl Debug for SkyPosition {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_tuple("SkyPosition").field(&self.0).finish()
+        f.debug_tuple("SkyPosition")
+            .field(&self.0)
+            .field(&self.1)
+            .finish()
+    }
+}
+
+/// The angle in degrees between two (not necessarily unit) vectors
+pub(crate) fn vector_angle(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    let dot = a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+    let cross = [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ];
+    // atan2 is well conditioned at both small and large angles
+    let sin = cross.iter().map(|x| x * x).sum::<f64>().sqrt();
+    sin.atan2(dot).to_degrees()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_time_from_eop_table() {
+        let eop = |mjd| crate::eop::Eop {
+            mjd,
+            xp: 0.1,
+            yp: 0.3,
+            dut1: 0.2,
+            dx: 0.0,
+            dy: 0.0,
+        };
+        let table = EopTable::new(vec![eop(60_000.0), eop(60_001.0)]).unwrap();
+        let observer = Observer::new_on_surface(37.0, 0.0, 0.0, Weather::default());
+        // Built without UT1-UTC, which the table should replace
+        let time = Timespec::from_split_time(Timescale::UTC, 2_460_000, 0.5, 37, 0.0);
+        let frame = Frame::with_eop(Accuracy::Reduced, &observer, time, &table).unwrap();
+        let ut1_utc =
+            (frame.time().jd(Timescale::UT1) - frame.time().jd(Timescale::UTC)) * 86_400.0;
+        assert!((ut1_utc - 0.2).abs() < 1e-3);
+        assert!(frame.time().diff(&time).abs() < 1e-6);
+
+        let late = Timespec::from_split_time(Timescale::UTC, 2_460_010, 0.5, 37, 0.0);
+        assert!(Frame::with_eop(Accuracy::Reduced, &observer, late, &table).is_err());
+    }
+
+    #[test]
+    fn transform_relabels_only_on_success() {
+        let mut star = CatalogEntry::new("star", "FK4", 1, 6.0, 20.0, 0.0, 0.0, 0.0, 0.0).unwrap();
+        let before = star.system();
+        let long_name = "X".repeat(SIZE_OF_CAT_NAME as usize + 1);
+        assert!(star
+            .transform(Transformation::J2000ToICRS, Some(long_name))
+            .is_err());
+        assert_eq!(star.system(), before);
+
+        star.transform(Transformation::ICRSToJ2000, Some("FK5".to_owned()))
+            .unwrap();
+        assert_eq!(star.catalog(), "FK5");
+        assert!(matches!(
+            star.system(),
+            CatalogSystem::MeanEquinox { equinox, .. } if equinox == NOVAS_JD_J2000
+        ));
+    }
+
+    #[test]
+    fn terra_adds_the_longitude() {
+        let observer = Observer::new_on_surface(0.0, 90.0, 0.0, Weather::default());
+        // The equatorial radius of the Earth in AU
+        let radius = 6_378_136.6 / 1.495_978_707e11;
+        let (pos, _) = observer.terra(0.0).unwrap();
+        assert!(pos[0].abs() < 1e-12 && (pos[1] - radius).abs() < 1e-12);
+        let (pos, _) = observer.terra(6.0).unwrap();
+        assert!((pos[0] + radius).abs() < 1e-12 && pos[1].abs() < 1e-12);
+    }
+
+    #[test]
+    fn parallactic_angle_sign_and_rate() {
+        let observer = Observer::new_on_surface(37.0, 0.0, 0.0, Weather::default());
+        // Two hours of hour angle either side of the meridian at J2000.0 (GMST 18.7 h)
+        let west = CatalogEntry::new("west", "", 0, 16.697, 0.0, 0.0, 0.0, 0.0, 0.0).unwrap();
+        let east = CatalogEntry::new("east", "", 0, 20.697, 0.0, 0.0, 0.0, 0.0, 0.0).unwrap();
+        let frame = Frame::new(Accuracy::Reduced, &observer, Timespec::J2000, 0.0, 0.0).unwrap();
+        let q_west = frame.parallactic_angle(&west).unwrap();
+        assert!(q_west > 0.0);
+        assert!(frame.parallactic_angle(&east).unwrap() < 0.0);
+
+        let later = Frame::new(
+            Accuracy::Reduced,
+            &observer,
+            Timespec::J2000.offset(60.0),
+            0.0,
+            0.0,
+        )
+        .unwrap();
+        let rate = (later.parallactic_angle(&west).unwrap() - q_west) / 60.0;
+        assert!((frame.field_rotation_rate(&west).unwrap() - rate).abs() < 1e-5);
+    }
+
+    #[test]
+    fn frames_shared_across_threads() {
+        let observer = Observer::new_on_surface(37.23, -118.28, 1222.0, Weather::default());
+        let frame = Frame::new(Accuracy::Reduced, &observer, Timespec::J2000, 0.0, 0.0).unwrap();
+        let stars = (0..8)
+            .map(|i| {
+                let (ra, dec) = (i as f64 * 3.0, i as f64 * 20.0 - 70.0);
+                CatalogEntry::new("star", "", i, ra, dec, 10.0, -5.0, 2.0, 0.0).unwrap()
+            })
+            .collect::<Vec<_>>();
+        let place = |frame: &Frame, star: &CatalogEntry| {
+            let pos = SkyPosition::try_from_frame(star, frame, ReferenceSystem::CIRS).unwrap();
+            let azel = frame
+                .apparent_local_coordinates(ReferenceSystem::CIRS, star, None)
+                .unwrap();
+            (pos.ra(), pos.dec(), azel)
+        };
+        let expected = stars
+            .iter()
+            .map(|star| place(&frame, star))
+            .collect::<Vec<_>>();
+
+        let (shared, cloned) = std::thread::scope(|scope| {
+            let shared = stars
+                .iter()
+                .map(|star| scope.spawn(|| place(&frame, star)))
+                .collect::<Vec<_>>();
+            let cloned = stars
+                .iter()
+                .map(|star| {
+                    let frame = frame.clone();
+                    scope.spawn(move || place(&frame, star))
+                })
+                .collect::<Vec<_>>();
+            (
+                shared
+                    .into_iter()
+                    .map(|h| h.join().unwrap())
+                    .collect::<Vec<_>>(),
+                cloned
+                    .into_iter()
+                    .map(|h| h.join().unwrap())
+                    .collect::<Vec<_>>(),
+            )
+        });
+        assert_eq!(shared, expected);
+        assert_eq!(cloned, expected);
     }
 }
@@ -0,0 +1,115 @@
+//! A one-shot convenience API for quick pointing calculations
+//!
+//! [`quick_azel`] strings together site lookup, SIMBAD name resolution, EOP lookup, frame construction, and
+//! refraction with sane defaults. For repeated or precise calculations, build the [`Observer`], [`Frame`], and
+//! [`CatalogEntry`] once and reuse them instead.
+
+use crate::{
+    coords::{Declination, RightAscension},
+    eop::eop_table,
+    positions::{CatalogEntry, Frame, Observer, ReferenceSystem, Weather},
+    refraction::StandardRefraction,
+    time::{IntoTimespec, Timescale, MJD0},
+    Accuracy,
+};
+
+/// Where to observe from in [`quick_azel`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum QuickSite<'a> {
+    /// An observatory from the built-in site database, by key or full name (see [`crate::sites`])
+    Named(&'a str),
+    /// Geodetic latitude and longitude in degrees (north and east positive), and height above sea level in meters
+    Geodetic { lat: f64, lon: f64, height: f64 },
+}
+
+impl<'a> From<&'a str> for QuickSite<'a> {
+    fn from(name: &'a str) -> Self {
+        Self::Named(name)
+    }
+}
+
+/// Latitude and longitude in degrees, at sea level
+impl From<(f64, f64)> for QuickSite<'_> {
+    fn from((lat, lon): (f64, f64)) -> Self {
+        Self::Geodetic {
+            lat,
+            lon,
+            height: 0.0,
+        }
+    }
+}
+
+/// Latitude and longitude in degrees, and height in meters
+impl From<(f64, f64, f64)> for QuickSite<'_> {
+    fn from((lat, lon, height): (f64, f64, f64)) -> Self {
+        Self::Geodetic { lat, lon, height }
+    }
+}
+
+/// What to point at in [`quick_azel`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum QuickTarget<'a> {
+    /// An identifier resolved through SIMBAD (e.g. "Vega" or "M 31")
+    Named(&'a str),
+    /// ICRS right ascension in hours and declination in degrees
+    Icrs { ra: f64, dec: f64 },
+}
+
+impl<'a> From<&'a str> for QuickTarget<'a> {
+    fn from(name: &'a str) -> Self {
+        Self::Named(name)
+    }
+}
+
+/// ICRS right ascension in hours and declination in degrees
+impl From<(f64, f64)> for QuickTarget<'_> {
+    fn from((ra, dec): (f64, f64)) -> Self {
+        Self::Icrs { ra, dec }
+    }
+}
+
+impl From<(RightAscension, Declination)> for QuickTarget<'_> {
+    fn from((ra, dec): (RightAscension, Declination)) -> Self {
+        Self::Icrs {
+            ra: ra.hours(),
+            dec: dec.degrees(),
+        }
+    }
+}
+
+/// The refracted azimuth and elevation in degrees of a target as seen from a site at a time
+///
+/// This uses reduced accuracy, standard weather and refraction, and UT1-UTC and polar offsets from the global EOP
+/// table (see [`set_eop_table`](crate::eop::set_eop_table)) when it covers the time, or zero otherwise. Without the
+/// table, times given as ISO strings or MJDs use the built-in leap seconds and UT1-UTC of zero (see
+/// [`IntoTimespec::into_timespec_approx`]), which can shift the position by up to about 14".
+///
+/// ```no_run
+/// let (az, el) = supernovas::quick_azel("OVRO", "Vega", "2024-09-17T06:12:18Z")?;
+/// # Ok::<(), supernovas::error::Error>(())
+/// ```
+pub fn quick_azel<'a>(
+    site: impl Into<QuickSite<'a>>,
+    target: impl Into<QuickTarget<'a>>,
+    time: impl IntoTimespec,
+) -> super::Result<(f64, f64)> {
+    let observer = match site.into() {
+        QuickSite::Named(name) => Observer::from_site(name)?,
+        QuickSite::Geodetic { lat, lon, height } => {
+            Observer::new_on_surface(lat, lon, height, Weather::default())
+        }
+    };
+    let entry = match target.into() {
+        QuickTarget::Named(name) => CatalogEntry::from_simbad(name, "HIP")?,
+        QuickTarget::Icrs { ra, dec } => {
+            CatalogEntry::new("target", "", 0, ra, dec, 0.0, 0.0, 0.0, 0.0)?
+        }
+    };
+    let time = time.into_timespec_approx()?;
+    let (dx, dy) = eop_table()
+        .and_then(|eop| eop.get(time.jd(Timescale::UTC) - MJD0))
+        // The table has the pole coordinates in arcseconds, the frame wants them in mas
+        .map_or((0.0, 0.0), |eop| (eop.xp * 1000.0, eop.yp * 1000.0));
+    let frame = Frame::new(Accuracy::Reduced, &observer, time, dx, dy)?;
+    frame.apparent_local_coordinates(ReferenceSystem::CIRS, &entry, Some(&StandardRefraction))
+}
@@ -0,0 +1,165 @@
+//! A fast pointing path for real-time control loops
+//!
+//! Constructing an observing [`Frame`] (precession-nutation, Earth orientation, TDB offset, the Sun and Earth
+//! states) dominates the cost of every sample. [`RealtimePointing`] instead builds full frames only at anchor times
+//! spaced `interval` seconds apart, and in between interpolates the slowly varying apparent (CIRS) place of the
+//! target while advancing the Earth rotation angle analytically. The pointing is exact at the anchors.
+//!
+//! The interpolation error grows with the square of the interval. With a 60 s interval it stays below 0.01 mas for
+//! stars and other distant sources, and below ~10 mas for the Moon (whose topocentric place curves fastest, through
+//! its diurnal parallax). A 10 s interval brings the Moon below 0.3 mas.
+
+use crate::{
+    error::Error,
+    positions::{Frame, Observer, Placeable, ReferenceSystem, SkyPosition},
+    refraction::RefractionModel,
+    time::{Timescale, Timespec},
+    Accuracy,
+};
+
+/// The rate of the Earth rotation angle in degrees per (UT1) second
+const ERA_RATE: f64 = 360.0 * 1.002_737_811_911_354_5 / 86_400.0;
+
+/// The slowly varying quantities at an anchor time
+struct Anchor {
+    time: Timespec,
+    /// Earth rotation angle in degrees
+    era: f64,
+    /// CIRS right ascension in hours
+    ra: f64,
+    /// CIRS declination in degrees
+    dec: f64,
+    /// The (az, el) correction in degrees from the simple rotation to the full horizontal transformation, mostly
+    /// due to polar motion
+    correction: (f64, f64),
+}
+
+/// Pointing to a single target, reusing full observing frames across many nearby timestamps
+pub struct RealtimePointing<'a, T> {
+    target: &'a T,
+    observer: &'a Observer,
+    interval: f64,
+    accuracy: Accuracy,
+    dx: f64,
+    dy: f64,
+    refraction: Option<&'a dyn RefractionModel>,
+    /// The anchors bracketing the most recent sample
+    anchors: Option<(Anchor, Anchor)>,
+}
+
+impl<'a, T: Placeable> RealtimePointing<'a, T> {
+    /// Point at `target` from a surface `observer`, building full frames every `interval` seconds
+    ///
+    /// By default, this uses reduced accuracy with no pole offsets and no refraction.
+    pub fn new(target: &'a T, observer: &'a Observer, interval: f64) -> super::Result<Self> {
+        if !observer.is_on_surface() {
+            return Err(Error::InvalidValue("observer"));
+        }
+        if interval.is_nan() || interval <= 0.0 {
+            return Err(Error::InvalidValue("interval"));
+        }
+        Ok(Self {
+            target,
+            observer,
+            interval,
+            accuracy: Accuracy::Reduced,
+            dx: 0.0,
+            dy: 0.0,
+            refraction: None,
+            anchors: None,
+        })
+    }
+
+    /// Set the accuracy of the anchor frames
+    pub fn accuracy(mut self, accuracy: Accuracy) -> Self {
+        self.accuracy = accuracy;
+        self.anchors = None;
+        self
+    }
+
+    /// Set the polar offsets (dx, dy in mas) used for the anchor frames
+    pub fn pole_offsets(mut self, dx: f64, dy: f64) -> Self {
+        self.dx = dx;
+        self.dy = dy;
+        self.anchors = None;
+        self
+    }
+
+    /// Refract the elevations with the given model (which is evaluated at every sample)
+    pub fn refraction(mut self, model: &'a dyn RefractionModel) -> Self {
+        self.refraction = Some(model);
+        self
+    }
+
+    /// The local coordinates (az,el in degrees) of the target at the given time
+    ///
+    /// Samples should mostly move forward in time; a sample outside of the current anchors builds new ones.
+    pub fn az_el(&mut self, time: &Timespec) -> super::Result<(f64, f64)> {
+        let anchors = match self.anchors.take() {
+            Some((a0, a1)) if time.diff(&a0.time) >= 0.0 && time.diff(&a1.time) <= 0.0 => (a0, a1),
+            // Slide forward by one interval, reusing the later anchor
+            Some((_, a1)) if time.diff(&a1.time) > 0.0 && time.diff(&a1.time) <= self.interval => {
+                let next = self.anchor(a1.time.offset(self.interval))?;
+                (a1, next)
+            }
+            _ => (
+                self.anchor(*time)?,
+                self.anchor(time.offset(self.interval))?,
+            ),
+        };
+        let (a0, a1) = self.anchors.insert(anchors);
+
+        let dt = time.diff(&a0.time);
+        let f = dt / a1.time.diff(&a0.time);
+        let lerp = |x0: f64, x1: f64| x0 + f * (x1 - x0);
+        // Unwrap the right ascension (hours) and azimuth correction (degrees) across their branch cuts
+        let ra = a0.ra + f * ((a1.ra - a0.ra + 36.0).rem_euclid(24.0) - 12.0);
+        let dec = lerp(a0.dec, a1.dec);
+        let d_az = a0.correction.0
+            + f * ((a1.correction.0 - a0.correction.0 + 540.0).rem_euclid(360.0) - 180.0);
+        let d_el = lerp(a0.correction.1, a1.correction.1);
+        let era = a0.era + ERA_RATE * dt;
+
+        let (az, el) = self.rotate(era, ra, dec);
+        let az = (az + d_az).rem_euclid(360.0);
+        let el = el + d_el;
+        let el = match self.refraction {
+            Some(model) => model.refract(time.jd(Timescale::TT), self.observer, el),
+            None => el,
+        };
+        Ok((az, el))
+    }
+
+    /// Compute the full frame quantities at a time
+    fn anchor(&self, time: Timespec) -> super::Result<Anchor> {
+        let frame = Frame::new(self.accuracy, self.observer, &time, self.dx, self.dy)?;
+        let pos = SkyPosition::try_from_frame(self.target, &frame, ReferenceSystem::CIRS)?;
+        let (az, el) =
+            frame.apparent_local_coordinates(ReferenceSystem::CIRS, self.target, None)?;
+        let era = frame.era();
+        let (simple_az, simple_el) = self.rotate(era, pos.ra(), pos.dec());
+        let correction = (
+            (az - simple_az + 540.0).rem_euclid(360.0) - 180.0,
+            el - simple_el,
+        );
+        Ok(Anchor {
+            time,
+            era,
+            ra: pos.ra(),
+            dec: pos.dec(),
+            correction,
+        })
+    }
+
+    /// The local coordinates (az,el in degrees) of a CIRS place (ra in hours, dec in degrees), rotating by the Earth
+    /// rotation angle (in degrees) alone
+    fn rotate(&self, era: f64, ra: f64, dec: f64) -> (f64, f64) {
+        let ha = (era + self.observer.longitude() - 15.0 * ra).to_radians();
+        let (sin_lat, cos_lat) = self.observer.latitude().to_radians().sin_cos();
+        let (sin_dec, cos_dec) = dec.to_radians().sin_cos();
+        let (sin_ha, cos_ha) = ha.sin_cos();
+        let el = (sin_lat * sin_dec + cos_lat * cos_dec * cos_ha).asin();
+        let az = (-cos_dec * sin_ha).atan2(sin_dec * cos_lat - cos_dec * sin_lat * cos_ha);
+        (az.to_degrees().rem_euclid(360.0), el.to_degrees())
+    }
+}
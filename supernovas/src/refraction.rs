@@ -0,0 +1,172 @@
+//! Atmospheric refraction models
+
+use crate::{error::Error, positions::Observer};
+use std::sync::Mutex;
+use supernovas_sys::{
+    novas_optical_refraction, novas_radio_refraction, novas_refract_wavelength,
+    novas_refraction_type, novas_standard_refraction, novas_wave_refraction,
+};
+
+/// Guards the (process-global) observing wavelength of the SuperNOVAS wavelength-dependent model
+static WAVELENGTH_LOCK: Mutex<()> = Mutex::new(());
+
+/// Convergence tolerance (degrees) of the inverse refraction iteration
+const INV_REFRACT_TOLERANCE: f64 = 1e-7;
+
+/// Maximum number of iterations of the inverse refraction
+const INV_REFRACT_MAX_ITER: usize = 20;
+
+/// The kind of elevation a refraction correction is computed for
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RefractionType {
+    /// The input elevation is astrometric (unrefracted), i.e. computing the refraction to add
+    Astrometric,
+    /// The input elevation is observed (refracted), i.e. computing the refraction to remove
+    Observed,
+}
+
+impl From<RefractionType> for novas_refraction_type {
+    fn from(value: RefractionType) -> Self {
+        match value {
+            RefractionType::Astrometric => novas_refraction_type::NOVAS_REFRACT_ASTROMETRIC,
+            RefractionType::Observed => novas_refraction_type::NOVAS_REFRACT_OBSERVED,
+        }
+    }
+}
+
+/// A model of atmospheric refraction for observers on the surface of the Earth
+///
+/// This is implemented by the built-in SuperNOVAS models and by any closure with the same signature as
+/// [`RefractionModel::refraction`], so site-fitted refraction polynomials can be plugged in directly.
+pub trait RefractionModel: Send + Sync {
+    /// The refraction correction in degrees at elevation `el` (in degrees)
+    ///
+    /// - jd_tt: Terrestrial Time (TT) Julian date of the observation
+    /// - observer: The observer, including its weather parameters
+    /// - kind: Whether `el` is an astrometric or an observed elevation
+    /// - el: The elevation in degrees
+    fn refraction(&self, jd_tt: f64, observer: &Observer, kind: RefractionType, el: f64) -> f64;
+
+    /// Converts an astrometric (unrefracted) elevation in degrees to the observed (refracted) elevation
+    fn refract(&self, jd_tt: f64, observer: &Observer, el: f64) -> f64 {
+        el + self.refraction(jd_tt, observer, RefractionType::Astrometric, el)
+    }
+
+    /// Converts an observed (refracted) elevation in degrees, e.g. as measured by a mount, back to the
+    /// astrometric (unrefracted) elevation
+    ///
+    /// This inverts the astrometric direction of the model iteratively (as `novas_inv_refract` does), so it is
+    /// consistent with [`RefractionModel::refract`] for any model, including ones that only implement the
+    /// astrometric direction.
+    fn unrefract(&self, jd_tt: f64, observer: &Observer, el: f64) -> f64 {
+        let mut el0 = el;
+        for _ in 0..INV_REFRACT_MAX_ITER {
+            let next = el - self.refraction(jd_tt, observer, RefractionType::Astrometric, el0);
+            if (next - el0).abs() < INV_REFRACT_TOLERANCE {
+                return next;
+            }
+            el0 = next;
+        }
+        el0
+    }
+}
+
+impl<F> RefractionModel for F
+where
+    F: Fn(f64, &Observer, RefractionType, f64) -> f64 + Send + Sync,
+{
+    fn refraction(&self, jd_tt: f64, observer: &Observer, kind: RefractionType, el: f64) -> f64 {
+        self(jd_tt, observer, kind, el)
+    }
+}
+
+/// The standard refraction model of NOVAS, using the observer's temperature and pressure
+#[derive(Debug, Default, Copy, Clone)]
+pub struct StandardRefraction;
+
+impl RefractionModel for StandardRefraction {
+    fn refraction(&self, jd_tt: f64, observer: &Observer, kind: RefractionType, el: f64) -> f64 {
+        // Safety: The observer is always initialized and the model only reads from it
+        unsafe { novas_standard_refraction(jd_tt, &observer.inner.on_surf, kind.into(), el) }
+    }
+}
+
+/// The optical refraction model of NOVAS, using the observer's temperature and pressure
+#[derive(Debug, Default, Copy, Clone)]
+pub struct OpticalRefraction;
+
+impl RefractionModel for OpticalRefraction {
+    fn refraction(&self, jd_tt: f64, observer: &Observer, kind: RefractionType, el: f64) -> f64 {
+        // Safety: The observer is always initialized and the model only reads from it
+        unsafe { novas_optical_refraction(jd_tt, &observer.inner.on_surf, kind.into(), el) }
+    }
+}
+
+/// The radio refraction model of SuperNOVAS (Berman & Rockwell 1976), using the observer's temperature, pressure,
+/// and humidity
+#[derive(Debug, Default, Copy, Clone)]
+pub struct RadioRefraction;
+
+impl RefractionModel for RadioRefraction {
+    fn refraction(&self, jd_tt: f64, observer: &Observer, kind: RefractionType, el: f64) -> f64 {
+        // Safety: The observer is always initialized and the model only reads from it
+        unsafe { novas_radio_refraction(jd_tt, &observer.inner.on_surf, kind.into(), el) }
+    }
+}
+
+/// The wavelength-dependent optical/IR refraction model of SuperNOVAS (Ciddor 1996), using the observer's
+/// temperature, pressure, and humidity
+///
+/// Unlike [`OpticalRefraction`], which assumes 0.55 μm, this computes the refraction at the given observing
+/// wavelength, so users get the correct correction for their filter band.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct WaveRefraction {
+    wavelength: f64,
+}
+
+impl WaveRefraction {
+    /// Construct a new wavelength-dependent model for the given observing wavelength in μm
+    pub fn new(wavelength: f64) -> super::Result<Self> {
+        if !wavelength.is_finite() || wavelength <= 0.0 {
+            return Err(Error::InvalidValue("wavelength"));
+        }
+        Ok(Self { wavelength })
+    }
+
+    /// The observing wavelength in μm
+    pub fn wavelength(&self) -> f64 {
+        self.wavelength
+    }
+}
+
+impl RefractionModel for WaveRefraction {
+    fn refraction(&self, jd_tt: f64, observer: &Observer, kind: RefractionType, el: f64) -> f64 {
+        // The C library keeps the wavelength as global state, so hold the lock across setting and using it
+        let _guard = WAVELENGTH_LOCK.lock().unwrap();
+        // Safety: The observer is always initialized and the model only reads from it
+        unsafe {
+            let _ = novas_refract_wavelength(self.wavelength);
+            novas_wave_refraction(jd_tt, &observer.inner.on_surf, kind.into(), el)
+        }
+    }
+}
+
+/// The difference in refraction in degrees between two observing wavelengths (in μm) at astrometric elevation `el`
+/// (in degrees), i.e. the atmospheric dispersion along the vertical
+///
+/// This is positive when the first wavelength is refracted more (as bluer light is), which is what an atmospheric
+/// dispersion corrector must compensate, or a slit must be aligned along.
+pub fn differential_refraction(
+    jd_tt: f64,
+    observer: &Observer,
+    el: f64,
+    wavelength1: f64,
+    wavelength2: f64,
+) -> super::Result<f64> {
+    let first = WaveRefraction::new(wavelength1)?;
+    let second = WaveRefraction::new(wavelength2)?;
+    Ok(
+        first.refraction(jd_tt, observer, RefractionType::Astrometric, el)
+            - second.refraction(jd_tt, observer, RefractionType::Astrometric, el),
+    )
+}
@@ -0,0 +1,202 @@
+//! Earth-satellite targets from two-line element sets, propagated with SGP4
+
+use crate::{
+    doppler::{RangeRate, AU},
+    error::Error,
+    positions::{Frame, ReferenceSystem},
+    time::{Timescale, Timespec},
+};
+use std::mem::MaybeUninit;
+use supernovas_sys::{e_tilt, novas_accuracy, novas_app_to_hor, tod_to_gcrs, vector2radec};
+
+/// Step used for the finite-difference rates, in seconds
+const RATE_STEP: f64 = 1.0;
+
+/// An Earth-orbiting satellite described by a two-line element set (TLE)
+pub struct Satellite {
+    elements: sgp4::Elements,
+    constants: sgp4::Constants,
+}
+
+/// The topocentric pointing to a satellite
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SatellitePointing {
+    /// Azimuth in degrees
+    pub az: f64,
+    /// Elevation in degrees
+    pub el: f64,
+    /// Rate of change of azimuth in degrees/s
+    pub az_rate: f64,
+    /// Rate of change of elevation in degrees/s
+    pub el_rate: f64,
+}
+
+impl Satellite {
+    /// Construct a new [`Satellite`] from the two lines of a TLE, with an optional object name
+    pub fn from_tle(name: Option<&str>, line1: &str, line2: &str) -> super::Result<Self> {
+        let elements =
+            sgp4::Elements::from_tle(name.map(str::to_string), line1.as_bytes(), line2.as_bytes())
+                .map_err(|e| Error::Sgp4(e.to_string()))?;
+        let constants =
+            sgp4::Constants::from_elements(&elements).map_err(|e| Error::Sgp4(e.to_string()))?;
+        Ok(Self {
+            elements,
+            constants,
+        })
+    }
+
+    /// The name of the satellite, if the TLE had one
+    pub fn name(&self) -> Option<&str> {
+        self.elements.object_name.as_deref()
+    }
+
+    /// The epoch of the element set as a UTC Julian date
+    pub fn epoch(&self) -> f64 {
+        // SGP4 counts Julian years from J2000 in UTC
+        2_451_545.0 + self.elements.epoch() * 365.25
+    }
+
+    /// Propagate the elements to the given time, returning the TEME position (km) and velocity (km/s)
+    pub fn teme_state(&self, time: &Timespec) -> super::Result<([f64; 3], [f64; 3])> {
+        let minutes = (time.jd(Timescale::UTC) - self.epoch()) * 1440.0;
+        let prediction = self
+            .constants
+            .propagate(sgp4::MinutesSinceEpoch(minutes))
+            .map_err(|e| Error::Sgp4(e.to_string()))?;
+        Ok((prediction.position, prediction.velocity))
+    }
+
+    /// Propagate the elements to the given time, returning the geocentric GCRS position (km) and velocity (km/s)
+    pub fn gcrs_state(&self, time: &Timespec) -> super::Result<([f64; 3], [f64; 3])> {
+        let (pos, vel) = self.teme_state(time)?;
+        let jd_tdb = time.jd(Timescale::TDB);
+
+        // TEME shares the true equator of date but has its origin at the mean equinox,
+        // so rotate by the equation of the equinoxes to get to the true equinox of date (TOD)
+        let mut mobl = 0.0;
+        let mut tobl = 0.0;
+        let mut ee = 0.0;
+        let mut dpsi = 0.0;
+        let mut deps = 0.0;
+        // Safety: None of the output pointers are null
+        unsafe {
+            let _ = e_tilt(
+                jd_tdb,
+                novas_accuracy::NOVAS_FULL_ACCURACY,
+                &mut mobl,
+                &mut tobl,
+                &mut ee,
+                &mut dpsi,
+                &mut deps,
+            );
+        }
+        // The equation of the equinoxes is in seconds of time
+        let (sin_ee, cos_ee) = (ee * 15.0 / 3600.0).to_radians().sin_cos();
+        let rotate = |v: [f64; 3]| {
+            [
+                cos_ee * v[0] - sin_ee * v[1],
+                sin_ee * v[0] + cos_ee * v[1],
+                v[2],
+            ]
+        };
+
+        Ok((
+            tod_to_gcrs_vec(jd_tdb, &rotate(pos))?,
+            tod_to_gcrs_vec(jd_tdb, &rotate(vel))?,
+        ))
+    }
+
+    /// Computes the topocentric azimuth and elevation (in degrees), and their rates, of the satellite in the given frame
+    ///
+    /// Light-time and aberration are not applied, as they are negligible for near-Earth orbits at the level of the SGP4
+    /// model itself.
+    pub fn local_coordinates(&self, frame: &Frame) -> super::Result<SatellitePointing> {
+        let (az, el) = self.az_el(frame)?;
+        // Step forward in time to get the rates
+        let later = frame.time.offset(RATE_STEP);
+        let later_frame = Frame::new(
+            frame.inner.accuracy.into(),
+            frame.observer,
+            later,
+            frame.inner.dx,
+            frame.inner.dy,
+        )?;
+        let (az_later, el_later) = self.az_el(&later_frame)?;
+        // Unwrap the azimuth difference across north
+        let d_az = (az_later - az + 540.0).rem_euclid(360.0) - 180.0;
+        Ok(SatellitePointing {
+            az,
+            el,
+            az_rate: d_az / RATE_STEP,
+            el_rate: (el_later - el) / RATE_STEP,
+        })
+    }
+
+    /// Computes the topocentric range and range-rate of the satellite in the given frame
+    pub fn range_rate(&self, frame: &Frame) -> super::Result<RangeRate> {
+        let (pos, vel) = self.gcrs_state(&frame.time)?;
+        // Observer geocentric state, from AU and AU/day to km and km/s
+        let obs_pos: [f64; 3] =
+            std::array::from_fn(|i| (frame.inner.obs_pos[i] - frame.inner.earth_pos[i]) * AU);
+        let obs_vel: [f64; 3] = std::array::from_fn(|i| {
+            (frame.inner.obs_vel[i] - frame.inner.earth_vel[i]) * AU / 86_400.0
+        });
+        let rel_pos = std::array::from_fn(|i| pos[i] - obs_pos[i]);
+        let rel_vel = std::array::from_fn(|i| vel[i] - obs_vel[i]);
+        Ok(RangeRate::from_state(&rel_pos, &rel_vel))
+    }
+
+    /// The topocentric (GCRS) position of the satellite relative to the frame's observer, in AU
+    pub(crate) fn topocentric_position(&self, frame: &Frame) -> super::Result<[f64; 3]> {
+        let (pos, _) = self.gcrs_state(&frame.time)?;
+        Ok(std::array::from_fn(|i| {
+            pos[i] / AU - (frame.inner.obs_pos[i] - frame.inner.earth_pos[i])
+        }))
+    }
+
+    fn az_el(&self, frame: &Frame) -> super::Result<(f64, f64)> {
+        let topo = self.topocentric_position(frame)?;
+        let mut ra = 0.0;
+        let mut dec = 0.0;
+        let mut az = 0.0;
+        let mut el = 0.0;
+        // Safety: All the pointers are valid and non-null
+        unsafe {
+            let ret = vector2radec(topo.as_ptr(), &mut ra, &mut dec);
+            if ret != 0 {
+                return Err(Error::LowerLevel(ret));
+            }
+            let ret = novas_app_to_hor(
+                &frame.inner as *const _,
+                ReferenceSystem::GCRS.into(),
+                ra,
+                dec,
+                None,
+                &mut az,
+                &mut el,
+            );
+            if ret != 0 {
+                return Err(Error::LowerLevel(ret));
+            }
+        }
+        Ok((az, el))
+    }
+}
+
+/// Rotate a vector from the true equator and equinox of date to GCRS
+fn tod_to_gcrs_vec(jd_tdb: f64, v: &[f64; 3]) -> super::Result<[f64; 3]> {
+    let mut out = MaybeUninit::<[f64; 3]>::uninit();
+    // Safety: The input and output are both valid 3-vectors
+    unsafe {
+        let ret = tod_to_gcrs(
+            jd_tdb,
+            novas_accuracy::NOVAS_FULL_ACCURACY,
+            v.as_ptr(),
+            out.as_mut_ptr() as *mut f64,
+        );
+        if ret != 0 {
+            return Err(Error::LowerLevel(ret));
+        }
+        Ok(out.assume_init())
+    }
+}
@@ -0,0 +1,66 @@
+//! Ordering a list of targets for a night of observing
+//!
+//! This builds on the [`plan`](crate::plan) module: each target's observable windows are found, and the targets are
+//! ordered by when they transit (or reach their highest point) within the night, which is the usual order to
+//! observe them in to keep airmass low.
+
+use crate::{
+    plan::{best_times, visibility, Constraint},
+    positions::{Observer, Placeable},
+    time::Timespec,
+};
+
+/// A target placed in the schedule
+#[derive(Debug, Clone)]
+pub struct Scheduled {
+    /// The index of the target in the list given to [`by_transit`]
+    pub index: usize,
+    /// The time of the transit, or of the highest elevation if the target doesn't transit while observable
+    pub transit: Timespec,
+    /// The (unrefracted) elevation at `transit` in degrees
+    pub el: f64,
+    /// The suggested start of the observation, when the target becomes observable
+    pub start: Timespec,
+    /// The suggested end of the observation, when the target stops being observable
+    pub stop: Timespec,
+}
+
+/// Orders targets by transit time over the night `[start, stop]`
+///
+/// Each target is scheduled in its observable window (above `min_el` in degrees and the observer's horizon, with
+/// all of the constraints met, see [`visibility`]) that reaches the highest elevation. Targets that are never
+/// observable during the night are left out.
+pub fn by_transit<T: Placeable>(
+    targets: &[T],
+    observer: &Observer,
+    start: &Timespec,
+    stop: &Timespec,
+    min_el: f64,
+    constraints: &[Constraint],
+) -> super::Result<Vec<Scheduled>> {
+    let mut schedule = vec![];
+    for (index, target) in targets.iter().enumerate() {
+        let mut best: Option<Scheduled> = None;
+        for window in visibility(target, observer, start, stop, min_el, constraints)? {
+            let peak = best_times(
+                std::slice::from_ref(target),
+                observer,
+                &window.start,
+                &window.stop,
+            )?
+            .remove(0);
+            if best.as_ref().is_none_or(|best| peak.el > best.el) {
+                best = Some(Scheduled {
+                    index,
+                    transit: peak.time,
+                    el: peak.el,
+                    start: window.start,
+                    stop: window.stop,
+                });
+            }
+        }
+        schedule.extend(best);
+    }
+    schedule.sort_by(|a, b| a.transit.diff(&b.transit).total_cmp(&0.0));
+    Ok(schedule)
+}
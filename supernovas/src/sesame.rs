@@ -0,0 +1,176 @@
+//! Name resolution through the CDS Sesame service
+//!
+//! Sesame tries SIMBAD, then NED, then VizieR in turn, so a single call resolves both galactic identifiers (which
+//! SIMBAD covers best) and extragalactic ones (which NED covers best). The service that supplied the answer is
+//! reported with the result.
+
+use crate::{
+    error::Error,
+    net::{default_client, percent_encode, RateLimiter, RetryPolicy},
+    positions::CatalogEntry,
+};
+use quick_xml::{events::Event, reader::Reader};
+use reqwest::blocking::Client;
+use std::{collections::HashMap, io::BufReader};
+
+/// The base URL of the Sesame service, returning XML and trying SIMBAD, NED, then VizieR
+const SESAME_URL: &str = "https://cds.unistra.fr/cgi-bin/nph-sesame/-oxp/SNV";
+
+/// The name resolution services Sesame queries
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NameService {
+    Simbad,
+    Ned,
+    VizieR,
+}
+
+/// An identifier resolved by Sesame
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolved {
+    /// The astrometry of the object (ICRS, J2000.0)
+    pub entry: CatalogEntry,
+    /// The service that resolved the identifier
+    pub service: NameService,
+    /// The redshift of the object, if the service knows it
+    pub redshift: Option<f64>,
+}
+
+/// The CDS Sesame name resolver
+#[derive(Debug, Clone)]
+pub struct Sesame {
+    /// The HTTP client used for the queries
+    client: Client,
+    /// Timeout and retry behavior of the queries
+    retry: RetryPolicy,
+    /// The rate limit of the queries, if any
+    limiter: Option<RateLimiter>,
+}
+
+impl Default for Sesame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sesame {
+    /// Construct a new Sesame resolver
+    pub fn new() -> Self {
+        Self {
+            client: default_client(),
+            retry: RetryPolicy::default(),
+            limiter: None,
+        }
+    }
+
+    /// Use the given HTTP client (with its proxy, TLS, and timeout configuration) for all queries
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Use the given timeout and retry policy for all queries
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Share the given rate limiter for all queries
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    /// Resolve an identifier, using the first of SIMBAD, NED, and VizieR that knows it
+    pub fn resolve(&self, name: &str) -> super::Result<Resolved> {
+        // Sesame takes the bare identifier as its query string rather than a key=value parameter
+        let query_string = format!("{SESAME_URL}?{}", percent_encode(name.trim()));
+        let resp = self
+            .retry
+            .send(self.limiter.as_ref(), || self.client.get(&query_string))?;
+        let (service, fields) =
+            parse_resolver(resp)?.ok_or_else(|| Error::NotFound(name.to_string()))?;
+        let number = |key: &str| fields.get(key).and_then(|s| s.trim().parse::<f64>().ok());
+        let (Some(ra), Some(dec)) = (number("jradeg"), number("jdedeg")) else {
+            return Err(Error::NotFound(name.to_string()));
+        };
+        let entry = CatalogEntry::new(
+            name,
+            "",
+            0,
+            ra / 15.0,
+            dec,
+            number("pm/pmRA").unwrap_or(0.0),
+            number("pm/pmDE").unwrap_or(0.0),
+            number("plx/v").unwrap_or(0.0),
+            number("Vel/v").unwrap_or(0.0),
+        )?;
+        Ok(Resolved {
+            entry,
+            service,
+            redshift: number("z"),
+        })
+    }
+}
+
+/// Find the first resolver in a Sesame XML response that found the object, collecting the text of its fields
+///
+/// Nested fields are keyed by their path within the resolver, e.g. `plx/v`.
+fn parse_resolver(
+    resp: impl std::io::Read,
+) -> super::Result<Option<(NameService, HashMap<String, String>)>> {
+    let mut xml_reader = Reader::from_reader(BufReader::new(resp));
+    let mut buf = Vec::new();
+    let mut path: Vec<String> = vec![];
+    let mut resolver: Option<(NameService, HashMap<String, String>)> = None;
+    loop {
+        match xml_reader.read_event_into(&mut buf) {
+            Ok(Event::Eof) => break,
+            Ok(Event::Start(e)) => {
+                let tag = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                // Resolvers are named like "S=Simbad (CDS, via client/server)"
+                let service = (tag == "Resolver" && resolver.is_none())
+                    .then(|| e.try_get_attribute("name").ok().flatten())
+                    .flatten()
+                    .and_then(|a| match a.value.first() {
+                        Some(b'S') => Some(NameService::Simbad),
+                        Some(b'N') => Some(NameService::Ned),
+                        Some(b'V') => Some(NameService::VizieR),
+                        _ => None,
+                    });
+                match service {
+                    Some(service) => {
+                        resolver = Some((service, HashMap::new()));
+                        path.clear();
+                    }
+                    None => path.push(tag),
+                }
+            }
+            Ok(Event::Text(e)) => {
+                if let Some((_, fields)) = resolver.as_mut() {
+                    if !path.is_empty() {
+                        let text = e.unescape().map_err(|_| Error::InvalidString)?;
+                        fields.insert(path.join("/"), text.into_owned());
+                    }
+                }
+            }
+            Ok(Event::End(e)) => {
+                if e.name().as_ref() == b"Resolver" && resolver.is_some() && path.is_empty() {
+                    // A resolver that didn't find the object only has INFO or ERROR fields, so move on to the next
+                    if resolver
+                        .as_ref()
+                        .is_some_and(|(_, fields)| fields.contains_key("jradeg"))
+                    {
+                        break;
+                    }
+                    resolver = None;
+                } else {
+                    path.pop();
+                }
+            }
+            Err(_) => return Err(Error::InvalidString),
+            _ => (),
+        }
+        buf.clear();
+    }
+    Ok(resolver.filter(|(_, fields)| fields.contains_key("jradeg")))
+}
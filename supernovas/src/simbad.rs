@@ -1,102 +1,495 @@
 //! Utilities for querying the SIMBAD catalog entries
 
-use std::io::BufReader;
+use crate::{
+    cache::ResolutionCache,
+    catalog::{separation, CatalogSource},
+    coords::{Declination, RightAscension},
+    error::Error,
+    net::{default_client, is_transient, percent_encode, RateLimiter, RetryPolicy},
+    positions::{CatalogEntry, CatalogSystem},
+    votable::VoTable,
+};
+use reqwest::blocking::Client;
 
-use crate::positions::CatalogEntry;
-use quick_xml::{events::Event, reader::Reader};
+/// The SIMBAD mirrors
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SimbadMirror {
+    /// The primary site at the CDS in Strasbourg
+    Cds,
+    /// The mirror at the Harvard-Smithsonian Center for Astrophysics
+    Cfa,
+}
 
-impl CatalogEntry {
-    /// Construct a [`CatalogEntry`] from a SIMBAD query
-    pub fn from_simbad(ident: &str, catalog: &str) -> super::Result<Self> {
-        // By default, this is in ICRS, J2000
+impl SimbadMirror {
+    /// The base URL of the SIMBAD service at this mirror
+    pub fn url(&self) -> &'static str {
+        match self {
+            SimbadMirror::Cds => "https://simbad.cds.unistra.fr/simbad",
+            SimbadMirror::Cfa => "https://simbad.cfa.harvard.edu/simbad",
+        }
+    }
+}
+
+/// Additional VOTable output parameters of the richer object queries, appended after the astrometry
+const OBJECT_PARAMS: &str = "flux(B),flux(V),flux(R),otype,sp";
+
+/// A SIMBAD object: its astrometry, along with the basic data useful for planning
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimbadObject {
+    /// The astrometry of the object, with any missing space motion set to zero
+    pub entry: CatalogEntry,
+    /// Which parts of the astrometry SIMBAD had no value for
+    pub missing: MissingAstrometry,
+    /// Johnson B magnitude
+    pub mag_b: Option<f64>,
+    /// Johnson V magnitude
+    pub mag_v: Option<f64>,
+    /// Cousins R magnitude
+    pub mag_r: Option<f64>,
+    /// The (abbreviated) SIMBAD object type, e.g. `*` for a star or `G` for a galaxy
+    pub object_type: String,
+    /// The spectral type, e.g. `A0Va`
+    pub spectral_type: Option<String>,
+}
+
+/// The parts of the astrometry that SIMBAD has no value for
+///
+/// Many objects (e.g. galaxies, or faint stars) have no measured proper motion, parallax, or radial velocity. These
+/// are set to zero in the [`CatalogEntry`], which places the object as if it were fixed at infinite distance.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct MissingAstrometry {
+    /// No proper motion (in either coordinate)
+    pub proper_motion: bool,
+    /// No parallax
+    pub parallax: bool,
+    /// No radial velocity
+    pub radial_velocity: bool,
+    /// No numeric catalog number, e.g. for `2MASS J05551028+0724255`, so the entry's number is zero
+    pub catalog_number: bool,
+}
+
+impl MissingAstrometry {
+    /// Whether any of the astrometry is missing (not counting the catalog number)
+    pub fn any(&self) -> bool {
+        self.proper_motion || self.parallax || self.radial_velocity
+    }
+}
+
+/// The optional astrometric values of a SIMBAD row
+struct SpaceMotion {
+    pm_ra: Option<f64>,
+    pm_dec: Option<f64>,
+    parallax: Option<f64>,
+    rad_vel: Option<f64>,
+}
+
+impl SpaceMotion {
+    /// Which values are missing
+    fn missing(&self) -> MissingAstrometry {
+        MissingAstrometry {
+            proper_motion: self.pm_ra.is_none() || self.pm_dec.is_none(),
+            parallax: self.parallax.is_none(),
+            radial_velocity: self.rad_vel.is_none(),
+        }
+    }
+}
+
+/// The SIMBAD astronomical database, as a [`CatalogSource`]
+///
+/// Proper motions, parallaxes, and radial velocities SIMBAD doesn't have are set to zero; [`Simbad::object`] reports
+/// which ones were missing.
+#[derive(Debug, Clone)]
+pub struct Simbad {
+    /// The catalog used for the identifier and number of the returned entries
+    catalog: String,
+    /// The HTTP client used for the queries
+    client: Client,
+    /// Timeout and retry behavior of the queries
+    retry: RetryPolicy,
+    /// The rate limit of the queries, if any
+    limiter: Option<RateLimiter>,
+    /// The reference system and epoch of the returned entries
+    system: CatalogSystem,
+    /// The mirrors to query, in order of preference
+    mirrors: Vec<SimbadMirror>,
+}
+
+impl Simbad {
+    /// Construct a new SIMBAD source, reporting identifiers from the given catalog (e.g. "HIP")
+    pub fn new(catalog: &str) -> Self {
+        Self {
+            catalog: catalog.to_string(),
+            client: default_client(),
+            retry: RetryPolicy::default(),
+            limiter: None,
+            system: CatalogSystem::default(),
+            mirrors: vec![SimbadMirror::Cds, SimbadMirror::Cfa],
+        }
+    }
+
+    /// Use the given HTTP client (with its proxy, TLS, and timeout configuration) for all queries
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Use the given timeout and retry policy for all queries
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Share the given rate limiter for all queries
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    /// Query the given mirrors, in order of preference, instead of the CDS then the CfA
+    ///
+    /// When a mirror is unreachable (after the retries of the [`RetryPolicy`]), the query fails over to the next one.
+    /// Passing a single mirror disables the failover.
+    pub fn with_mirrors(mut self, mirrors: &[SimbadMirror]) -> Self {
+        self.mirrors = mirrors.to_vec();
+        self
+    }
+
+    /// Return entries in the given reference system and epoch, instead of ICRS at J2000.0
+    ///
+    /// SIMBAD is always queried in ICRS at J2000.0, and the results are converted locally (see
+    /// [`CatalogEntry::to_system`]). Cone search centers are still given in ICRS.
+    pub fn with_system(mut self, system: CatalogSystem) -> Self {
+        self.system = system;
+        self
+    }
+
+    /// Build an entry from a row of the results, in the requested reference system, along with what was missing
+    fn entry(
+        &self,
+        columns: &Columns,
+        row: &[String],
+    ) -> super::Result<(CatalogEntry, MissingAstrometry)> {
+        let (entry, missing) = columns.entry(row)?;
+        // SIMBAD returns ICRS positions at J2000.0, which is the default system of a new entry
+        Ok((entry.to_system(self.system)?, missing))
+    }
+
+    /// Build an object from a row of the results of a query with the [`OBJECT_PARAMS`]
+    fn object_from_row(&self, columns: &Columns, row: &[String]) -> super::Result<SimbadObject> {
+        let mag = |col: Option<usize>| text(row, col).and_then(|s| s.parse().ok());
+        let (entry, missing) = self.entry(columns, row)?;
+        Ok(SimbadObject {
+            entry,
+            missing,
+            mag_b: mag(columns.mag_b),
+            mag_v: mag(columns.mag_v),
+            mag_r: mag(columns.mag_r),
+            object_type: text(row, columns.object_type)
+                .unwrap_or_default()
+                .to_string(),
+            spectral_type: text(row, columns.spectral_type).map(str::to_string),
+        })
+    }
+
+    /// The VOTable output parameters, which determine the columns of the results
+    fn output_params(&self) -> String {
+        format!(
+            "output.format=votable&output.params=main_id,id({}),ra,dec,pmra,pmdec,plx,rv_value",
+            self.catalog
+        )
+    }
+
+    /// Resolve a single object by its identifier, including its magnitudes, object type, and spectral type
+    pub fn object(&self, name: &str) -> super::Result<SimbadObject> {
         let query_string = format!(
-            "https://simbad.cds.unistra.fr/simbad/sim-id?output.format=votable&Ident={ident}&output.params=main_id,id({catalog}),ra,dec,pmra,pmdec,plx,rv_value"
+            "/sim-id?Ident={}&{},{OBJECT_PARAMS}",
+            percent_encode(name),
+            self.output_params()
         );
-        let resp = reqwest::blocking::get(query_string)?;
-        let bufread = BufReader::new(resp);
-        let mut xml_reader = Reader::from_reader(bufread);
-
-        let mut columns = Vec::new();
-        let mut buf = Vec::new();
-
-        let mut td_read = false;
-        let mut td_text = false;
-        // Seek to the table and pull out all the table entries
-        loop {
-            match xml_reader.read_event_into(&mut buf) {
-                // exits the loop when reaching end of file
-                Ok(Event::Eof) => break,
-                Ok(Event::Start(e)) => {
-                    if matches!(e.name().as_ref(), b"TD") {
-                        td_read = true;
-                    }
-                }
-                Ok(Event::Text(e)) => {
-                    if td_read {
-                        columns.push(e.unescape().unwrap().into_owned());
-                        td_text = true;
-                    }
-                }
-                Ok(Event::End(e)) => {
-                    if matches!(e.name().as_ref(), b"TD") {
-                        td_read = false;
-                        if !td_text {
-                            // Empty columns
-                            columns.push("".to_string());
-                        }
-                        td_text = false;
-                    }
-                }
-                _ => (),
+        let (columns, rows) = self.query_votable(&query_string)?;
+        match rows.first() {
+            Some(row) => self.object_from_row(&columns, row),
+            None => Err(Error::NotFound(name.to_string())),
+        }
+    }
+
+    /// Find all objects within a cone on the sky, including their magnitudes, object types, and spectral types
+    ///
+    /// The arguments are as for [`CatalogSource::cone`].
+    pub fn cone_objects(&self, ra: f64, dec: f64, radius: f64) -> super::Result<Vec<SimbadObject>> {
+        let (columns, rows) =
+            self.query_votable(&self.cone_query(ra, dec, radius, OBJECT_PARAMS))?;
+        rows.iter()
+            .map(|row| self.object_from_row(&columns, row))
+            .collect()
+    }
+
+    /// Find the identified object closest to a position, e.g. to label a detection
+    ///
+    /// The arguments are as for [`CatalogSource::cone`]. This errors with [`Error::NotFound`] if there are no objects
+    /// within the radius.
+    pub fn nearest(&self, ra: f64, dec: f64, radius: f64) -> super::Result<SimbadObject> {
+        let (columns, rows) =
+            self.query_votable(&self.cone_query(ra, dec, radius, OBJECT_PARAMS))?;
+        let mut nearest: Option<(&Vec<String>, f64)> = None;
+        for row in &rows {
+            // Compare the ICRS positions, before any conversion into the requested system
+            let (entry, _) = columns.entry(row)?;
+            let sep = separation(ra, dec, entry.ra(), entry.dec());
+            if nearest.is_none_or(|(_, best)| sep < best) {
+                nearest = Some((row, sep));
             }
-            buf.clear();
         }
+        match nearest {
+            Some((row, _)) => self.object_from_row(&columns, row),
+            None => Err(Error::NotFound(format!("{ra} {dec}"))),
+        }
+    }
 
-        // Parse catalog info
-        let (cat, num) = if let Some((cat, id)) = columns[1].split_once(' ') {
-            (cat, id.parse().expect("Invalid catalog ID"))
+    /// The query string of a cone search, with any extra output parameters
+    fn cone_query(&self, ra: f64, dec: f64, radius: f64, extra_params: &str) -> String {
+        let extra = if extra_params.is_empty() {
+            String::new()
         } else {
-            ("", 0)
+            format!(",{extra_params}")
         };
+        format!(
+            "/sim-coo?Coord={}%20{dec}&CooFrame=ICRS&Radius={radius}&Radius.unit=deg&{}{extra}",
+            ra * 15.0,
+            self.output_params()
+        )
+    }
 
-        // Parse RA and DEC
-        let ra_parts = columns[2]
-            .split_whitespace()
-            .into_iter()
-            .collect::<Vec<_>>();
-        let (ra_h, ra_m, ra_s) = (
-            ra_parts[0].parse().expect("invalid ra_h"),
-            ra_parts[1].parse().expect("invalid ra_m"),
-            ra_parts[2].parse().expect("invalid ra_s"),
-        );
+    /// Perform a query (the path and query string after the base URL) that returns a VOTable, locating the output
+    /// columns and collecting the rows of the table
+    fn query_votable(&self, query: &str) -> super::Result<(Columns, Vec<Vec<String>>)> {
+        let mut mirrors = self.mirrors.iter().peekable();
+        let resp = loop {
+            let Some(mirror) = mirrors.next() else {
+                return Err(Error::InvalidValue("mirrors"));
+            };
+            let url = format!("{}{query}", mirror.url());
+            match self
+                .retry
+                .send(self.limiter.as_ref(), || self.client.get(&url))
+            {
+                Ok(resp) => break resp,
+                // Move on to the next mirror if this one is down
+                Err(e) if is_transient(&e) && mirrors.peek().is_some() => continue,
+                Err(e) => return Err(e.into()),
+            }
+        };
+        let table = VoTable::parse(resp)?;
+        Ok((Columns::new(&table)?, table.rows))
+    }
+}
 
-        let dec_parts = columns[3]
-            .split_whitespace()
-            .into_iter()
-            .collect::<Vec<_>>();
-        let (dec_d, dec_m, dec_s) = (
-            dec_parts[0].parse().expect("invalid dec_d"),
-            dec_parts[1].parse().expect("invalid dec_m"),
-            dec_parts[2].parse().expect("invalid dec_s"),
+impl CatalogSource for Simbad {
+    fn resolve(&self, name: &str) -> super::Result<CatalogEntry> {
+        let query_string = format!(
+            "/sim-id?Ident={}&{}",
+            percent_encode(name),
+            self.output_params()
         );
+        let (columns, rows) = self.query_votable(&query_string)?;
+        match rows.first() {
+            Some(row) => Ok(self.entry(&columns, row)?.0),
+            None => Err(Error::NotFound(name.to_string())),
+        }
+    }
 
-        // SIMBAD appends NAME to qualify common or historical names, which we want to drop
-        let name = if columns[0].starts_with("NAME") {
-            columns[0].strip_prefix("NAME ").unwrap().to_string()
+    fn cone(&self, ra: f64, dec: f64, radius: f64) -> super::Result<Vec<CatalogEntry>> {
+        let (columns, rows) = self.query_votable(&self.cone_query(ra, dec, radius, ""))?;
+        rows.iter()
+            .map(|row| Ok(self.entry(&columns, row)?.0))
+            .collect()
+    }
+}
+
+impl CatalogEntry {
+    /// Construct a [`CatalogEntry`] from a SIMBAD query
+    ///
+    /// Results are memoized in the process-wide [`ResolutionCache::global`], so repeated lookups of the same
+    /// identifier only query SIMBAD once.
+    pub fn from_simbad(ident: &str, catalog: &str) -> super::Result<Self> {
+        Self::from_simbad_in(ident, catalog, CatalogSystem::default())
+    }
+
+    /// Construct a [`CatalogEntry`] from a SIMBAD query, in the given reference system and epoch
+    ///
+    /// Results are memoized like those of [`CatalogEntry::from_simbad`].
+    pub fn from_simbad_in(
+        ident: &str,
+        catalog: &str,
+        system: CatalogSystem,
+    ) -> super::Result<Self> {
+        let key = format!("simbad:{catalog}:{system:?}:{}", ident.trim());
+        ResolutionCache::global().get_or_resolve(&key, || {
+            Simbad::new(catalog).with_system(system).resolve(ident)
+        })
+    }
+}
+
+/// The positions of the output columns in a SIMBAD VOTable
+struct Columns {
+    main_id: usize,
+    id: Option<usize>,
+    ra: usize,
+    dec: usize,
+    /// Whether the coordinates are in decimal degrees, rather than sexagesimal
+    degrees: bool,
+    pm_ra: Option<usize>,
+    pm_dec: Option<usize>,
+    parallax: Option<usize>,
+    rad_vel: Option<usize>,
+    mag_b: Option<usize>,
+    mag_v: Option<usize>,
+    mag_r: Option<usize>,
+    object_type: Option<usize>,
+    spectral_type: Option<usize>,
+}
+
+impl Columns {
+    /// Locate the columns by their FIELD names (or UCDs)
+    fn new(table: &VoTable) -> super::Result<Self> {
+        if table.fields.is_empty() {
+            // Without any declarations, all we can do is trust the requested output order
+            return Ok(Self {
+                main_id: 0,
+                id: Some(1),
+                ra: 2,
+                dec: 3,
+                degrees: false,
+                pm_ra: Some(4),
+                pm_dec: Some(5),
+                parallax: Some(6),
+                rad_vel: Some(7),
+                mag_b: Some(8),
+                mag_v: Some(9),
+                mag_r: Some(10),
+                object_type: Some(11),
+                spectral_type: Some(12),
+            });
+        }
+        let required = |names: &[&str], ucd: &str| {
+            table
+                .column(names, Some(ucd))
+                .ok_or_else(|| Error::Table(format!("SIMBAD result has no {} column", names[0])))
+        };
+        let ra = required(&["RA"], "pos.eq.ra;meta.main")?;
+        let dec = required(&["DEC"], "pos.eq.dec;meta.main")?;
+        Ok(Self {
+            main_id: required(&["MAIN_ID"], "meta.id;meta.main")?,
+            // The catalog identifier column is named after the catalog, e.g. ID_HIP
+            id: table
+                .fields
+                .iter()
+                .position(|field| field.name.to_ascii_uppercase().starts_with("ID")),
+            ra,
+            dec,
+            degrees: table.fields[ra].unit.as_deref() == Some("deg"),
+            pm_ra: table.column(&["PMRA"], Some("pos.pm;pos.eq.ra")),
+            pm_dec: table.column(&["PMDEC"], Some("pos.pm;pos.eq.dec")),
+            parallax: table.column(&["PLX_VALUE", "PLX"], Some("pos.parallax.trig")),
+            rad_vel: table.column(&["RV_VALUE"], Some("spect.dopplerVeloc.opt")),
+            mag_b: table.column(&["FLUX_B"], Some("phot.mag;em.opt.B")),
+            mag_v: table.column(&["FLUX_V"], Some("phot.mag;em.opt.V")),
+            mag_r: table.column(&["FLUX_R"], Some("phot.mag;em.opt.R")),
+            object_type: table.column(&["OTYPE"], Some("src.class")),
+            spectral_type: table.column(&["SP_TYPE"], Some("src.spType")),
+        })
+    }
+
+    /// Build a [`CatalogEntry`] from a single row of the table, defaulting any missing space motion to zero
+    fn entry(&self, row: &[String]) -> super::Result<(CatalogEntry, MissingAstrometry)> {
+        let required = |col: usize| {
+            text(row, Some(col)).ok_or_else(|| Error::Table("Incomplete SIMBAD row".to_string()))
+        };
+        let number = |col: Option<usize>| text(row, col).and_then(|s| s.parse().ok());
+
+        // Parse catalog info
+        // The catalog name may itself contain spaces (e.g. `Gaia DR3 123`), and not every identifier is numeric
+        let (cat, num) = match text(row, self.id).and_then(|id| id.rsplit_once(' ')) {
+            Some((cat, id)) => (cat.trim(), id.parse().ok()),
+            None => ("", None),
+        };
+
+        // Parse RA and DEC
+        let invalid = |what: &str| Error::Table(format!("Invalid SIMBAD {what}"));
+        let (ra, dec) = if self.degrees {
+            let ra: f64 = required(self.ra)?.parse().map_err(|_| invalid("ra"))?;
+            let dec: f64 = required(self.dec)?.parse().map_err(|_| invalid("dec"))?;
+            (ra / 15.0, dec)
         } else {
-            columns[0].to_string()
+            let ra: RightAscension = required(self.ra)?.parse()?;
+            let dec: Declination = required(self.dec)?.parse()?;
+            (ra.hours(), dec.degrees())
         };
 
-        CatalogEntry::new_hms(
-            &name,
+        // SIMBAD appends NAME to qualify common or historical names, which we want to drop
+        let main_id = required(self.main_id)?;
+        let name = main_id.strip_prefix("NAME ").unwrap_or(main_id);
+
+        let motion = SpaceMotion {
+            pm_ra: number(self.pm_ra),
+            pm_dec: number(self.pm_dec),
+            parallax: number(self.parallax),
+            rad_vel: number(self.rad_vel),
+        };
+        let entry = CatalogEntry::new(
+            name,
             cat,
-            num,
-            (ra_h, ra_m, ra_s),
-            (dec_d, dec_m, dec_s),
-            columns[4].parse().unwrap_or(0.0),
-            columns[5].parse().unwrap_or(0.0),
-            columns[6].parse().unwrap_or(0.0),
-            columns[7].parse().unwrap_or(0.0),
-        )
+            num.unwrap_or(0),
+            ra,
+            dec,
+            motion.pm_ra.unwrap_or(0.0),
+            motion.pm_dec.unwrap_or(0.0),
+            motion.parallax.unwrap_or(0.0),
+            motion.rad_vel.unwrap_or(0.0),
+        )?;
+        let missing = MissingAstrometry {
+            catalog_number: num.is_none(),
+            ..motion.missing()
+        };
+        Ok((entry, missing))
+    }
+}
+
+/// The trimmed text of a cell, if it exists and isn't empty or the "~" SIMBAD uses for missing values
+fn text(row: &[String], col: Option<usize>) -> Option<&str> {
+    row.get(col?)
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty() && *s != "~")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A row in the requested output order, for a table without FIELD declarations
+    fn row(id: &str) -> Vec<String> {
+        let mut row = vec!["~".to_string(); 13];
+        row[0] = "NAME Betelgeuse".to_string();
+        row[1] = id.to_string();
+        row[2] = "05 55 10.305".to_string();
+        row[3] = "+07 24 25.43".to_string();
+        row
+    }
+
+    #[test]
+    fn catalog_ids() {
+        let columns = Columns::new(&VoTable::default()).unwrap();
+
+        let (entry, missing) = columns.entry(&row("Gaia DR3 3340206066228232576")).unwrap();
+        assert_eq!(entry.catalog(), "Gaia DR3");
+        assert_eq!(entry.number(), 3_340_206_066_228_232_576);
+        assert!(!missing.catalog_number);
+
+        let (entry, missing) = columns.entry(&row("2MASS J05551028+0724255")).unwrap();
+        assert_eq!(entry.name(), "Betelgeuse");
+        assert_eq!(entry.catalog(), "2MASS");
+        assert_eq!(entry.number(), 0);
+        assert!(missing.catalog_number);
+        assert!(missing.proper_motion);
     }
 }
@@ -0,0 +1,86 @@
+//! A small, embedded database of observatory sites
+//!
+//! Coordinates are approximate (to ~10 m) geodetic positions of each site's main instrument. They are intended for
+//! examples and quick calculations, not for precise pointing, which needs the surveyed station coordinates.
+
+use crate::{
+    error::Error,
+    positions::{Observer, Weather},
+};
+use std::sync::LazyLock;
+
+/// The raw site list, one site per line
+const SITES: &str = include_str!("../data/sites.csv");
+
+/// An observatory site
+#[derive(Debug, Clone, PartialEq)]
+pub struct Site {
+    key: &'static str,
+    name: &'static str,
+    lat: f64,
+    lon: f64,
+    height: f64,
+}
+
+impl Site {
+    /// The short lookup key (e.g. "OVRO")
+    pub fn key(&self) -> &str {
+        self.key
+    }
+
+    /// The full name of the site
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    /// Geodetic latitude in degrees; north positive
+    pub fn lat(&self) -> f64 {
+        self.lat
+    }
+
+    /// Geodetic longitude in degrees; east positive
+    pub fn lon(&self) -> f64 {
+        self.lon
+    }
+
+    /// Altitude above sea level in meters
+    pub fn height(&self) -> f64 {
+        self.height
+    }
+
+    /// An observer at the site with the given weather
+    pub fn observer(&self, weather: Weather) -> Observer {
+        Observer::new_on_surface(self.lat, self.lon, self.height, weather)
+    }
+}
+
+static SITE_LIST: LazyLock<Vec<Site>> = LazyLock::new(|| {
+    SITES
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.is_empty())
+        .map(|line| {
+            let cols = line.split(',').collect::<Vec<_>>();
+            Site {
+                key: cols[0],
+                name: cols[1],
+                lat: cols[2].parse().expect("invalid embedded latitude"),
+                lon: cols[3].parse().expect("invalid embedded longitude"),
+                height: cols[4].parse().expect("invalid embedded height"),
+            }
+        })
+        .collect()
+});
+
+/// All of the sites in the database
+pub fn all() -> &'static [Site] {
+    &SITE_LIST
+}
+
+/// Look up a site by its key or full name (case-insensitively)
+pub fn lookup(name: &str) -> super::Result<&'static Site> {
+    let name = name.trim();
+    SITE_LIST
+        .iter()
+        .find(|site| site.key.eq_ignore_ascii_case(name) || site.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| Error::NotFound(name.to_string()))
+}
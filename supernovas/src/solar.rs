@@ -0,0 +1,38 @@
+//! Conveniences for the position of the Sun
+
+use crate::{
+    planets::Planet,
+    positions::{Frame, Observer, ReferenceSystem, SkyPosition},
+    refraction::RefractionModel,
+    time::{Timescale, Timespec},
+    Accuracy,
+};
+
+/// The equation of time (apparent minus mean solar time) in minutes at the given instant
+///
+/// This is derived from the apparent (true-of-date) right ascension of the Sun from the solar ephemeris and the
+/// Greenwich apparent sidereal time.
+pub fn equation_of_time(time: &Timespec) -> super::Result<f64> {
+    let observer = Observer::new_at_geocenter();
+    let frame = Frame::new(Accuracy::Reduced, &observer, time, 0.0, 0.0)?;
+    let sun = SkyPosition::try_from_frame(&Planet::Sun, &frame, ReferenceSystem::TOD)?;
+    // Greenwich mean solar time is UT1 (from midnight), apparent solar time is the Sun's hour angle + 12h
+    let ut1 = (time.jd(Timescale::UT1) - 0.5).rem_euclid(1.0) * 24.0;
+    let hour_angle = frame.gst() - sun.ra();
+    // Wrap (hour_angle + 12h - ut1) into [-12h, 12h)
+    let eot = (hour_angle - ut1).rem_euclid(24.0) - 12.0;
+    Ok(eot * 60.0)
+}
+
+/// The topocentric local coordinates (az,el in degrees) of the Sun for an observer at the given instant
+///
+/// If a refraction model is given (and the observer is on the surface), the elevation is refracted. This uses a
+/// reduced-accuracy frame, which is far better than the Sun's apparent radius.
+pub fn sun_azel(
+    observer: &Observer,
+    time: &Timespec,
+    refraction: Option<&dyn RefractionModel>,
+) -> super::Result<(f64, f64)> {
+    let frame = Frame::new(Accuracy::Reduced, observer, time, 0.0, 0.0)?;
+    frame.apparent_local_coordinates(ReferenceSystem::CIRS, &Planet::Sun, refraction)
+}
@@ -0,0 +1,343 @@
+//! Reading and writing catalog entries, pointing tracks, and visibility windows as CSV and ECSV tables
+//!
+//! Columns follow the astropy naming conventions (`ra` and `dec` in degrees, `pmra`/`pmdec` in mas/yr,
+//! `parallax` in mas, and `radial_velocity` in km/s), so tables can be exchanged directly with Python tooling
+//! and observatory source lists. Times are written as UTC Julian dates.
+
+use crate::{
+    error::Error, plan::Window, positions::CatalogEntry, time::Timescale, track::TrackPoint,
+};
+use std::io::{BufRead, Write};
+
+/// Column name, ECSV data type, and unit of a table column
+type Column = (&'static str, &'static str, Option<&'static str>);
+
+/// Columns of the catalog tables, in order
+const COLUMNS: [Column; 9] = [
+    ("name", "string", None),
+    ("catalog", "string", None),
+    ("number", "int64", None),
+    ("ra", "float64", Some("deg")),
+    ("dec", "float64", Some("deg")),
+    ("pmra", "float64", Some("mas / yr")),
+    ("pmdec", "float64", Some("mas / yr")),
+    ("parallax", "float64", Some("mas")),
+    ("radial_velocity", "float64", Some("km / s")),
+];
+
+/// Columns of the pointing track tables, in order
+const TRACK_COLUMNS: [Column; 5] = [
+    ("jd_utc", "float64", Some("d")),
+    ("az", "float64", Some("deg")),
+    ("el", "float64", Some("deg")),
+    ("az_rate", "float64", Some("deg / s")),
+    ("el_rate", "float64", Some("deg / s")),
+];
+
+/// Columns of the visibility tables, in order
+const WINDOW_COLUMNS: [Column; 3] = [
+    ("start_jd_utc", "float64", Some("d")),
+    ("stop_jd_utc", "float64", Some("d")),
+    ("duration", "float64", Some("s")),
+];
+
+/// Write catalog entries as a CSV table with a header row
+pub fn write_csv<W: Write>(mut writer: W, entries: &[CatalogEntry]) -> super::Result<()> {
+    write_csv_header(&mut writer, &COLUMNS)?;
+    write_rows(&mut writer, entries)
+}
+
+/// Write catalog entries as an (astropy compatible) ECSV table
+pub fn write_ecsv<W: Write>(mut writer: W, entries: &[CatalogEntry]) -> super::Result<()> {
+    write_ecsv_header(&mut writer, &COLUMNS)?;
+    write_csv(writer, entries)
+}
+
+/// Write a pointing track as a CSV table with a header row
+pub fn write_track_csv<W: Write>(mut writer: W, points: &[TrackPoint]) -> super::Result<()> {
+    write_csv_header(&mut writer, &TRACK_COLUMNS)?;
+    for point in points {
+        writeln!(
+            writer,
+            "{},{},{},{},{}",
+            point.time.jd(Timescale::UTC),
+            point.az,
+            point.el,
+            point.az_rate,
+            point.el_rate
+        )?;
+    }
+    Ok(())
+}
+
+/// Write a pointing track as an (astropy compatible) ECSV table
+pub fn write_track_ecsv<W: Write>(mut writer: W, points: &[TrackPoint]) -> super::Result<()> {
+    write_ecsv_header(&mut writer, &TRACK_COLUMNS)?;
+    write_track_csv(writer, points)
+}
+
+/// Write visibility windows (e.g. from [`visibility`](crate::plan::visibility)) as a CSV table with a header row
+pub fn write_visibility_csv<W: Write>(mut writer: W, windows: &[Window]) -> super::Result<()> {
+    write_csv_header(&mut writer, &WINDOW_COLUMNS)?;
+    for window in windows {
+        writeln!(
+            writer,
+            "{},{},{}",
+            window.start.jd(Timescale::UTC),
+            window.stop.jd(Timescale::UTC),
+            window.duration()
+        )?;
+    }
+    Ok(())
+}
+
+/// Write visibility windows as an (astropy compatible) ECSV table
+pub fn write_visibility_ecsv<W: Write>(mut writer: W, windows: &[Window]) -> super::Result<()> {
+    write_ecsv_header(&mut writer, &WINDOW_COLUMNS)?;
+    write_visibility_csv(writer, windows)
+}
+
+fn write_csv_header<W: Write>(writer: &mut W, columns: &[Column]) -> super::Result<()> {
+    let header = columns
+        .iter()
+        .map(|(name, _, _)| *name)
+        .collect::<Vec<_>>()
+        .join(",");
+    writeln!(writer, "{header}")?;
+    Ok(())
+}
+
+/// Write the ECSV metadata, which precedes the CSV header row
+fn write_ecsv_header<W: Write>(writer: &mut W, columns: &[Column]) -> super::Result<()> {
+    writeln!(writer, "# %ECSV 1.0")?;
+    writeln!(writer, "# ---")?;
+    writeln!(writer, "# delimiter: ','")?;
+    writeln!(writer, "# datatype:")?;
+    for (name, datatype, unit) in columns {
+        match unit {
+            Some(unit) => writeln!(
+                writer,
+                "# - {{name: {name}, unit: {unit}, datatype: {datatype}}}"
+            )?,
+            None => writeln!(writer, "# - {{name: {name}, datatype: {datatype}}}")?,
+        }
+    }
+    writeln!(writer, "# schema: astropy-2.0")?;
+    Ok(())
+}
+
+/// Read catalog entries from a CSV table with a header row
+///
+/// Columns are matched by name (a few common aliases like `RA`, `pm_ra`, or `plx` are accepted),
+/// so their order doesn't matter. `name`, `ra`, and `dec` are required; missing astrometry defaults to zero.
+pub fn read_csv<R: BufRead>(reader: R) -> super::Result<Vec<CatalogEntry>> {
+    read_delimited(reader, ',')
+}
+
+/// Read catalog entries from an ECSV table
+///
+/// Only the header metadata needed for parsing (the delimiter) is interpreted; the column
+/// names must follow the same conventions as [`read_csv`].
+pub fn read_ecsv<R: BufRead>(reader: R) -> super::Result<Vec<CatalogEntry>> {
+    let mut lines = Vec::new();
+    let mut delimiter = ' ';
+    for line in reader.lines() {
+        let line = line?;
+        match line.strip_prefix('#') {
+            Some(meta) => {
+                if let Some(delim) = meta.trim().strip_prefix("delimiter:") {
+                    delimiter = delim
+                        .trim()
+                        .trim_matches('\'')
+                        .chars()
+                        .next()
+                        .unwrap_or(' ');
+                }
+            }
+            None => lines.push(line),
+        }
+    }
+    read_delimited(lines.join("\n").as_bytes(), delimiter)
+}
+
+fn write_rows<W: Write>(writer: &mut W, entries: &[CatalogEntry]) -> super::Result<()> {
+    for entry in entries {
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{},{},{}",
+            quote(entry.name()),
+            quote(entry.catalog()),
+            entry.number(),
+            entry.ra() * 15.0,
+            entry.dec(),
+            entry.pm_ra(),
+            entry.pm_dec(),
+            entry.parallax(),
+            entry.rad_vel()
+        )?;
+    }
+    Ok(())
+}
+
+fn read_delimited<R: BufRead>(reader: R, delimiter: char) -> super::Result<Vec<CatalogEntry>> {
+    let mut lines = reader.lines();
+    let header = match lines.next() {
+        Some(line) => split(&line?, delimiter),
+        None => return Ok(vec![]),
+    };
+    let find = |aliases: &[&str]| {
+        header
+            .iter()
+            .position(|col| aliases.iter().any(|a| col.eq_ignore_ascii_case(a)))
+    };
+    let required = |aliases: &[&str]| {
+        find(aliases).ok_or_else(|| Error::Table(format!("Missing column {}", aliases[0])))
+    };
+    let name_col = required(&["name", "main_id"])?;
+    let ra_col = required(&["ra", "ra_deg", "raj2000"])?;
+    let dec_col = required(&["dec", "dec_deg", "dej2000"])?;
+    let catalog_col = find(&["catalog"]);
+    let number_col = find(&["number", "id"]);
+    let pm_ra_col = find(&["pmra", "pm_ra"]);
+    let pm_dec_col = find(&["pmdec", "pm_dec"]);
+    let plx_col = find(&["parallax", "plx"]);
+    let rv_col = find(&["radial_velocity", "rad_vel", "rv"]);
+
+    let mut entries = Vec::new();
+    for line in lines {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let row = split(&line, delimiter);
+        let text = |col: Option<usize>| col.and_then(|c| row.get(c)).map(String::as_str);
+        let number = |col: Option<usize>| -> super::Result<f64> {
+            match text(col) {
+                None | Some("") => Ok(0.0),
+                Some(s) => s
+                    .parse()
+                    .map_err(|_| Error::Table(format!("Invalid number {s}"))),
+            }
+        };
+        // Catalog numbers can exceed the integers an f64 holds exactly, so they are parsed directly
+        let integer = |col: Option<usize>| -> super::Result<i64> {
+            match text(col) {
+                None | Some("") => Ok(0),
+                Some(s) => s
+                    .parse()
+                    .map_err(|_| Error::Table(format!("Invalid integer {s}"))),
+            }
+        };
+        entries.push(CatalogEntry::new(
+            text(Some(name_col)).unwrap_or_default(),
+            text(catalog_col).unwrap_or_default(),
+            integer(number_col)?,
+            number(Some(ra_col))? / 15.0,
+            number(Some(dec_col))?,
+            number(pm_ra_col)?,
+            number(pm_dec_col)?,
+            number(plx_col)?,
+            number(rv_col)?,
+        )?);
+    }
+    Ok(entries)
+}
+
+/// Quote a string field if it contains characters that would break the row
+fn quote(s: &str) -> String {
+    if s.contains([',', '"', ' ']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Split a delimited line into fields, respecting double-quoted fields
+fn split(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    // Whether the current field was quoted, so an empty quoted field is kept
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => {
+                in_quotes = !in_quotes;
+                quoted = true;
+            }
+            c if c == delimiter && !in_quotes => {
+                push_field(&mut fields, std::mem::take(&mut field), quoted, delimiter);
+                quoted = false;
+            }
+            c => field.push(c),
+        }
+    }
+    push_field(&mut fields, field, quoted, delimiter);
+    fields
+}
+
+/// Add a field to a split row
+///
+/// Whitespace-delimited tables may have runs of spaces between fields, so empty unquoted fields are dropped from
+/// those.
+fn push_field(fields: &mut Vec<String>, field: String, quoted: bool, delimiter: char) {
+    let field = field.trim();
+    if delimiter != ' ' || quoted || !field.is_empty() {
+        fields.push(field.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_round_trip() {
+        let entries = vec![
+            CatalogEntry::new(
+                "Vega", "HIP", 91262, 18.6156, 38.7837, 200.94, 286.23, 130.23, -13.5,
+            )
+            .unwrap(),
+            CatalogEntry::new("a \"quoted\", name", "", 0, 1.0, -2.0, 0.0, 0.0, 0.0, 0.0).unwrap(),
+        ];
+        let mut csv = vec![];
+        write_csv(&mut csv, &entries).unwrap();
+        let read = read_csv(csv.as_slice()).unwrap();
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[0].name(), "Vega");
+        assert_eq!(read[0].number(), 91262);
+        assert!((read[0].ra() - 18.6156).abs() < 1e-12);
+        assert_eq!(read[1].name(), "a \"quoted\", name");
+    }
+
+    #[test]
+    fn large_catalog_numbers() {
+        // Gaia DR3 source IDs exceed 2^53
+        let csv = "name,number,ra,dec\nstar,4295806720123456789,10,20\n";
+        let read = read_csv(csv.as_bytes()).unwrap();
+        assert_eq!(read[0].number(), 4_295_806_720_123_456_789);
+        assert!(read_csv("name,number,ra,dec\nstar,1.5,10,20\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn whitespace_delimited_fields() {
+        assert_eq!(split("a   b  c", ' '), ["a", "b", "c"]);
+        assert_eq!(split(r#"a "" c"#, ' '), ["a", "", "c"]);
+        assert_eq!(split(r#""two words"  x"#, ' '), ["two words", "x"]);
+        assert_eq!(split("a,,c", ','), ["a", "", "c"]);
+    }
+
+    #[test]
+    fn ecsv_with_space_delimiter() {
+        let ecsv = "# %ECSV 1.0\n# ---\n# delimiter: ' '\nname catalog ra dec\n\"M 31\" \"\" 10.68 41.27\n";
+        let read = read_ecsv(ecsv.as_bytes()).unwrap();
+        assert_eq!(read[0].name(), "M 31");
+        assert_eq!(read[0].catalog(), "");
+        assert!((read[0].dec() - 41.27).abs() < 1e-12);
+    }
+}
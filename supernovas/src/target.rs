@@ -0,0 +1,98 @@
+//! A single type for every kind of observable target
+
+#[cfg(feature = "calceph")]
+use crate::ephem::EphemObject;
+use crate::{
+    error::Error,
+    orbital::OrbitalObject,
+    planets::Planet,
+    positions::{object_name, CatalogEntry, Frame, Placeable},
+    uncertainty::ErrorEllipse,
+};
+use std::{borrow::Cow, mem::MaybeUninit};
+use supernovas_sys::{make_redshifted_object, object};
+
+/// Any kind of target, so heterogeneous target lists can be held behind one type
+///
+/// This is [`Placeable`], so it can be used everywhere a [`CatalogEntry`] can.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Target {
+    /// A sidereal source from a catalog
+    Catalog(CatalogEntry),
+    /// A major planet, the Sun, or the Moon
+    Planet(Planet),
+    /// A body looked up by NAIF ID in the loaded ephemeris
+    #[cfg(feature = "calceph")]
+    Ephem(EphemObject),
+    /// A body on a Keplerian orbit
+    Orbital(OrbitalObject),
+    /// A distant (extragalactic) source at an ICRS position with a redshift
+    Redshifted {
+        /// The source name
+        name: String,
+        /// ICRS right ascension in hours
+        ra: f64,
+        /// ICRS declination in degrees
+        dec: f64,
+        /// Redshift
+        z: f64,
+    },
+}
+
+impl Placeable for Target {
+    fn object(&self) -> super::Result<Cow<'_, object>> {
+        match self {
+            Target::Catalog(entry) => entry.object(),
+            Target::Planet(planet) => planet.object(),
+            #[cfg(feature = "calceph")]
+            Target::Ephem(ephem) => ephem.object(),
+            Target::Orbital(orbital) => orbital.object(),
+            Target::Redshifted { name, ra, dec, z } => {
+                let name = object_name(name)?;
+                let mut obj = MaybeUninit::uninit();
+                // Safety: The name is null-terminated within the NOVAS name size and the object will not be null
+                let obj = unsafe {
+                    let ret =
+                        make_redshifted_object(name.as_ptr(), *ra, *dec, *z, obj.as_mut_ptr());
+                    if ret != 0 {
+                        return Err(Error::LowerLevel(ret));
+                    }
+                    obj.assume_init()
+                };
+                Ok(Cow::Owned(obj))
+            }
+        }
+    }
+
+    fn error_ellipse(&self, frame: &Frame) -> Option<ErrorEllipse> {
+        match self {
+            Target::Catalog(entry) => entry.error_ellipse(frame),
+            _ => None,
+        }
+    }
+}
+
+impl From<CatalogEntry> for Target {
+    fn from(value: CatalogEntry) -> Self {
+        Target::Catalog(value)
+    }
+}
+
+impl From<Planet> for Target {
+    fn from(value: Planet) -> Self {
+        Target::Planet(value)
+    }
+}
+
+#[cfg(feature = "calceph")]
+impl From<EphemObject> for Target {
+    fn from(value: EphemObject) -> Self {
+        Target::Ephem(value)
+    }
+}
+
+impl From<OrbitalObject> for Target {
+    fn from(value: OrbitalObject) -> Self {
+        Target::Orbital(value)
+    }
+}
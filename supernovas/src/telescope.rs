@@ -0,0 +1,353 @@
+//! A single entry point for telescope control software
+
+use crate::{
+    error::Error,
+    pointing::PointingModel,
+    positions::{Frame, Observer, Placeable, ReferenceSystem},
+    refraction::RefractionModel,
+    time::Timespec,
+    Accuracy,
+};
+
+/// A region of the sky the telescope must not point into (e.g. a building, or the Sun's neighborhood)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct KeepOutZone {
+    /// Azimuth range (from, to) in degrees, going east from `from`, so it may wrap through north
+    pub az: (f64, f64),
+    /// Elevation range (min, max) in degrees
+    pub el: (f64, f64),
+}
+
+impl KeepOutZone {
+    fn contains(&self, az: f64, el: f64) -> bool {
+        az_in_range(az, self.az) && el >= self.el.0 && el <= self.el.1
+    }
+}
+
+/// The region of the sky the mount can reach
+#[derive(Debug, Clone, PartialEq)]
+pub struct MountLimits {
+    /// Lowest reachable elevation in degrees
+    pub min_el: f64,
+    /// Highest reachable elevation in degrees
+    pub max_el: f64,
+    /// Reachable azimuth range (from, to) in degrees, going east from `from`, or `None` for all azimuths
+    pub az: Option<(f64, f64)>,
+    /// Regions that must not be pointed into
+    pub keep_out: Vec<KeepOutZone>,
+}
+
+impl Default for MountLimits {
+    fn default() -> Self {
+        Self {
+            min_el: 0.0,
+            max_el: 90.0,
+            az: None,
+            keep_out: vec![],
+        }
+    }
+}
+
+/// The reason a position cannot be pointed to
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LimitViolation {
+    /// The elevation is outside of the mount's elevation range
+    Elevation { el: f64, min: f64, max: f64 },
+    /// The azimuth is outside of the mount's azimuth range
+    Azimuth { az: f64 },
+    /// The position is below the observer's horizon profile
+    Horizon { az: f64, el: f64, min_el: f64 },
+    /// The position is inside a keep-out zone
+    KeepOut { az: f64, el: f64, zone: KeepOutZone },
+}
+
+impl std::fmt::Display for LimitViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LimitViolation::Elevation { el, min, max } => {
+                write!(f, "elevation {el:.3}° outside of [{min}°, {max}°]")
+            }
+            LimitViolation::Azimuth { az } => {
+                write!(f, "azimuth {az:.3}° outside of the mount range")
+            }
+            LimitViolation::Horizon { az, el, min_el } => {
+                write!(
+                    f,
+                    "elevation {el:.3}° below the horizon ({min_el:.3}°) at azimuth {az:.3}°"
+                )
+            }
+            LimitViolation::KeepOut { az, el, .. } => {
+                write!(f, "position ({az:.3}°, {el:.3}°) inside a keep-out zone")
+            }
+        }
+    }
+}
+
+impl MountLimits {
+    /// Check whether the position (az,el in degrees) can be pointed to by the mount of the given observer
+    pub fn check(&self, az: f64, el: f64, observer: &Observer) -> Result<(), LimitViolation> {
+        if el < self.min_el || el > self.max_el {
+            return Err(LimitViolation::Elevation {
+                el,
+                min: self.min_el,
+                max: self.max_el,
+            });
+        }
+        if let Some(range) = self.az {
+            if !az_in_range(az, range) {
+                return Err(LimitViolation::Azimuth { az });
+            }
+        }
+        let min_el = observer.min_elevation(az);
+        if observer.horizon().is_some() && el < min_el {
+            return Err(LimitViolation::Horizon { az, el, min_el });
+        }
+        if let Some(zone) = self.keep_out.iter().find(|zone| zone.contains(az, el)) {
+            return Err(LimitViolation::KeepOut {
+                az,
+                el,
+                zone: *zone,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Whether an azimuth lies in the range going east from `range.0` to `range.1` (all in degrees)
+///
+/// Ranges spanning 360° or more, like (0, 360) or (-180, 180), are the full circle.
+fn az_in_range(az: f64, range: (f64, f64)) -> bool {
+    if range.1 - range.0 >= 360.0 {
+        return true;
+    }
+    let span = (range.1 - range.0).rem_euclid(360.0);
+    (az - range.0).rem_euclid(360.0) <= span
+}
+
+/// The fully corrected pointing to a source
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Pointing {
+    /// Observed (refracted) azimuth of the source in degrees
+    pub az: f64,
+    /// Observed (refracted) elevation of the source in degrees
+    pub el: f64,
+    /// Mount azimuth to command in degrees, including the pointing model
+    pub mount_az: f64,
+    /// Mount elevation to command in degrees, including the pointing model
+    pub mount_el: f64,
+}
+
+/// A telescope: an observer together with its refraction model, pointing model, and mount limits
+pub struct Telescope {
+    observer: Observer,
+    refraction: Option<Box<dyn RefractionModel>>,
+    pointing_model: PointingModel,
+    limits: MountLimits,
+    accuracy: Accuracy,
+    ref_sys: ReferenceSystem,
+    dx: f64,
+    dy: f64,
+}
+
+impl Telescope {
+    /// Construct a new telescope at the given observer
+    ///
+    /// By default, this has no refraction, an empty pointing model, and a 0-90° elevation range, and computes
+    /// reduced-accuracy places in CIRS with no pole offsets.
+    pub fn new(observer: Observer) -> Self {
+        Self {
+            observer,
+            refraction: None,
+            pointing_model: PointingModel::default(),
+            limits: MountLimits::default(),
+            accuracy: Accuracy::Reduced,
+            ref_sys: ReferenceSystem::CIRS,
+            dx: 0.0,
+            dy: 0.0,
+        }
+    }
+
+    /// Refract the elevations with the given model
+    pub fn with_refraction(mut self, model: impl RefractionModel + 'static) -> Self {
+        self.refraction = Some(Box::new(model));
+        self
+    }
+
+    /// Apply the given pointing model to the drive coordinates
+    pub fn with_pointing_model(mut self, model: PointingModel) -> Self {
+        self.pointing_model = model;
+        self
+    }
+
+    /// Set the limits of the mount
+    pub fn with_limits(mut self, limits: MountLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Set the accuracy of the frame computations
+    pub fn with_accuracy(mut self, accuracy: Accuracy) -> Self {
+        self.accuracy = accuracy;
+        self
+    }
+
+    /// Set the reference system of the apparent places
+    pub fn with_reference_system(mut self, ref_sys: ReferenceSystem) -> Self {
+        self.ref_sys = ref_sys;
+        self
+    }
+
+    /// Set the polar offsets (dx, dy in mas) used for each frame
+    pub fn with_pole_offsets(mut self, dx: f64, dy: f64) -> Self {
+        self.dx = dx;
+        self.dy = dy;
+        self
+    }
+
+    /// The observer of the telescope
+    pub fn observer(&self) -> &Observer {
+        &self.observer
+    }
+
+    /// The pointing model of the telescope
+    pub fn pointing_model(&self) -> &PointingModel {
+        &self.pointing_model
+    }
+
+    /// The limits of the mount
+    pub fn limits(&self) -> &MountLimits {
+        &self.limits
+    }
+
+    /// Computes the fully corrected drive coordinates to a target at the given time
+    ///
+    /// This returns [`Error::Unreachable`] if the target is outside of the mount limits, below the observer's
+    /// horizon profile, or in a keep-out zone.
+    pub fn point_at(&self, target: &impl Placeable, time: &Timespec) -> super::Result<Pointing> {
+        let frame = Frame::new(self.accuracy, &self.observer, time, self.dx, self.dy)?;
+        let (az, el) =
+            frame.apparent_local_coordinates(self.ref_sys, target, self.refraction.as_deref())?;
+        let (mount_az, mount_el) = self.pointing_model.apply(az, el);
+        // Check where the source is on the sky, as the pointing corrections are small compared to any limit
+        self.limits
+            .check(az, el, &self.observer)
+            .map_err(Error::Unreachable)?;
+        Ok(Pointing {
+            az,
+            el,
+            mount_az,
+            mount_el,
+        })
+    }
+}
+
+/// The azimuth travel range of a mount whose cable wrap allows more than one turn (e.g. -270° to +270°)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CableWrap {
+    /// Lowest mount azimuth in degrees
+    pub min_az: f64,
+    /// Highest mount azimuth in degrees
+    pub max_az: f64,
+}
+
+/// A planned slew between two mount positions
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Slew {
+    /// The mount azimuth to command in degrees, on the chosen wrap branch
+    pub az: f64,
+    /// The mount elevation to command in degrees
+    pub el: f64,
+    /// Azimuth travel in degrees (signed, positive eastward)
+    pub az_travel: f64,
+    /// Elevation travel in degrees (signed, positive upward)
+    pub el_travel: f64,
+}
+
+impl Slew {
+    /// The slew distance in degrees along the slower axis, assuming both axes move at the same rate at once
+    pub fn distance(&self) -> f64 {
+        self.az_travel.abs().max(self.el_travel.abs())
+    }
+}
+
+impl CableWrap {
+    /// Choose the wrap branch (az ± 360°) for a target azimuth that needs the least travel from the current mount
+    /// azimuth, and plan the slew
+    ///
+    /// - current_az, current_el: The current mount position in degrees (on its wrap branch)
+    /// - az, el: The target mount position in degrees (in any branch, e.g. 0-360)
+    pub fn slew(
+        &self,
+        current_az: f64,
+        current_el: f64,
+        az: f64,
+        el: f64,
+    ) -> Result<Slew, LimitViolation> {
+        // Every branch of the target azimuth within the wrap range
+        let first = az - ((az - self.min_az) / 360.0).floor() * 360.0;
+        let branch = std::iter::successors(Some(first), |a| Some(a + 360.0))
+            .take_while(|a| *a <= self.max_az)
+            .min_by(|a, b| (a - current_az).abs().total_cmp(&(b - current_az).abs()))
+            .ok_or(LimitViolation::Azimuth { az })?;
+        Ok(Slew {
+            az: branch,
+            el,
+            az_travel: branch - current_az,
+            el_travel: el - current_el,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::positions::Weather;
+
+    #[test]
+    fn full_circle_azimuth_ranges() {
+        for range in [(0.0, 360.0), (-180.0, 180.0), (-270.0, 270.0)] {
+            for az in [0.0, 90.0, 180.0, 270.0, 359.9] {
+                assert!(az_in_range(az, range), "{az} in {range:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn wrapped_azimuth_ranges() {
+        // Through north
+        assert!(az_in_range(350.0, (300.0, 30.0)));
+        assert!(az_in_range(10.0, (300.0, 30.0)));
+        assert!(!az_in_range(180.0, (300.0, 30.0)));
+        // Given with a negative start
+        assert!(az_in_range(350.0, (-60.0, 30.0)));
+        assert!(!az_in_range(90.0, (-60.0, 30.0)));
+        // Not wrapping
+        assert!(az_in_range(120.0, (90.0, 180.0)));
+        assert!(!az_in_range(200.0, (90.0, 180.0)));
+    }
+
+    #[test]
+    fn mount_limits_and_keep_out_zones() {
+        let observer = Observer::new_on_surface(37.23, -118.28, 1222.0, Weather::default());
+        let limits = MountLimits {
+            az: Some((-180.0, 180.0)),
+            keep_out: vec![KeepOutZone {
+                az: (350.0, 10.0),
+                el: (0.0, 20.0),
+            }],
+            ..Default::default()
+        };
+        assert_eq!(limits.check(90.0, 45.0, &observer), Ok(()));
+        assert_eq!(limits.check(355.0, 45.0, &observer), Ok(()));
+        assert!(matches!(
+            limits.check(5.0, 10.0, &observer),
+            Err(LimitViolation::KeepOut { .. })
+        ));
+        let everywhere = KeepOutZone {
+            az: (0.0, 360.0),
+            el: (0.0, 5.0),
+        };
+        assert!(everywhere.contains(123.0, 2.0));
+        assert!(!everywhere.contains(123.0, 6.0));
+    }
+}
@@ -1,7 +1,21 @@
 //! Routines involving construction and conversion between instances in time in different time scales
 
-use std::{fmt::Debug, mem::MaybeUninit};
-use supernovas_sys::{novas_set_split_time, novas_timescale, novas_timespec};
+use crate::{
+    eop::{eop_table, EopTable},
+    error::Error,
+};
+use std::{
+    cmp::Ordering,
+    fmt::{Debug, Display},
+    hash::{Hash, Hasher},
+    mem::MaybeUninit,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use supernovas_sys::{
+    novas_diff_time, novas_get_split_time, novas_get_time, novas_offset_time, novas_set_split_time,
+    novas_timescale, novas_timespec,
+};
 
 #[cfg(feature = "hifitime")]
 use hifitime::{ut1::Ut1Provider, Duration, Epoch};
@@ -28,11 +42,88 @@ pub enum Timescale {
     UT1 = 7,
 }
 
+/// The Modified Julian Date (UTC) from which each value of TAI-UTC applies, from IERS Bulletin C
+const LEAP_SECONDS: [(i64, i32); 28] = [
+    (41317, 10),
+    (41499, 11),
+    (41683, 12),
+    (42048, 13),
+    (42413, 14),
+    (42778, 15),
+    (43144, 16),
+    (43509, 17),
+    (43874, 18),
+    (44239, 19),
+    (44786, 20),
+    (45151, 21),
+    (45516, 22),
+    (46247, 23),
+    (47161, 24),
+    (47892, 25),
+    (48257, 26),
+    (48804, 27),
+    (49169, 28),
+    (49534, 29),
+    (50083, 30),
+    (50630, 31),
+    (51179, 32),
+    (53736, 33),
+    (54832, 34),
+    (56109, 35),
+    (57204, 36),
+    (57754, 37),
+];
+
+/// Julian date of the Modified Julian Date epoch
+pub(crate) const MJD0: f64 = 2_400_000.5;
+
+/// TT - TAI in seconds
+const TT_TAI: f64 = 32.184;
+
+/// Julian date of the Unix epoch
+const JD_UNIX: f64 = 2_440_587.5;
+
+/// The leap seconds (TAI-UTC) in effect at a UTC Modified Julian Date
+///
+/// Dates before 1972, when UTC was not yet defined with whole leap seconds, return 0.
+pub fn leap_seconds(mjd_utc: f64) -> i32 {
+    LEAP_SECONDS
+        .iter()
+        .rev()
+        .find(|(start, _)| mjd_utc >= *start as f64)
+        .map_or(0, |(_, leap)| *leap)
+}
+
 /// The instant object needed for time calculations
+///
+/// Instants compare (and order) by their TT Julian date alone, regardless of their UT1-UTC or leap seconds.
+#[derive(Copy, Clone)]
 #[repr(transparent)]
 pub struct Timespec(pub(crate) novas_timespec);
 
 impl Timespec {
+    /// The J2000.0 epoch, 2000 January 1, 12h TT (JD 2451545.0 TT)
+    ///
+    /// UT1 is set from the leap seconds (32) and UT1-UTC (0.3552 s) of that date.
+    pub const J2000: Self = Self(novas_timespec {
+        ijd_tt: 2_451_545,
+        fjd_tt: 0.0,
+        tt2tdb: -9.575_743_486e-5,
+        ut1_to_tt: 32.0 + TT_TAI - 0.3552,
+        dut1: 0.3552,
+    });
+
+    /// The B1950.0 (Besselian) epoch, JD 2433282.42345905 TT
+    ///
+    /// This predates UTC, so UT1 is set from ΔT = TT - UT1 = 29.15 s, with no leap seconds.
+    pub const B1950: Self = Self(novas_timespec {
+        ijd_tt: 2_433_282,
+        fjd_tt: 0.423_459_05,
+        tt2tdb: -7.192_465_045e-5,
+        ut1_to_tt: 29.15,
+        dut1: TT_TAI - 29.15,
+    });
+
     /// Sets an astronomical time to the split Julian Date value, defined in the specified timescale.
     ///
     /// The split into the integer and fractional parts can be done in any convenient way.
@@ -53,6 +144,399 @@ impl Timespec {
         };
         Timespec(ts)
     }
+
+    /// Sets an astronomical time to a single Julian date in the specified timescale
+    ///
+    /// A single `f64` Julian date resolves about 40 μs; use [`Timespec::from_two_part_jd`] or
+    /// [`Timespec::from_split_time`] for better precision. The other arguments are as for
+    /// [`Timespec::from_split_time`].
+    pub fn from_jd(timescale: Timescale, jd: f64, leap: i32, dut1: f64) -> Self {
+        Self::from_two_part_jd(timescale, jd, 0.0, leap, dut1)
+    }
+
+    /// Sets an astronomical time to a two-part Julian date `jd1 + jd2` in the specified timescale, as used by SOFA,
+    /// ERFA, and many data files
+    ///
+    /// The parts may be split in any way (e.g. the date and the time of day, or J2000 and the days since), and are
+    /// recombined without losing precision. The other arguments are as for [`Timespec::from_split_time`].
+    pub fn from_two_part_jd(
+        timescale: Timescale,
+        jd1: f64,
+        jd2: f64,
+        leap: i32,
+        dut1: f64,
+    ) -> Self {
+        let (int1, int2) = (jd1.floor(), jd2.floor());
+        let fraction = (jd1 - int1) + (jd2 - int2);
+        let ijd = int1 + int2 + fraction.floor();
+        Self::from_split_time(
+            timescale,
+            ijd as i64,
+            fraction - fraction.floor(),
+            leap,
+            dut1,
+        )
+    }
+
+    /// Construct a UTC instant from a split Julian date, taking the leap seconds from the built-in table and UT1-UTC
+    /// from the EOP table
+    ///
+    /// This errors if the EOP table doesn't cover the date.
+    pub fn from_utc(ijd: i64, fjd: f64, eop: &EopTable) -> super::Result<Self> {
+        let mjd = (ijd as f64 - MJD0) + fjd;
+        let dut1 = eop.get(mjd)?.dut1;
+        Ok(Self::from_split_time(
+            Timescale::UTC,
+            ijd,
+            fjd,
+            leap_seconds(mjd),
+            dut1,
+        ))
+    }
+
+    /// Construct an instant from the system clock (e.g. `SystemTime::now()`, or a converted `chrono` time), taking
+    /// UT1-UTC from the EOP table
+    pub fn from_system_time(time: SystemTime, eop: &EopTable) -> super::Result<Self> {
+        let secs = match time.duration_since(UNIX_EPOCH) {
+            Ok(since) => since.as_secs_f64(),
+            Err(e) => -e.duration().as_secs_f64(),
+        };
+        // Unix time has exactly 86400 seconds a day, so the day and fraction map directly to the UTC date
+        let days = secs.div_euclid(86_400.0);
+        let fjd = secs.rem_euclid(86_400.0) / 86_400.0 + JD_UNIX.fract();
+        Self::from_utc(JD_UNIX.trunc() as i64 + days as i64, fjd, eop)
+    }
+
+    /// The current instant from the system clock, taking UT1-UTC from the global EOP table (see
+    /// [`set_eop_table`](crate::eop::set_eop_table))
+    pub fn now() -> super::Result<Self> {
+        Self::from_system_time(SystemTime::now(), &eop_table()?)
+    }
+
+    /// The (single precision) Julian date of this instant in the given timescale
+    pub fn jd(&self, timescale: Timescale) -> f64 {
+        // Safety: The timespec is always initialized
+        unsafe { novas_get_time(&self.0 as *const _, novas_timescale(timescale as u32)) }
+    }
+
+    /// The Julian date of this instant in the given timescale, split into its integer and fractional parts for
+    /// full precision
+    pub fn split_jd(&self, timescale: Timescale) -> (i64, f64) {
+        let mut ijd = 0;
+        // Safety: The timespec is always initialized
+        let fjd = unsafe {
+            novas_get_split_time(
+                &self.0 as *const _,
+                novas_timescale(timescale as u32),
+                &mut ijd as *mut _,
+            )
+        };
+        (ijd as i64, fjd)
+    }
+
+    /// The difference `self - other` in (SI) seconds
+    pub fn diff(&self, other: &Timespec) -> f64 {
+        // Safety: Both timespecs are always initialized
+        unsafe { novas_diff_time(&self.0 as *const _, &other.0 as *const _) }
+    }
+
+    /// A new instant offset from this one by the given number of (SI) seconds
+    pub fn offset(&self, seconds: f64) -> Self {
+        let mut ts = MaybeUninit::uninit();
+        // Safety: Neither pointer is null, which is the only failure mode
+        let ts = unsafe {
+            let _ = novas_offset_time(&self.0 as *const _, seconds, ts.as_mut_ptr());
+            ts.assume_init()
+        };
+        Timespec(ts)
+    }
+}
+
+/// The ways a [`Timespec`] can be formatted as text
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TimeFormat {
+    /// ISO 8601 calendar date and time in UTC, e.g. `2024-03-01T12:34:56.789Z`
+    Iso8601,
+    /// A Julian date in the given timescale, e.g. `JD 2460371.024363426 TT`
+    JulianDate(Timescale),
+}
+
+impl Timespec {
+    /// Format this instant as text
+    pub fn format(&self, format: TimeFormat) -> String {
+        match format {
+            TimeFormat::Iso8601 => self.to_string(),
+            TimeFormat::JulianDate(timescale) => {
+                format!("JD {:.9} {timescale:?}", self.jd(timescale))
+            }
+        }
+    }
+}
+
+/// Formats as an ISO 8601 UTC timestamp, with the precision (default 3, at most 6) setting the decimals of the
+/// seconds
+///
+/// Instants within a leap second are shown as the first second of the following day.
+impl Display for Timespec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let precision = f.precision().unwrap_or(3).min(6);
+        let units_per_day = 86_400 * 10i64.pow(precision as u32);
+        // Count from the midnight before JD 0, so the day number is the integer part. The fraction of the day is
+        // rounded on its own, since a single f64 Julian date only resolves about 40 µs
+        let (ijd, fjd) = self.split_jd(Timescale::UTC);
+        let units = ijd * units_per_day + ((fjd + 0.5) * units_per_day as f64).round() as i64;
+        let (day, units) = (
+            units.div_euclid(units_per_day),
+            units.rem_euclid(units_per_day),
+        );
+        let (year, month, day) = civil_date(day);
+        let scale = 10i64.pow(precision as u32);
+        let (seconds, fraction) = (units / scale, units % scale);
+        write!(
+            f,
+            "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )?;
+        if precision > 0 {
+            write!(f, ".{fraction:0precision$}")?;
+        }
+        write!(f, "Z")
+    }
+}
+
+/// The (proleptic) Gregorian calendar date (year, month, day) of a Julian day number (Richards 2013)
+fn civil_date(jdn: i64) -> (i64, i64, i64) {
+    let f = jdn + 1401 + (((4 * jdn + 274_277) / 146_097) * 3) / 4 - 38;
+    let e = 4 * f + 3;
+    let g = (e % 1461) / 4;
+    let h = 5 * g + 2;
+    let day = (h % 153) / 5 + 1;
+    let month = (h / 153 + 2) % 12 + 1;
+    let year = e / 1461 - 4716 + (14 - month) / 12;
+    (year, month, day)
+}
+
+/// The number of days in a month (1-12) of a (proleptic) Gregorian calendar year
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// The Julian day number of a (proleptic) Gregorian calendar date, the inverse of [`civil_date`]
+fn julian_day_number(year: i64, month: i64, day: i64) -> i64 {
+    let a = (14 - month) / 12;
+    let y = year + 4800 - a;
+    let m = month + 12 * a - 3;
+    day + (153 * m + 2) / 5 + 365 * y + y / 4 - y / 100 + y / 400 - 32_045
+}
+
+/// Parses an ISO 8601 UTC timestamp, taking UT1-UTC from the global EOP table (see
+/// [`set_eop_table`](crate::eop::set_eop_table))
+///
+/// Accepts `YYYY-MM-DD`, optionally followed by `T` (or a space) and `hh:mm`, `hh:mm:ss`, or `hh:mm:ss.sss`, and an
+/// optional `Z` suffix. Other UTC offsets are not supported.
+impl FromStr for Timespec {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (ijd, fjd) = parse_utc(s)?;
+        Self::from_utc(ijd, fjd, &eop_table()?)
+    }
+}
+
+/// The UTC Julian date of an ISO 8601 timestamp, as a day number and fraction
+fn parse_utc(s: &str) -> super::Result<(i64, f64)> {
+    let s = s.trim();
+    let s = s.strip_suffix(['Z', 'z']).unwrap_or(s);
+    let (date, time) = s.split_once(['T', ' ']).unwrap_or((s, "00:00"));
+    let number = |part: Option<&str>| -> super::Result<i64> {
+        part.filter(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|part| part.parse().ok())
+            .ok_or(Error::InvalidString)
+    };
+
+    let mut parts = date.split('-');
+    let (year, month, day) = (
+        number(parts.next())?,
+        number(parts.next())?,
+        number(parts.next())?,
+    );
+    if parts.next().is_some() {
+        return Err(Error::InvalidString);
+    }
+    let mut parts = time.split(':');
+    let (hour, minute) = (number(parts.next())?, number(parts.next())?);
+    let second = match parts.next() {
+        Some(second) => second.parse::<f64>().map_err(|_| Error::InvalidString)?,
+        None => 0.0,
+    };
+    if parts.next().is_some()
+        || !(1..=12).contains(&month)
+        || !(1..=days_in_month(year, month)).contains(&day)
+        || hour >= 24
+        || minute >= 60
+        // Allow for leap seconds
+        || !(0.0..61.0).contains(&second)
+    {
+        return Err(Error::InvalidString);
+    }
+
+    // The Julian day number is the date at noon, so go back half a day for midnight
+    let fjd = ((hour * 60 + minute) as f64 * 60.0 + second) / 86_400.0 - 0.5;
+    Ok((julian_day_number(year, month, day), fjd))
+}
+
+/// A UTC instant with UT1-UTC from the global EOP table, or zero if the table is missing or doesn't cover it
+fn utc_or_approx(ijd: i64, fjd: f64) -> Timespec {
+    eop_table()
+        .and_then(|eop| Timespec::from_utc(ijd, fjd, &eop))
+        .unwrap_or_else(|_| {
+            let leap = leap_seconds((ijd as f64 - MJD0) + fjd);
+            Timespec::from_split_time(Timescale::UTC, ijd, fjd, leap, 0.0)
+        })
+}
+
+/// A Modified Julian Date in UTC
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct Mjd(pub f64);
+
+/// Conversion of the ways an instant may be given into a [`Timespec`]
+///
+/// Functions taking an `impl IntoTimespec` accept a [`Timespec`] (or a reference to one), an ISO 8601 UTC string,
+/// an [`Mjd`], or (with the `hifitime` feature) a hifitime epoch and UT1 provider. The string and MJD conversions
+/// take UT1-UTC from the global EOP table (see [`set_eop_table`](crate::eop::set_eop_table)), so they error if it
+/// doesn't cover the date.
+pub trait IntoTimespec: Sized {
+    /// Convert into a [`Timespec`]
+    fn into_timespec(self) -> super::Result<Timespec>;
+
+    /// Convert into a [`Timespec`], taking UT1-UTC as zero (with the built-in leap seconds) where the global EOP
+    /// table is missing or doesn't cover the date
+    ///
+    /// UT1-UTC is kept under 0.9 s, so this costs up to about 14" of error in the Earth's rotation angle, which
+    /// is fine for pointing but not for precise astrometry.
+    fn into_timespec_approx(self) -> super::Result<Timespec> {
+        self.into_timespec()
+    }
+}
+
+impl IntoTimespec for Timespec {
+    fn into_timespec(self) -> super::Result<Timespec> {
+        Ok(self)
+    }
+}
+
+impl IntoTimespec for &Timespec {
+    fn into_timespec(self) -> super::Result<Timespec> {
+        Ok(*self)
+    }
+}
+
+impl IntoTimespec for &str {
+    fn into_timespec(self) -> super::Result<Timespec> {
+        self.parse()
+    }
+
+    fn into_timespec_approx(self) -> super::Result<Timespec> {
+        let (ijd, fjd) = parse_utc(self)?;
+        Ok(utc_or_approx(ijd, fjd))
+    }
+}
+
+impl IntoTimespec for &String {
+    fn into_timespec(self) -> super::Result<Timespec> {
+        self.parse()
+    }
+
+    fn into_timespec_approx(self) -> super::Result<Timespec> {
+        let (ijd, fjd) = parse_utc(self)?;
+        Ok(utc_or_approx(ijd, fjd))
+    }
+}
+
+impl Mjd {
+    /// The UTC Julian date as a day number and fraction
+    fn split_jd(self) -> (i64, f64) {
+        // Split before adding the epoch, so the fraction keeps the full precision of the MJD
+        let day = self.0.floor();
+        (day as i64 + (MJD0 + 0.5) as i64, self.0 - day - 0.5)
+    }
+}
+
+impl IntoTimespec for Mjd {
+    fn into_timespec(self) -> super::Result<Timespec> {
+        let (ijd, fjd) = self.split_jd();
+        Timespec::from_utc(ijd, fjd, &eop_table()?)
+    }
+
+    fn into_timespec_approx(self) -> super::Result<Timespec> {
+        let (ijd, fjd) = self.split_jd();
+        Ok(utc_or_approx(ijd, fjd))
+    }
+}
+
+#[cfg(feature = "hifitime")]
+impl IntoTimespec for (Epoch, Ut1Provider) {
+    fn into_timespec(self) -> super::Result<Timespec> {
+        Ok(self.into())
+    }
+}
+
+#[cfg(feature = "hifitime")]
+impl IntoTimespec for (Epoch, &Ut1Provider) {
+    fn into_timespec(self) -> super::Result<Timespec> {
+        Ok(self.into())
+    }
+}
+
+impl Timespec {
+    /// The TT Julian date as an integer day and a fraction of a day in [0, 1), which is unique for each instant
+    fn normalized(&self) -> (i64, f64) {
+        let whole = self.0.fjd_tt.floor();
+        let (ijd, fjd) = (self.0.ijd_tt + whole as i64, self.0.fjd_tt - whole);
+        // The fraction rounds up to a whole day for tiny negative fractions
+        if fjd >= 1.0 {
+            (ijd + 1, 0.0)
+        } else {
+            (ijd, fjd)
+        }
+    }
+}
+
+/// Instants are compared by their TT Julian date alone, regardless of the UT1 and TDB offsets they carry
+impl PartialEq for Timespec {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Timespec {}
+
+impl PartialOrd for Timespec {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Timespec {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b) = (self.normalized(), other.normalized());
+        a.0.cmp(&b.0).then(a.1.total_cmp(&b.1))
+    }
+}
+
+impl Hash for Timespec {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let (ijd, fjd) = self.normalized();
+        ijd.hash(state);
+        fjd.to_bits().hash(state);
+    }
 }
 
 // Spoof the debug print for the inner struct
@@ -69,15 +553,16 @@ impl Debug for Timespec {
 }
 
 #[cfg(feature = "hifitime")]
-impl From<(Epoch, Ut1Provider)> for Timespec {
-    fn from(item: (Epoch, Ut1Provider)) -> Self {
+impl Timespec {
+    /// Convert a hifitime epoch, taking UT1-UTC from the provider
+    fn from_epoch(epoch: Epoch, provider: &Ut1Provider) -> Self {
         // hifitime is "TAI-native" while NOVAS likes to work in "TT"
         // This is a little weird because TT is derived from TAI, so it would be "more correct" to work in TT
         // Here, we use hifitime to perform the conversions between whatever timescale the Epoch is in to TT as we don't trust the implementation in the C
         // Case and point, there is a bug in SuperNOVAS where the conversion between TAI and TT is 3us off
 
         // Extract the TT time (TT days since the Julian epoch)
-        let tt = item.0.to_jde_tt_duration();
+        let tt = epoch.to_jde_tt_duration();
         let (_, d, h, m, s, ms, us, ns) = tt.decompose();
         let ijd_tt = d as i64;
         // Recompose the days remainder as a single float
@@ -85,13 +570,105 @@ impl From<(Epoch, Ut1Provider)> for Timespec {
         let fjd_tt = tt_remainder.to_seconds();
 
         // Get the total accumulated leap seconds
-        let leap = item.0.leap_seconds_iers();
+        let leap = epoch.leap_seconds_iers();
 
         // Compute UT1-UTC difference
-        let utc = item.0.to_utc_duration();
-        let ut1 = item.0.to_ut1_duration(item.1);
+        let utc = epoch.to_utc_duration();
+        let ut1 = epoch.to_ut1_duration(provider);
         let dut1 = (ut1 - utc).to_seconds();
 
         Timespec::from_split_time(Timescale::TT, ijd_tt, fjd_tt, leap, dut1)
     }
 }
+
+#[cfg(feature = "hifitime")]
+impl From<(Epoch, Ut1Provider)> for Timespec {
+    fn from((epoch, provider): (Epoch, Ut1Provider)) -> Self {
+        Self::from_epoch(epoch, &provider)
+    }
+}
+
+#[cfg(feature = "hifitime")]
+impl From<(Epoch, &Ut1Provider)> for Timespec {
+    fn from((epoch, provider): (Epoch, &Ut1Provider)) -> Self {
+        Self::from_epoch(epoch, provider)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn equal_across_split_representations() {
+        let a = Timespec::from_split_time(Timescale::TT, 2_460_000, 0.25, 37, 0.0);
+        let b = Timespec::from_split_time(Timescale::TT, 2_459_999, 1.25, 37, 0.0);
+        let c = Timespec::from_split_time(Timescale::TT, 2_460_001, -0.75, 37, 0.0);
+        assert_eq!(a, b);
+        assert_eq!(a, c);
+        assert_eq!(HashSet::from([a, b, c]).len(), 1);
+    }
+
+    #[test]
+    fn ordering() {
+        let a = Timespec::from_split_time(Timescale::TT, 2_460_000, 0.999, 37, 0.0);
+        let b = Timespec::from_split_time(Timescale::TT, 2_460_001, 0.0, 37, 0.0);
+        assert!(a < b);
+        assert_eq!(a.max(b), b);
+        let mut times = vec![b, a, b.offset(-1.0)];
+        times.sort();
+        assert_eq!(times, [a, b.offset(-1.0), b]);
+    }
+
+    #[test]
+    fn iso_microseconds() {
+        let time =
+            Timespec::from_split_time(Timescale::UTC, 2_460_000, 0.123_456 / 86_400.0, 37, 0.0);
+        assert_eq!(format!("{time:.6}"), "2023-02-24T12:00:00.123456Z");
+        assert_eq!(time.to_string(), "2023-02-24T12:00:00.123Z");
+        assert_eq!(format!("{:.0}", time.offset(-0.2)), "2023-02-24T12:00:00Z");
+    }
+
+    #[test]
+    fn invalid_dates() {
+        assert_eq!(days_in_month(2024, 2), 29);
+        assert_eq!(days_in_month(2000, 2), 29);
+        assert_eq!(days_in_month(1900, 2), 28);
+        assert_eq!(days_in_month(2023, 4), 30);
+        for s in [
+            "2023-02-29",
+            "1900-02-29T00:00:00Z",
+            "2024-04-31",
+            "2024-00-10",
+            "2024-01-01-05",
+            "2024-01-01-05T00:00:00Z",
+            "2024-01-01T00:00:00:00",
+        ] {
+            assert!(s.parse::<Timespec>().is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn approximate_without_eop() {
+        // No test installs a global EOP table
+        let time = "2024-09-17T06:12:18Z".into_timespec_approx().unwrap();
+        assert_eq!(time.to_string(), "2024-09-17T06:12:18.000Z");
+        assert!((time.jd(Timescale::UT1) - time.jd(Timescale::UTC)).abs() < 1e-12);
+        let tt_utc = (time.jd(Timescale::TT) - time.jd(Timescale::UTC)) * 86_400.0;
+        assert!((tt_utc - 69.184).abs() < 1e-3);
+        let mjd = Mjd(60_570.25).into_timespec_approx().unwrap();
+        assert!((mjd.jd(Timescale::UTC) - (60_570.25 + MJD0)).abs() < 1e-9);
+        assert!("2024-13-01".into_timespec_approx().is_err());
+    }
+
+    #[cfg(feature = "hifitime")]
+    #[test]
+    fn hifitime_borrowed_provider() {
+        let epoch = Epoch::from_gregorian_utc(2024, 9, 17, 6, 12, 18, 0);
+        let provider = Ut1Provider::default();
+        let borrowed = Timespec::from((epoch, &provider));
+        assert_eq!(borrowed, Timespec::from((epoch, provider)));
+        assert!((borrowed.jd(Timescale::UT1) - borrowed.jd(Timescale::UTC)).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,167 @@
+//! Lazily computed pointing tracks
+
+use crate::{
+    error::Error,
+    positions::{Frame, Observer, Placeable, ReferenceSystem},
+    refraction::RefractionModel,
+    time::Timespec,
+    Accuracy,
+};
+
+/// A single point of a pointing track
+#[derive(Debug)]
+pub struct TrackPoint {
+    /// The time of the point
+    pub time: Timespec,
+    /// Azimuth in degrees
+    pub az: f64,
+    /// Elevation in degrees
+    pub el: f64,
+    /// Rate of change of azimuth in degrees/s
+    pub az_rate: f64,
+    /// Rate of change of elevation in degrees/s
+    pub el_rate: f64,
+}
+
+/// An iterator over the pointing to a source at regular intervals
+///
+/// Points are computed lazily, one observing frame per step, so streaming consumers (drive loops, plotters)
+/// never need to materialize the whole track. Rates are the finite difference to the following step.
+pub struct Track<'a, T> {
+    target: &'a T,
+    observer: &'a Observer,
+    stop: Timespec,
+    step: f64,
+    accuracy: Accuracy,
+    ref_sys: ReferenceSystem,
+    dx: f64,
+    dy: f64,
+    refraction: Option<&'a dyn RefractionModel>,
+    /// The next time to yield, along with its (already computed) pointing
+    next: Option<(Timespec, Option<(f64, f64)>)>,
+}
+
+impl<'a, T: Placeable> Track<'a, T> {
+    /// Iterate over the pointing to `target` from `start` to `stop` (inclusive) every `step` seconds
+    ///
+    /// By default, this uses reduced accuracy in CIRS with no pole offsets and no refraction. The step must be
+    /// positive and finite.
+    pub fn iter(
+        target: &'a T,
+        observer: &'a Observer,
+        start: &Timespec,
+        stop: &Timespec,
+        step: f64,
+    ) -> super::Result<Self> {
+        if !(step.is_finite() && step > 0.0) {
+            return Err(Error::InvalidValue("step"));
+        }
+        Ok(Self {
+            target,
+            observer,
+            stop: *stop,
+            step,
+            accuracy: Accuracy::Reduced,
+            ref_sys: ReferenceSystem::CIRS,
+            dx: 0.0,
+            dy: 0.0,
+            refraction: None,
+            next: Some((*start, None)),
+        })
+    }
+
+    /// Set the accuracy of the frame computations
+    pub fn accuracy(mut self, accuracy: Accuracy) -> Self {
+        self.accuracy = accuracy;
+        self
+    }
+
+    /// Set the reference system of the apparent places
+    pub fn reference_system(mut self, ref_sys: ReferenceSystem) -> Self {
+        self.ref_sys = ref_sys;
+        self
+    }
+
+    /// Set the polar offsets (dx, dy in mas) used for each frame
+    pub fn pole_offsets(mut self, dx: f64, dy: f64) -> Self {
+        self.dx = dx;
+        self.dy = dy;
+        self
+    }
+
+    /// Refract the elevations with the given model
+    pub fn refraction(mut self, model: &'a dyn RefractionModel) -> Self {
+        self.refraction = Some(model);
+        self
+    }
+
+    fn az_el(&self, time: &Timespec) -> super::Result<(f64, f64)> {
+        let frame = Frame::new(self.accuracy, self.observer, time, self.dx, self.dy)?;
+        frame.apparent_local_coordinates(self.ref_sys, self.target, self.refraction)
+    }
+}
+
+impl<T: Placeable> Iterator for Track<'_, T> {
+    type Item = super::Result<TrackPoint>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (time, cached) = self.next.take()?;
+        if time.diff(&self.stop) > 0.0 {
+            return None;
+        }
+        let current = match cached {
+            Some(azel) => azel,
+            None => match self.az_el(&time) {
+                Ok(azel) => azel,
+                Err(e) => return Some(Err(e)),
+            },
+        };
+        // The next step is needed for the rates anyway, so keep it around for the next iteration
+        let later = time.offset(self.step);
+        let ahead = match self.az_el(&later) {
+            Ok(azel) => azel,
+            Err(e) => return Some(Err(e)),
+        };
+        // Unwrap the azimuth difference across north
+        let d_az = (ahead.0 - current.0 + 540.0).rem_euclid(360.0) - 180.0;
+        self.next = Some((later, Some(ahead)));
+        Some(Ok(TrackPoint {
+            time,
+            az: current.0,
+            el: current.1,
+            az_rate: d_az / self.step,
+            el_rate: (ahead.1 - current.1) / self.step,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::positions::{CatalogEntry, Weather};
+
+    #[test]
+    fn invalid_steps() {
+        let observer = Observer::new_on_surface(37.0, 0.0, 0.0, Weather::default());
+        let star = CatalogEntry::new("star", "", 0, 18.7, 20.0, 0.0, 0.0, 0.0, 0.0).unwrap();
+        let start = Timespec::J2000;
+        let stop = start.offset(60.0);
+        for step in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(Track::iter(&star, &observer, &start, &stop, step).is_err());
+        }
+    }
+
+    #[test]
+    fn points_every_step() {
+        let observer = Observer::new_on_surface(37.0, 0.0, 0.0, Weather::default());
+        let star = CatalogEntry::new("star", "", 0, 18.7, 20.0, 0.0, 0.0, 0.0, 0.0).unwrap();
+        let start = Timespec::J2000;
+        let stop = start.offset(61.0);
+        let points = Track::iter(&star, &observer, &start, &stop, 20.0)
+            .unwrap()
+            .collect::<crate::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(points.len(), 4);
+        assert!((points[3].time.diff(&start) - 60.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,290 @@
+//! Propagation of catalog astrometric uncertainties to the place and pointing of a source
+//!
+//! The catalog uncertainties are treated as independent (uncorrelated) 1σ Gaussian errors. The position error grows
+//! with the proper motion error times the time since the catalog epoch, and the parallax error displaces the source
+//! along its parallactic ellipse. The apparent-place corrections (aberration, light deflection, precession and
+//! nutation) change the position by far less than the uncertainties involved, so they are treated as exact.
+//!
+//! Where that linearization is suspect, [`sample_az_el`] estimates the pointing scatter from random realizations of
+//! the astrometry and the Earth orientation parameters instead.
+
+use crate::{
+    error::Error,
+    positions::{CatalogEntry, Frame, ReferenceSystem},
+    refraction::RefractionModel,
+};
+
+/// Julian days per Julian year
+const DAYS_PER_YEAR: f64 = 365.25;
+
+/// The 1σ astrometric uncertainties of a catalog entry
+///
+/// The position uncertainties apply at the epoch of the entry's [`CatalogSystem`](crate::positions::CatalogSystem).
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Uncertainty {
+    /// Uncertainty in right ascension (as the great-circle offset α cos δ) in mas
+    pub ra: f64,
+    /// Uncertainty in declination in mas
+    pub dec: f64,
+    /// Uncertainty in the proper motion in right ascension (μα cos δ) in mas/yr
+    pub pm_ra: f64,
+    /// Uncertainty in the proper motion in declination in mas/yr
+    pub pm_dec: f64,
+    /// Uncertainty in the parallax in mas
+    pub parallax: f64,
+}
+
+impl Uncertainty {
+    /// The uncertainties propagated to `jd_tt` from the epoch `epoch` (both TT Julian dates)
+    ///
+    /// The proper motion error adds to the position error in quadrature, growing with the time in either direction.
+    pub fn at_epoch(&self, epoch: f64, jd_tt: f64) -> Self {
+        let years = (jd_tt - epoch) / DAYS_PER_YEAR;
+        Self {
+            ra: self.ra.hypot(years * self.pm_ra),
+            dec: self.dec.hypot(years * self.pm_dec),
+            ..*self
+        }
+    }
+}
+
+/// A 1σ error ellipse on the sky
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct ErrorEllipse {
+    /// Semi-major axis in mas
+    pub semi_major: f64,
+    /// Semi-minor axis in mas
+    pub semi_minor: f64,
+    /// Position angle of the major axis in degrees, east of north in [0, 180)
+    pub position_angle: f64,
+}
+
+impl ErrorEllipse {
+    /// The ellipse of a covariance matrix of (east, north) offsets in mas²
+    fn from_covariance(east: f64, north: f64, cross: f64) -> Self {
+        let mean = (east + north) / 2.0;
+        let spread = ((east - north) / 2.0).hypot(cross);
+        // Angle of the major axis from east, towards north
+        let angle = 0.5 * (2.0 * cross).atan2(east - north);
+        Self {
+            semi_major: (mean + spread).sqrt(),
+            semi_minor: (mean - spread).max(0.0).sqrt(),
+            position_angle: (90.0 - angle.to_degrees()).rem_euclid(180.0),
+        }
+    }
+}
+
+/// The error ellipse of a source with the given uncertainties (at `epoch`) placed at `jd_tt`
+///
+/// - ra, dec: Position of the source in hours and degrees
+/// - obs_pos: Barycentric position of the observer in AU, in the same system as the position
+pub(crate) fn error_ellipse(
+    uncertainty: &Uncertainty,
+    epoch: f64,
+    jd_tt: f64,
+    ra: f64,
+    dec: f64,
+    obs_pos: &[f64; 3],
+) -> ErrorEllipse {
+    let propagated = uncertainty.at_epoch(epoch, jd_tt);
+    // The parallactic displacement is the parallax times the observer's offset across the line of sight, away from
+    // the observer's direction
+    let (sin_ra, cos_ra) = (ra * 15.0).to_radians().sin_cos();
+    let (sin_dec, cos_dec) = dec.to_radians().sin_cos();
+    let east = [-sin_ra, cos_ra, 0.0];
+    let north = [-sin_dec * cos_ra, -sin_dec * sin_ra, cos_dec];
+    let dot = |a: &[f64; 3]| a.iter().zip(obs_pos).map(|(a, b)| a * b).sum::<f64>();
+    let (p_east, p_north) = (dot(&east), dot(&north));
+    let var_plx = propagated.parallax.powi(2);
+    ErrorEllipse::from_covariance(
+        propagated.ra.powi(2) + var_plx * p_east.powi(2),
+        propagated.dec.powi(2) + var_plx * p_north.powi(2),
+        var_plx * p_east * p_north,
+    )
+}
+
+/// The 1σ uncertainties of the Earth orientation parameters, as published with them by the IERS
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct EopUncertainty {
+    /// Uncertainty in UT1-UTC in seconds
+    pub dut1: f64,
+    /// Uncertainty in the x coordinate of the pole in mas
+    pub xp: f64,
+    /// Uncertainty in the y coordinate of the pole in mas
+    pub yp: f64,
+}
+
+/// Configuration of a Monte Carlo pointing uncertainty estimate, see [`sample_az_el`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MonteCarlo {
+    /// Number of realizations to draw
+    pub samples: usize,
+    /// Seed of the random number generator, so estimates are reproducible
+    pub seed: u64,
+    /// Uncertainties of the Earth orientation parameters
+    pub eop: EopUncertainty,
+}
+
+impl Default for MonteCarlo {
+    fn default() -> Self {
+        Self {
+            samples: 1000,
+            seed: 0,
+            eop: EopUncertainty::default(),
+        }
+    }
+}
+
+/// The empirical scatter of the local coordinates over the realizations of a [`MonteCarlo`] estimate
+#[derive(Debug, Clone, PartialEq)]
+pub struct AzElScatter {
+    /// Azimuth of the nominal (unperturbed) place in degrees
+    pub az: f64,
+    /// Elevation of the nominal (unperturbed) place in degrees
+    pub el: f64,
+    /// Mean offset of the realizations from the nominal azimuth in degrees
+    pub az_bias: f64,
+    /// Mean offset of the realizations from the nominal elevation in degrees
+    pub el_bias: f64,
+    /// Standard deviation of the azimuth in degrees
+    pub az_std: f64,
+    /// Standard deviation of the elevation in degrees
+    pub el_std: f64,
+    /// Correlation coefficient of the azimuth and elevation
+    pub correlation: f64,
+    /// The (az, el) of every realization in degrees
+    pub realizations: Vec<(f64, f64)>,
+}
+
+/// Estimates the scatter of the local coordinates (az, el in degrees) of a catalog entry by drawing realizations of
+/// its astrometry and of the Earth orientation parameters
+///
+/// This is an alternative to the linear [error ellipse](crate::positions::SkyPosition::error_ellipse) for cases where
+/// linearization is suspect, such as low elevations (where refraction changes quickly) or large proper motion
+/// uncertainties. Every parameter is drawn from an independent Gaussian; parallaxes are kept non-negative. The
+/// entry's uncertainties are given with [`CatalogEntry::with_uncertainty`]; without them, only the EOP vary.
+pub fn sample_az_el(
+    entry: &CatalogEntry,
+    frame: &Frame,
+    ref_sys: ReferenceSystem,
+    refraction: Option<&dyn RefractionModel>,
+    config: &MonteCarlo,
+) -> super::Result<AzElScatter> {
+    if config.samples < 2 {
+        return Err(Error::InvalidValue("samples"));
+    }
+    let (az, el) = frame.apparent_local_coordinates(ref_sys, entry, refraction)?;
+    // Draw the astrometry at the epoch the uncertainties apply to, so the position and proper motion errors combine
+    // correctly when propagated to the entry's epoch
+    let (uncertainty, epoch) = entry
+        .uncertainty
+        .unwrap_or((Uncertainty::default(), entry.system().epoch()));
+    let base = entry.at_epoch(epoch)?;
+    let mas_to_deg = 1.0 / 3_600_000.0;
+    let cos_dec = base.dec().to_radians().cos();
+
+    let mut rng = Gaussian::new(config.seed);
+    let mut realizations = Vec::with_capacity(config.samples);
+    for _ in 0..config.samples {
+        let dec = (base.dec() + rng.next(uncertainty.dec) * mas_to_deg).clamp(-90.0, 90.0);
+        let ra = base.ra() + rng.next(uncertainty.ra) * mas_to_deg / 15.0 / cos_dec;
+        let drawn = CatalogEntry::new(
+            base.name(),
+            base.catalog(),
+            base.number(),
+            // A tiny negative RA wraps to exactly 24 in floating point, so wrap twice
+            ra.rem_euclid(24.0) % 24.0,
+            dec,
+            base.pm_ra() + rng.next(uncertainty.pm_ra),
+            base.pm_dec() + rng.next(uncertainty.pm_dec),
+            (base.parallax() + rng.next(uncertainty.parallax)).max(0.0),
+            base.rad_vel(),
+        )?
+        .with_system(base.system())
+        .at_epoch(entry.system().epoch())?;
+
+        let dut1 = rng.next(config.eop.dut1);
+        let mut time = *frame.time();
+        time.0.dut1 += dut1;
+        time.0.ut1_to_tt -= dut1;
+        let drawn_frame = Frame::new(
+            frame.inner.accuracy.into(),
+            frame.observer(),
+            time,
+            frame.inner.dx + rng.next(config.eop.xp),
+            frame.inner.dy + rng.next(config.eop.yp),
+        )?;
+        realizations.push(drawn_frame.apparent_local_coordinates(ref_sys, &drawn, refraction)?);
+    }
+
+    // Offsets from the nominal place, with the azimuth unwrapped across north
+    let offsets = realizations
+        .iter()
+        .map(|&(a, e)| ((a - az + 540.0).rem_euclid(360.0) - 180.0, e - el))
+        .collect::<Vec<_>>();
+    let n = offsets.len() as f64;
+    let az_bias = offsets.iter().map(|o| o.0).sum::<f64>() / n;
+    let el_bias = offsets.iter().map(|o| o.1).sum::<f64>() / n;
+    let (mut var_az, mut var_el, mut cov) = (0.0, 0.0, 0.0);
+    for (d_az, d_el) in &offsets {
+        var_az += (d_az - az_bias).powi(2);
+        var_el += (d_el - el_bias).powi(2);
+        cov += (d_az - az_bias) * (d_el - el_bias);
+    }
+    let (az_std, el_std) = ((var_az / (n - 1.0)).sqrt(), (var_el / (n - 1.0)).sqrt());
+    let correlation = if az_std > 0.0 && el_std > 0.0 {
+        cov / (n - 1.0) / (az_std * el_std)
+    } else {
+        0.0
+    };
+    Ok(AzElScatter {
+        az,
+        el,
+        az_bias,
+        el_bias,
+        az_std,
+        el_std,
+        correlation,
+        realizations,
+    })
+}
+
+/// A small, seedable source of Gaussian deviates (SplitMix64 and the Box-Muller transform)
+struct Gaussian {
+    state: u64,
+    /// The second deviate of the last Box-Muller pair
+    spare: Option<f64>,
+}
+
+impl Gaussian {
+    fn new(seed: u64) -> Self {
+        Self {
+            state: seed,
+            spare: None,
+        }
+    }
+
+    /// A uniform deviate in (0, 1]
+    fn uniform(&mut self) -> f64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        ((z >> 11) + 1) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A Gaussian deviate with zero mean and the given standard deviation
+    fn next(&mut self, sigma: f64) -> f64 {
+        let unit = match self.spare.take() {
+            Some(spare) => spare,
+            None => {
+                let radius = (-2.0 * self.uniform().ln()).sqrt();
+                let (sin, cos) = (std::f64::consts::TAU * self.uniform()).sin_cos();
+                self.spare = Some(radius * sin);
+                radius * cos
+            }
+        };
+        unit * sigma
+    }
+}
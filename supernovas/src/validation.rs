@@ -0,0 +1,238 @@
+//! Self-test against published reference values
+//!
+//! Each check compares a quantity computed through this wrapper with a value fixed by its definition, published by
+//! the IAU/IERS, or from the ERFA (SOFA) test suite, so unit mix-ups or sign errors between the wrapper and the C library are caught at runtime, e.g.
+//! with [`selftest`] at the start of an observing session.
+
+use crate::{
+    airmass::{airmass, AirmassModel},
+    coords::{galactic_to_icrs, icrs_to_galactic},
+    error::Error,
+    geodesy::{geodetic_to_ecef, Ellipsoid},
+    positions::{CatalogEntry, Frame, Observer, ReferenceSystem, SkyPosition, Weather},
+    refraction::OpticalRefraction,
+    time::{leap_seconds, Timescale, Timespec},
+    Accuracy,
+};
+
+/// A single comparison with a reference value
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+    /// What was checked
+    pub name: &'static str,
+    /// The reference value
+    pub expected: f64,
+    /// The value computed by this library
+    pub actual: f64,
+    /// The allowed absolute difference
+    pub tolerance: f64,
+}
+
+impl Check {
+    /// Whether the computed value agrees with the reference
+    pub fn passed(&self) -> bool {
+        (self.actual - self.expected).abs() <= self.tolerance
+    }
+}
+
+/// Run all of the reference checks
+pub fn checks() -> super::Result<Vec<Check>> {
+    let mut checks = vec![];
+    let mut check = |name, expected, actual, tolerance| {
+        checks.push(Check {
+            name,
+            expected,
+            actual,
+            tolerance,
+        })
+    };
+
+    // The reference values below are from the ERFA test suite (t_erfa_c.c), which are the same as SOFA's. Reduced
+    // accuracy is used, since full accuracy needs a high-precision planet ephemeris to be configured.
+
+    // eraEra00 and eraObl06 at MJD 54388.0 (UT1 and TT; the 65 s between them changes the obliquity by a few μas)
+    let mjd54388 = Timespec::from_split_time(Timescale::UT1, 2_454_388, 0.5, 33, 0.0);
+    let geocenter = Observer::new_at_geocenter();
+    let frame = Frame::new(Accuracy::Reduced, &geocenter, mjd54388, 0.0, 0.0)?;
+    check(
+        "ERA at MJD 54388 UT1 (deg)",
+        0.402_283_724_002_815_8_f64.to_degrees(),
+        frame.era(),
+        1e-8,
+    );
+    check(
+        "Mean obliquity at MJD 54388 TT (deg)",
+        0.409_074_922_938_725_8_f64.to_degrees(),
+        frame.mean_obliquity(),
+        1e-6,
+    );
+
+    // eraGmst06 at MJD 53736.0 UT1 (the TT term of GMST changes by far less than the tolerance over the leap seconds)
+    let mjd53736 = Timespec::from_split_time(Timescale::UT1, 2_453_736, 0.5, 33, 0.0);
+    let frame = Frame::new(Accuracy::Reduced, &geocenter, mjd53736, 0.0, 0.0)?;
+    check(
+        "GMST at MJD 53736 UT1 (h)",
+        1.754_174_971_870_091_2_f64.to_degrees() / 15.0,
+        frame.gst() - frame.equation_of_equinoxes() / 3600.0,
+        1e-6,
+    );
+
+    // The star of the eraAtci13 and eraAtco13 tests, whose proper motion in RA is given there as dα/dt of 1e-5 rad/yr
+    let star = CatalogEntry::new(
+        "ERFA test star",
+        "",
+        0,
+        2.71_f64.to_degrees() / 15.0,
+        0.174_f64.to_degrees(),
+        (1e-5 * 0.174_f64.cos()).to_degrees() * 3_600_000.0,
+        5e-6_f64.to_degrees() * 3_600_000.0,
+        100.0,
+        55.0,
+    )?;
+
+    // eraAtci13: the geocentric CIRS place at TDB 2456165.5 + 0.401182685. The tolerance allows for the reduced
+    // accuracy nutation and Earth ephemeris.
+    let tdb = Timespec::from_split_time(Timescale::TDB, 2_456_165, 0.901_182_685, 35, 0.0);
+    let frame = Frame::new(Accuracy::Reduced, &geocenter, tdb, 0.0, 0.0)?;
+    let cirs = SkyPosition::try_from_frame(&star, &frame, ReferenceSystem::CIRS)?;
+    check(
+        "Geocentric CIRS right ascension of the eraAtci13 star (deg)",
+        2.710_121_572_968_697_f64.to_degrees(),
+        cirs.ra() * 15.0,
+        1e-4,
+    );
+    check(
+        "Geocentric CIRS declination of the eraAtci13 star (deg)",
+        0.172_937_136_721_953_9_f64.to_degrees(),
+        cirs.dec(),
+        1e-4,
+    );
+
+    // eraAtco13: the observed place from a site in the Andes at UTC 2456384.5 + 0.969254051, with the polar motion
+    // and UT1-UTC of the test. The tolerances allow for the different refraction models at this low elevation.
+    let site = Observer::new_on_surface(
+        (-1.234_585_6_f64).to_degrees(),
+        (-0.527_800_806_f64).to_degrees(),
+        2738.0,
+        Weather {
+            temperature_c: 12.8,
+            pressure_mbar: 731.0,
+            humidity: 59.0,
+        },
+    );
+    let utc = Timespec::from_split_time(Timescale::UTC, 2_456_385, 0.469_254_051, 35, 0.155_067_5);
+    // xp = 2.47230737e-7 rad and yp = 1.82640464e-6 rad
+    let frame = Frame::new(Accuracy::Reduced, &site, utc, 50.995, 376.723)?;
+    let (az, el) =
+        frame.apparent_local_coordinates(ReferenceSystem::CIRS, &star, Some(&OpticalRefraction))?;
+    check(
+        "Observed azimuth of the eraAtco13 star (deg)",
+        0.092_339_522_248_951_23_f64.to_degrees(),
+        az,
+        0.02,
+    );
+    check(
+        "Observed elevation of the eraAtco13 star (deg)",
+        90.0 - 1.407_758_704_513_55_f64.to_degrees(),
+        el,
+        0.02,
+    );
+
+    // TT - TAI is 32.184 s by definition, and TAI - UTC was 37 s from 2017
+    let utc = Timespec::from_split_time(Timescale::UTC, 2_459_000, 0.5, 37, 0.0);
+    check(
+        "TT - UTC in 2020 (s)",
+        69.184,
+        (utc.jd(Timescale::TT) - utc.jd(Timescale::UTC)) * 86_400.0,
+        1e-3,
+    );
+    check(
+        "Leap seconds on 2017 January 1",
+        37.0,
+        leap_seconds(57_754.0).into(),
+        0.0,
+    );
+
+    // The galactic pole and center as defined for the Hipparcos catalog
+    let (_, b) = icrs_to_galactic(192.859_48 / 15.0, 27.128_25)?;
+    check("Galactic latitude of the north pole (deg)", 90.0, b, 1e-3);
+    let (ra, dec) = galactic_to_icrs(0.0, 0.0)?;
+    check(
+        "ICRS right ascension of the galactic center (deg)",
+        266.405_10,
+        ra * 15.0,
+        1e-3,
+    );
+    check(
+        "ICRS declination of the galactic center (deg)",
+        -28.936_175,
+        dec,
+        1e-3,
+    );
+
+    // WGS84 semi-minor axis
+    let pole = geodetic_to_ecef(90.0, 0.0, 0.0, Ellipsoid::WGS84);
+    check("WGS84 polar radius (m)", 6_356_752.314_245, pole[2], 1e-3);
+
+    // The secant of the zenith angle
+    check(
+        "Plane-parallel airmass at 30° elevation",
+        2.0,
+        airmass(30.0, AirmassModel::PlaneParallel),
+        1e-12,
+    );
+
+    Ok(checks)
+}
+
+/// Run all of the reference checks, returning an error that lists any that failed
+pub fn selftest() -> super::Result<()> {
+    let failures = checks()?
+        .into_iter()
+        .filter(|check| !check.passed())
+        .map(|check| {
+            format!(
+                "{}: expected {}, got {}",
+                check.name, check.expected, check.actual
+            )
+        })
+        .collect::<Vec<_>>();
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Validation(failures.join("; ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selftest_passes() {
+        selftest().unwrap();
+    }
+
+    #[test]
+    fn every_check_passes() {
+        for check in checks().unwrap() {
+            assert!(check.passed(), "{check:?}");
+        }
+    }
+
+    #[test]
+    fn check_tolerance() {
+        let check = Check {
+            name: "test",
+            expected: 1.0,
+            actual: 1.5,
+            tolerance: 0.5,
+        };
+        assert!(check.passed());
+        assert!(!Check {
+            actual: 1.6,
+            ..check
+        }
+        .passed());
+    }
+}
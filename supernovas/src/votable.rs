@@ -0,0 +1,176 @@
+//! A minimal reader for the IVOA VOTable format returned by CDS and NED
+//!
+//! Only the `FIELD` declarations and the `TABLEDATA` cells of the table are read, which is enough to look columns
+//! up by name or UCD instead of by position, so services reordering or adding output columns can't silently shift
+//! values into the wrong place.
+
+use crate::error::Error;
+use quick_xml::{
+    events::{BytesStart, Event},
+    reader::Reader,
+};
+use std::io::BufReader;
+
+/// A column declaration of a VOTable
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Field {
+    /// The column name
+    pub name: String,
+    /// The column identifier, if given
+    pub id: Option<String>,
+    /// The Unified Content Descriptor of the column (e.g. `pos.eq.ra;meta.main`), if given
+    pub ucd: Option<String>,
+    /// The unit of the column, if given
+    pub unit: Option<String>,
+}
+
+impl Field {
+    /// Whether the column has the given name or identifier (compared case-insensitively)
+    pub fn is_named(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+            || self
+                .id
+                .as_deref()
+                .is_some_and(|id| id.eq_ignore_ascii_case(name))
+    }
+
+    /// Whether the column has exactly the given UCD (compared case-insensitively)
+    pub fn has_ucd(&self, ucd: &str) -> bool {
+        self.ucd
+            .as_deref()
+            .is_some_and(|own| own.eq_ignore_ascii_case(ucd))
+    }
+}
+
+/// The fields and rows of a VOTable
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VoTable {
+    /// The column declarations, in column order
+    pub fields: Vec<Field>,
+    /// The text of every cell of every row
+    pub rows: Vec<Vec<String>>,
+}
+
+impl VoTable {
+    /// Parse a VOTable document
+    pub fn parse(resp: impl std::io::Read) -> super::Result<Self> {
+        let mut xml_reader = Reader::from_reader(BufReader::new(resp));
+        let mut table = Self::default();
+        let mut columns = Vec::new();
+        let mut buf = Vec::new();
+        // The text of the cell being read, if inside a TD
+        let mut cell: Option<String> = None;
+        loop {
+            match xml_reader.read_event_into(&mut buf) {
+                Ok(Event::Eof) => break,
+                Ok(Event::Start(e)) => match e.name().as_ref() {
+                    b"FIELD" => table.fields.push(field(&e)?),
+                    b"TD" => cell = Some(String::new()),
+                    _ => (),
+                },
+                Ok(Event::Empty(e)) => match e.name().as_ref() {
+                    b"FIELD" => table.fields.push(field(&e)?),
+                    b"TD" => columns.push(String::new()),
+                    _ => (),
+                },
+                Ok(Event::Text(e)) => {
+                    if let Some(cell) = cell.as_mut() {
+                        let text = e.unescape().map_err(|_| Error::InvalidString)?;
+                        cell.push_str(&text);
+                    }
+                }
+                Ok(Event::CData(e)) => {
+                    if let Some(cell) = cell.as_mut() {
+                        cell.push_str(&String::from_utf8_lossy(&e));
+                    }
+                }
+                Ok(Event::End(e)) => match e.name().as_ref() {
+                    b"TD" => columns.push(cell.take().unwrap_or_default()),
+                    b"TR" => table.rows.push(std::mem::take(&mut columns)),
+                    _ => (),
+                },
+                Err(_) => return Err(Error::InvalidString),
+                _ => (),
+            }
+            buf.clear();
+        }
+        Ok(table)
+    }
+
+    /// The index of the first column with any of the given names (or identifiers), or else with the given UCD
+    pub fn column(&self, names: &[&str], ucd: Option<&str>) -> Option<usize> {
+        self.fields
+            .iter()
+            .position(|field| names.iter().any(|name| field.is_named(name)))
+            .or_else(|| {
+                let ucd = ucd?;
+                self.fields.iter().position(|field| field.has_ucd(ucd))
+            })
+    }
+}
+
+/// Read the attributes of a FIELD declaration
+fn field(e: &BytesStart) -> super::Result<Field> {
+    let mut field = Field::default();
+    for attr in e.attributes() {
+        let attr = attr.map_err(|_| Error::InvalidString)?;
+        let value = attr
+            .unescape_value()
+            .map_err(|_| Error::InvalidString)?
+            .into_owned();
+        match attr.key.as_ref() {
+            b"name" => field.name = value,
+            b"ID" => field.id = Some(value),
+            b"ucd" => field.ucd = Some(value),
+            b"unit" => field.unit = Some(value),
+            _ => (),
+        }
+    }
+    Ok(field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<VOTABLE version="1.4">
+<RESOURCE><TABLE>
+<FIELD name="main_id" datatype="char" arraysize="*" ucd="meta.id;meta.main"/>
+<FIELD name="RA_d" ID="ra" datatype="double" ucd="pos.eq.ra;meta.main" unit="deg"></FIELD>
+<FIELD name="DEC_d" datatype="double" ucd="pos.eq.dec;meta.main" unit="deg"/>
+<FIELD name="plx" datatype="double"/>
+<DATA><TABLEDATA>
+<TR><TD>* alf Lyr</TD><TD>279.23473479</TD><TD>+38.78368896</TD><TD>130.23</TD></TR>
+<TR><TD>NAME Barnard&apos;s &amp; co</TD><TD><![CDATA[269.45]]></TD><TD>4.69</TD><TD/></TR>
+</TABLEDATA></DATA>
+</TABLE></RESOURCE>
+</VOTABLE>"#;
+
+    #[test]
+    fn fields_and_rows() {
+        let table = VoTable::parse(TABLE.as_bytes()).unwrap();
+        assert_eq!(table.fields.len(), 4);
+        assert_eq!(table.fields[1].id.as_deref(), Some("ra"));
+        assert_eq!(table.fields[1].unit.as_deref(), Some("deg"));
+        assert_eq!(
+            table.rows,
+            [
+                ["* alf Lyr", "279.23473479", "+38.78368896", "130.23"],
+                ["NAME Barnard's & co", "269.45", "4.69", ""],
+            ]
+        );
+    }
+
+    #[test]
+    fn columns_by_name_id_or_ucd() {
+        let table = VoTable::parse(TABLE.as_bytes()).unwrap();
+        assert_eq!(table.column(&["MAIN_ID"], None), Some(0));
+        assert_eq!(table.column(&["ra"], None), Some(1));
+        assert_eq!(
+            table.column(&["dec"], Some("pos.eq.dec;meta.main")),
+            Some(2)
+        );
+        assert_eq!(table.column(&["pmra"], Some("pos.pm")), None);
+    }
+}
@@ -0,0 +1,87 @@
+//! FITS World Coordinate System (WCS) header keywords
+//!
+//! Imaging pipelines can stamp their headers with a gnomonic (TAN) projection centered on the same place that was
+//! used to point the telescope, so the image astrometry and the pointing agree by construction.
+
+use crate::{
+    error::Error,
+    positions::{Frame, Placeable, ReferenceSystem, SkyPosition},
+    time::Timescale,
+};
+use std::collections::BTreeMap;
+use supernovas_sys::NOVAS_JD_J2000;
+
+/// The value of a FITS header keyword
+#[derive(Debug, Clone, PartialEq)]
+pub enum WcsValue {
+    Float(f64),
+    String(String),
+}
+
+impl std::fmt::Display for WcsValue {
+    /// Formats the value as it appears in a FITS header card
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WcsValue::Float(x) => write!(f, "{x:E}"),
+            // Fixed-format strings are quoted, padded to at least 8 characters, and escape quotes by doubling them
+            WcsValue::String(s) => write!(f, "'{:<8}'", s.replace('\'', "''")),
+        }
+    }
+}
+
+/// The standard WCS keywords of an image centered on a target
+///
+/// - frame: The observing frame used for pointing
+/// - ref_sys: The reference system of the pointing (one of ICRS, GCRS, J2000, MOD, or TOD)
+/// - center: The target at the reference pixel
+/// - crpix: The (1-based) reference pixel (x, y)
+/// - scale: Plate scale in arcseconds per pixel
+/// - rotation: Rotation of the image in degrees, in the sense of `CROTA2` (north through east)
+///
+/// The keywords are `CTYPE1/2`, `CUNIT1/2`, `CRVAL1/2`, `CRPIX1/2`, the `CDi_j` matrix (with RA increasing to the
+/// left, as on the sky), `RADESYS`, `EQUINOX`, and `MJD-OBS`.
+pub fn keywords(
+    frame: &Frame,
+    ref_sys: ReferenceSystem,
+    center: &impl Placeable,
+    crpix: (f64, f64),
+    scale: f64,
+    rotation: f64,
+) -> super::Result<BTreeMap<&'static str, WcsValue>> {
+    let jd_tt = frame.time().jd(Timescale::TT);
+    let (radesys, equinox) = match ref_sys {
+        ReferenceSystem::ICRS | ReferenceSystem::GCRS => ("ICRS", 2000.0),
+        ReferenceSystem::J2000 => ("FK5", 2000.0),
+        ReferenceSystem::MOD => ("FK5", 2000.0 + (jd_tt - NOVAS_JD_J2000) / 365.25),
+        ReferenceSystem::TOD => ("GAPPT", 2000.0 + (jd_tt - NOVAS_JD_J2000) / 365.25),
+        // CIRS right ascensions are measured from the CIO, which has no FITS equivalent
+        ReferenceSystem::CIRS => return Err(Error::InvalidValue("ref_sys")),
+    };
+    let pos = SkyPosition::try_from_frame(center, frame, ref_sys)?;
+    let cdelt = scale / 3600.0;
+    let (sin, cos) = rotation.to_radians().sin_cos();
+
+    let mut keys = BTreeMap::new();
+    let mut string =
+        |key: &'static str, value: &str| keys.insert(key, WcsValue::String(value.to_owned()));
+    string("CTYPE1", "RA---TAN");
+    string("CTYPE2", "DEC--TAN");
+    string("CUNIT1", "deg");
+    string("CUNIT2", "deg");
+    string("RADESYS", radesys);
+    for (key, value) in [
+        ("CRVAL1", pos.ra() * 15.0),
+        ("CRVAL2", pos.dec()),
+        ("CRPIX1", crpix.0),
+        ("CRPIX2", crpix.1),
+        ("CD1_1", -cdelt * cos),
+        ("CD1_2", -cdelt * sin),
+        ("CD2_1", -cdelt * sin),
+        ("CD2_2", cdelt * cos),
+        ("EQUINOX", equinox),
+        ("MJD-OBS", frame.time().jd(Timescale::UTC) - 2400000.5),
+    ] {
+        keys.insert(key, WcsValue::Float(value));
+    }
+    Ok(keys)
+}
@@ -1,9 +1,48 @@
 use std::env;
 use std::path::PathBuf;
 
+/// Environment variable selecting a system-installed SuperNOVAS (found with pkg-config) over the vendored sources
+const USE_PKG_CONFIG: &str = "SUPERNOVAS_SYS_USE_PKG_CONFIG";
+
+/// Whether to link a system-installed SuperNOVAS, either from the `system` feature or the environment variable
+fn use_system_library() -> bool {
+    println!("cargo:rerun-if-env-changed={USE_PKG_CONFIG}");
+    env::var_os("CARGO_FEATURE_SYSTEM").is_some()
+        || env::var(USE_PKG_CONFIG).is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Find a system-installed SuperNOVAS with pkg-config (which also emits the link flags), returning its include paths
+fn link_system_library() -> Vec<PathBuf> {
+    let lib = pkg_config::Config::new()
+        .atleast_version("1.1")
+        .probe("supernovas")
+        .unwrap_or_else(|e| {
+            panic!("Unable to find a system SuperNOVAS with pkg-config (unset {USE_PKG_CONFIG} to build the vendored sources): {e}")
+        });
+    lib.include_paths
+}
+
 fn main() {
+    let include_paths = if use_system_library() {
+        link_system_library()
+    } else {
+        build_vendored_library();
+        vec![PathBuf::from("vendor/include")]
+    };
+    generate_bindings(&include_paths);
+}
+
+/// Whether the cargo feature with the given name is enabled
+fn feature(name: &str) -> bool {
+    let var = format!("CARGO_FEATURE_{}", name.to_uppercase().replace('-', "_"));
+    env::var_os(var).is_some()
+}
+
+/// Compile and link the vendored SuperNOVAS sources, with the solar-system sources of the enabled features
+fn build_vendored_library() {
     // Build supernovas C library
-    cc::Build::new()
+    let mut build = cc::Build::new();
+    build
         .include("vendor/include")
         // Sources
         .file("vendor/src/novas.c")
@@ -11,16 +50,51 @@ fn main() {
         .file("vendor/src/super.c")
         .file("vendor/src/timescale.c")
         .file("vendor/src/frames.c")
-        .file("vendor/src/refract.c")
-        .file("vendor/src/solsys3.c")
-        .file("vendor/src/solsys-ephem.c")
+        .file("vendor/src/refract.c");
+    if feature("earth_sun_calc") {
         // Bake in the default, low-resolution epehm provider
-        .define("DEFAULT_SOLSYS", "3")
-        .define("BUILTIN_SOLSYS3", "1")
-        .define("BUILTIN_SOLSYS_EPHEM", "1")
-        // Build
-        .compile("supernovas");
+        build
+            .file("vendor/src/solsys3.c")
+            .define("DEFAULT_SOLSYS", "3")
+            .define("BUILTIN_SOLSYS3", "1");
+    }
+    if feature("solsys-ephem") {
+        build
+            .file("vendor/src/solsys-ephem.c")
+            .define("BUILTIN_SOLSYS_EPHEM", "1");
+    }
+    if feature("readeph") {
+        // The placeholder readeph() for minor bodies, used by the legacy ephemeris routines
+        build
+            .file("vendor/src/readeph0.c")
+            .define("DEFAULT_READEPH", "1");
+    }
+    if feature("solsys-calceph") {
+        build
+            .file("vendor/src/solsys-calceph.c")
+            .includes(calceph_include_paths());
+        println!("cargo:rustc-link-lib=calceph");
+    }
+    // Build
+    build.compile("supernovas");
+}
+
+/// The include paths of the CALCEPH C library, from `CALCEPH_INCLUDE_DIR` or else pkg-config
+fn calceph_include_paths() -> Vec<PathBuf> {
+    println!("cargo:rerun-if-env-changed=CALCEPH_INCLUDE_DIR");
+    if let Some(dir) = env::var_os("CALCEPH_INCLUDE_DIR") {
+        return vec![PathBuf::from(dir)];
+    }
+    // pkg-config also emits the link search path
+    pkg_config::Config::new()
+        .cargo_metadata(true)
+        .probe("calceph")
+        .map(|lib| lib.include_paths)
+        .unwrap_or_default()
+}
 
+/// Generate the bindings, searching the include paths for the SuperNOVAS headers
+fn generate_bindings(include_paths: &[PathBuf]) {
     // Tell cargo to invalidate the built crate whenever the wrapper changes
     println!("cargo:rerun-if-changed=wrapper.h");
 
@@ -31,11 +105,19 @@ fn main() {
         // The input header we would like to generate
         // bindings for.
         .header("wrapper.h")
+        .clang_args(
+            include_paths
+                .iter()
+                .map(|path| format!("-I{}", path.display())),
+        )
+        // Bind the CALCEPH provider functions too, if they were built
+        .clang_args(feature("solsys-calceph").then_some("-DSUPERNOVAS_SYS_CALCEPH"))
         // Only build bindings for NOVAS, not for it's dependents
         .allowlist_file(".*novas.h")
         .allowlist_file(".*eph_manager.h")
         .allowlist_file(".*nutation.h")
         .allowlist_file(".*solarsystem.h")
+        .allowlist_file(".*novas-calceph.h")
         // Use "newtype enums" for the C enums (to avoid UB)
         .newtype_enum("novas_accuracy")
         .newtype_enum("novas_timescale")
@@ -53,6 +135,7 @@ fn main() {
         .newtype_enum("novas_origin")
         .newtype_enum("novas_planet")
         .newtype_enum("novas_pole_offset_type")
+        .newtype_enum("novas_reference_plane")
         .newtype_enum("novas_reference_system")
         .newtype_enum("novas_refraction_model")
         .newtype_enum("novas_refraction_type")
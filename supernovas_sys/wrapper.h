@@ -1,2 +1,5 @@
-#include "vendor/include/novas.h"
+#include <novas.h>
+#ifdef SUPERNOVAS_SYS_CALCEPH
+#include <novas-calceph.h>
+#endif
 // TODO: Solar System objects (if we need to)
\ No newline at end of file